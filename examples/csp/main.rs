@@ -1,5 +1,6 @@
 use agent::improve::algorithms::Verbosity;
 use agent::{
+    error::{Error, Result},
     improve::{algorithms::SteepestDescend, resolver::Resolver},
     problem::{self, Problem, StatePerturbation, SuitableState, Utility},
 };
@@ -70,8 +71,17 @@ impl problem::CostructSolution for Csp {
 
 impl Utility for Csp {
     fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        self.try_heuristic(state)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_heuristic(&self, state: &Self::State) -> Result<Self::Cost> {
         if state.len() != self.n {
-            panic!()
+            return Err(Error::InvalidState(format!(
+                "{} variables assigned out of {}",
+                state.len(),
+                self.n
+            )));
         }
 
         let mut cost = 0;
@@ -86,7 +96,7 @@ impl Utility for Csp {
         cost += max(3 - state[4], 0);
         // C_5
         cost += max(3 - (state[0] + state[4]), 0);
-        cost
+        Ok(cost)
     }
 }
 
@@ -137,6 +147,8 @@ impl StatePerturbation for Csp {
 fn main() {
     let mut resolver = Resolver::new(SteepestDescend::with_verbosity(rand::rng(), Verbosity::Max));
     let csp = Csp::new(5);
-    let sol = resolver.resolve(&csp);
-    println!("{:?}", sol)
+    match resolver.try_resolve(&csp) {
+        Ok(sol) => println!("{:?}", sol),
+        Err(e) => eprintln!("error: {}", e),
+    }
 }
@@ -1,10 +1,12 @@
 use std::fmt;
+use std::fs::File;
 use std::time::Duration;
 
 use agent::improve::{
     algorithms::GeneticAlgorithm, algorithms::HillClimbing, algorithms::ImprovingAlgorithm,
     algorithms::LocalBeam, algorithms::SimulatedAnnealing, algorithms::SteepestDescend,
-    resolver::Resolver,
+    algorithms::TabuSearch, benchmark::Benchmark, operators::CrossoverOperator,
+    operators::OnePoint, resolver::Resolver,
 };
 use agent::problem::{
     CostructSolution, Crossover, InitState, Problem, StatePerturbation, SuitableState, Utility,
@@ -108,6 +110,19 @@ impl DeploymentQueens {
         (result, (cost as f64).into())
     }
 
+    /// Conflicts of a queen in (col, row) with the queens of the other columns.
+    fn conflicts_at(&self, col: usize, row: usize) -> usize {
+        let mut conflicts = 0;
+        for (other_col, other_row) in self.pos.iter().enumerate() {
+            if other_col != col
+                && (*other_row == row || other_row.abs_diff(row) == other_col.abs_diff(col))
+            {
+                conflicts += 1;
+            }
+        }
+        conflicts
+    }
+
     fn move_queen(&self, dir: &MoveQueen) -> Self {
         let mut new_pos = self.pos.clone();
         new_pos[dir.col] = dir.new_row;
@@ -224,6 +239,22 @@ impl StatePerturbation for NQueen {
     fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
         state.move_queen(action)
     }
+
+    fn perturb_in_place(&self, state: &mut Self::State, action: &Self::Perturbation) {
+        state.pos[action.col] = action.new_row;
+    }
+
+    // only the conflicts of the moved queen change: O(n) instead of O(n²)
+    fn delta_heuristic(
+        &self,
+        state: &Self::State,
+        h: OrderedFloat<f64>,
+        action: &Self::Perturbation,
+    ) -> Option<agent::error::Result<OrderedFloat<f64>>> {
+        let old = state.conflicts_at(action.col, state.pos[action.col]);
+        let new = state.conflicts_at(action.col, action.new_row);
+        Some(Ok(h - old as f64 + new as f64))
+    }
 }
 
 impl Crossover for NQueen {
@@ -234,13 +265,15 @@ impl Crossover for NQueen {
         other: &Self::State,
     ) -> Self::State {
         // TODO: move the board in such a way as to minimize attacks
-        let crossover_point = rng.random_range(0..self.n);
-        let mut new_pos = state.pos[..crossover_point].to_vec();
-        new_pos.extend_from_slice(&other.pos[crossover_point..]);
-        Self::State::new(new_pos)
+        Self::State::new(OnePoint.crossover(rng, &state.pos, &other.pos))
     }
 }
 
+/// The queen moved last stays in its column for a few iterations.
+fn moved_column(_: &DeploymentQueens, m: &MoveQueen, _: &DeploymentQueens) -> usize {
+    m.col
+}
+
 fn resolve_nqueen<A: ImprovingAlgorithm<NQueen>>(
     problem: &NQueen,
     resolver: &mut Resolver<A, NQueen>,
@@ -326,6 +359,15 @@ fn run_one_time_nqueen(size: usize, n_restarts: usize) {
     ));
 
     run_one_time_nqueen_algo(&problem, &mut resolver, n_restarts);
+
+    println!("Tabu Search:");
+    let mut resolver = Resolver::new(TabuSearch::from_parts(
+        rand::rng(),
+        moved_column,
+        size / 4 + 1,
+        1000,
+    ));
+    run_one_time_nqueen_algo(&problem, &mut resolver, n_restarts);
 }
 
 fn run_nqueen(n: usize, iterations: u32, restarts: usize) {
@@ -358,6 +400,16 @@ fn run_nqueen(n: usize, iterations: u32, restarts: usize) {
         0.8,
     ));
     resolve_nqueen(&problem, &mut resolver, iterations);
+
+    println!("Tabu Search:");
+    let mut resolver = Resolver::new(TabuSearch::from_parts(
+        rand::rng(),
+        moved_column,
+        n / 4 + 1,
+        1000,
+    ));
+    resolve_nqueen(&problem, &mut resolver, iterations);
+    resolve_restart_nqueen(&problem, &mut resolver, iterations, restarts);
 }
 
 fn run_nqueen_explorer<'a, Backend: FrontierBackend<'a, NQueen> + fmt::Debug>(
@@ -398,6 +450,33 @@ fn run_nqueens_explorer(n: usize) {
     arena.reset();
 }
 
+fn run_benchmark(n: usize, seeds: u64, restarts: usize, csv: Option<String>) {
+    let small = NQueen::new(n);
+    let large = NQueen::new(2 * n);
+    let tenure = n / 4 + 1;
+    let report = Benchmark::new(0..seeds)
+        .verified()
+        .with_instance(format!("{} queens", n), &small)
+        .with_instance(format!("{} queens", 2 * n), &large)
+        .with_restarts(restarts)
+        .with_algorithm("Steepest Descend", "", SteepestDescend::new)
+        .with_parameters(
+            "Hill Climbing",
+            [("lateral 10", 10), ("lateral 100", 100)],
+            |lateral, rng| HillClimbing::with_max_lateral(rng, *lateral),
+        )
+        .with_algorithm("Simulated Annealing", "", SimulatedAnnealing::new)
+        .with_algorithm("Tabu Search", format!("tenure {}", tenure), move |rng| {
+            TabuSearch::from_parts(rng, moved_column, tenure, 1000)
+        })
+        .run();
+    println!("{}", report.to_markdown());
+    if let Some(path) = csv {
+        let file = File::create(&path).expect("cannot create the CSV file");
+        report.write_csv(file).expect("cannot write the CSV file");
+    }
+}
+
 use clap::Parser;
 
 #[derive(Parser)]
@@ -420,6 +499,18 @@ enum Command {
         #[clap(short, long)]
         restarts: usize,
     },
+    /// Benchmarks the improving algorithms on n and 2n queens, one run for every seed.
+    Benchmark {
+        #[clap(short)]
+        n: usize,
+        #[clap(short, long)]
+        seeds: u64,
+        #[clap(short, long)]
+        restarts: usize,
+        /// Writes the runs in CSV to this file.
+        #[clap(long)]
+        csv: Option<String>,
+    },
 }
 
 fn main() {
@@ -433,5 +524,11 @@ fn main() {
             iterations,
             restarts,
         } => run_nqueen(n, iterations, restarts),
+        Command::Benchmark {
+            n,
+            seeds,
+            restarts,
+            csv,
+        } => run_benchmark(n, seeds, restarts, csv),
     }
 }
@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use agent::problem::{CostructSolution, Problem, SuitableState, Utility};
+use agent::statexplorer::{parallel::HDAStarExplorer, resolver::AStarExplorer};
+
+use bumpalo::Bump;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Action {
+    Left,
+    Right,
+    Down,
+    Up,
+    Suck,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct HouseState {
+    pos: (usize, usize),
+    where_dirty: Vec<(usize, usize)>,
+}
+
+struct CleanProblem {
+    rows: usize,
+    cols: usize,
+}
+
+impl Problem for CleanProblem {
+    type State = HouseState;
+}
+
+impl CostructSolution for CleanProblem {
+    type Action = Action;
+    type Cost = u32;
+
+    fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+        let (x, y) = state.pos;
+        let mut actions = Vec::with_capacity(5);
+        if state.where_dirty.contains(&state.pos) {
+            actions.push(Action::Suck);
+        }
+        if x != 0 {
+            actions.push(Action::Left);
+        }
+        if x < self.rows - 1 {
+            actions.push(Action::Right);
+        }
+        if y != 0 {
+            actions.push(Action::Up);
+        }
+        if y < self.cols - 1 {
+            actions.push(Action::Down);
+        }
+        actions.into_iter()
+    }
+
+    fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, u32) {
+        let (x, y) = state.pos;
+        let mut new_state = state.clone();
+        match action {
+            Action::Left => new_state.pos = (x - 1, y),
+            Action::Right => new_state.pos = (x + 1, y),
+            Action::Up => new_state.pos = (x, y - 1),
+            Action::Down => new_state.pos = (x, y + 1),
+            Action::Suck => new_state.where_dirty.retain(|p| *p != state.pos),
+        }
+        (new_state, 1)
+    }
+}
+
+impl Utility for CleanProblem {
+    fn heuristic(&self, state: &Self::State) -> u32 {
+        // one move towards the nearest dirty cell and one suck for every dirty cell
+        let nearest = state
+            .where_dirty
+            .iter()
+            .map(|p| (p.0.abs_diff(state.pos.0) + p.1.abs_diff(state.pos.1)) as u32)
+            .min()
+            .unwrap_or(0);
+        nearest + state.where_dirty.len() as u32
+    }
+}
+
+impl SuitableState for CleanProblem {
+    fn is_suitable(&self, state: &Self::State) -> bool {
+        state.where_dirty.is_empty()
+    }
+}
+
+fn main() {
+    let problem = CleanProblem { rows: 16, cols: 16 };
+    let init_state = HouseState {
+        pos: (3, 2),
+        where_dirty: vec![(10, 15), (14, 1), (13, 12), (1, 13), (7, 7), (0, 6)],
+    };
+
+    let arena = Bump::new();
+    let mut explorer = AStarExplorer::new(&problem, &arena);
+    let result = explorer.search(init_state.clone());
+    let base = result.total_time;
+    println!(
+        "A*: cost {} expanded {} time {:?}",
+        result.actions.unwrap().len(),
+        result.stats.expanded,
+        base
+    );
+
+    let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut threads = 1;
+    while threads <= max_threads.max(2) {
+        let mut explorer = HDAStarExplorer::with_threads(&problem, threads);
+        let result = explorer.search(init_state.clone());
+        println!(
+            "HDA* {} threads: cost {} expanded {} time {:?} speedup {:.2}",
+            threads,
+            result.actions.unwrap().len(),
+            result.stats.expanded,
+            result.total_time,
+            speedup(base, result.total_time)
+        );
+        threads *= 2;
+    }
+}
+
+fn speedup(base: Duration, time: Duration) -> f64 {
+    base.as_secs_f64() / time.as_secs_f64()
+}
@@ -1,4 +1,6 @@
-use agent::problem::{CostructSolution, InitState, Problem, SuitableState, Utility};
+use agent::problem::{
+    CostructSolution, InitState, PheromoneFeature, Problem, SuitableState, Utility,
+};
 
 use bumpalo::Bump;
 
@@ -8,7 +10,7 @@ pub enum AminoAcid {
     P,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Dir {
     Up,
     Down,
@@ -254,3 +256,11 @@ impl<'a> InitState for ProteinFolding<'a> {
         self.arena.alloc(Default::default())
     }
 }
+
+impl PheromoneFeature for ProteinFolding<'_> {
+    type Feature = (usize, Dir);
+
+    fn feature(&self, state: &Self::State, dir: &Self::Action) -> Self::Feature {
+        (state.depth, *dir)
+    }
+}
@@ -1,4 +1,5 @@
 mod formulation;
+mod parallel;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -6,17 +7,20 @@ use std::{
 };
 
 use agent::{
-    problem::InitState,
+    improve::colony::{AntColony, AntConfig, AntVariant},
+    problem::{CostructSolution, InitState},
     statexplorer::{
         frontier::{
             AStarBackend, BestFirstBackend, DequeBackend, FrontierBackend, MinCostBackend,
             StackBackend,
         },
-        resolver::TreeExplorer,
+        parallel::HDAStarExplorer,
+        resolver::{AStarExplorer, TreeExplorer},
     },
 };
 use bumpalo::Bump;
 use formulation::{AminoAcid, Dir, ProteinFolding};
+use parallel::SharedFolding;
 use rand::seq::SliceRandom;
 
 fn run_example<'a, B: FrontierBackend<'a, ProteinFolding<'a>> + std::fmt::Debug>(
@@ -42,6 +46,19 @@ fn run_example_get_time<'a, B: FrontierBackend<'a, ProteinFolding<'a>>>(
     r.total_time
 }
 
+fn run_colony(problem: &ProteinFolding) {
+    let config = AntConfig {
+        variant: AntVariant::MaxMin { ratio: 20.0 },
+        ..AntConfig::default()
+    };
+    let mut colony = AntColony::new(rand::rng(), config);
+    let r = colony.solve(problem);
+    println!("iterations: {}, ants: {}", r.iterations, r.completed);
+    if let Some(best) = r.best {
+        print_solution(&problem.aminoacids, best.actions);
+    }
+}
+
 type MinCost<'a> = MinCostBackend<'a, ProteinFolding<'a>>;
 type AStar<'a> = AStarBackend<'a, ProteinFolding<'a>>;
 type BestFirst<'a> = BestFirstBackend<'a, ProteinFolding<'a>>;
@@ -148,11 +165,17 @@ fn run_all(protein: Vec<AminoAcid>) {
     }
     {
         let arena_problem = Bump::new();
-        let problem = ProteinFolding::new(protein, &arena_problem);
+        let problem = ProteinFolding::new(protein.clone(), &arena_problem);
         println!("AStar:");
         let arena_explorer = Bump::new();
         run_example::<AStar>(&arena_explorer, &problem);
     }
+    {
+        let arena_problem = Bump::new();
+        let problem = ProteinFolding::new(protein, &arena_problem);
+        println!("AntColony:");
+        run_colony(&problem);
+    }
     // println!("BestFirst:");
     // run_example::<BestFirst>(protein);
     // println!("DFS:");
@@ -161,6 +184,46 @@ fn run_all(protein: Vec<AminoAcid>) {
     // run_example::<BFS>(protein);
 }
 
+fn plan_cost(problem: &SharedFolding, actions: Vec<Dir>) -> u32 {
+    let mut state = problem.init_state();
+    let mut total = 0;
+    for dir in actions {
+        let (next, cost) = problem.result(&state, &dir);
+        state = next;
+        total += cost;
+    }
+    total
+}
+
+fn run_speedup(protein: Vec<AminoAcid>) {
+    let problem = SharedFolding::new(protein);
+    let arena = Bump::new();
+    let result = AStarExplorer::new(&problem, &arena).search(problem.init_state());
+    let base = result.total_time;
+    println!(
+        "A*: cost {} expanded {} time {:?}",
+        plan_cost(&problem, result.actions.unwrap()),
+        result.stats.expanded,
+        base
+    );
+
+    let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut threads = 1;
+    while threads <= max_threads.max(2) {
+        let mut explorer = HDAStarExplorer::with_threads(&problem, threads);
+        let result = explorer.search(problem.init_state());
+        println!(
+            "HDA* {} threads: cost {} expanded {} time {:?} speedup {:.2}",
+            threads,
+            plan_cost(&problem, result.actions.unwrap()),
+            result.stats.expanded,
+            result.total_time,
+            base.as_secs_f64() / result.total_time.as_secs_f64()
+        );
+        threads *= 2;
+    }
+}
+
 use AminoAcid::*;
 
 fn random_protein(n: usize, h_number: usize) -> Vec<AminoAcid> {
@@ -236,6 +299,10 @@ enum Commands {
         #[clap(short, long)]
         iters: usize,
     },
+    /// Compares A* with HDA* on an increasing number of threads.
+    Speedup {
+        aminoacids: AminoAcidSequence,
+    },
 }
 
 /*
@@ -253,6 +320,7 @@ fn main() {
     match args {
         Commands::RunProtein { aminoacids } => run_all(aminoacids.0),
         Commands::RandTest { len, iters } => random_test(len, iters),
+        Commands::Speedup { aminoacids } => run_speedup(aminoacids.0),
     }
 
     // let mut rng = rand::rng();
@@ -0,0 +1,119 @@
+use agent::problem::{CostructSolution, InitState, Problem, SuitableState, Utility};
+
+use crate::formulation::{AminoAcid, Dir};
+
+/// The formulation of `ProteinFolding` with the positions in a vector instead of a list in an
+/// arena, so that the states can be sent to the workers of `HDAStarExplorer`.
+pub struct SharedFolding {
+    pub aminoacids: Vec<AminoAcid>,
+    h_number: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Fold {
+    positions: Vec<(isize, isize)>,
+    has_turned: bool,
+    contacts: u32,
+}
+
+impl Fold {
+    fn depth(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    fn head(&self) -> (isize, isize) {
+        self.positions[self.depth()]
+    }
+}
+
+fn step((x, y): (isize, isize), dir: Dir) -> (isize, isize) {
+    match dir {
+        Dir::Up => (x - 1, y),
+        Dir::Down => (x + 1, y),
+        Dir::Left => (x, y - 1),
+        Dir::Right => (x, y + 1),
+    }
+}
+
+impl SharedFolding {
+    pub fn new(aminoacids: Vec<AminoAcid>) -> Self {
+        let h_number = aminoacids.iter().filter(|a| **a == AminoAcid::H).count() as u32;
+        Self {
+            aminoacids,
+            h_number,
+        }
+    }
+}
+
+impl Problem for SharedFolding {
+    type State = Fold;
+}
+
+impl CostructSolution for SharedFolding {
+    type Action = Dir;
+    type Cost = u32;
+
+    fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+        if state.depth() == 0 {
+            return vec![Dir::Up].into_iter();
+        }
+        // like in `ProteinFolding`, the first turn is always to the right
+        let dirs: &[Dir] = if state.has_turned {
+            &[Dir::Left, Dir::Down, Dir::Up, Dir::Right]
+        } else {
+            &[Dir::Down, Dir::Up, Dir::Right]
+        };
+        let head = state.head();
+        dirs.iter()
+            .copied()
+            .filter(|dir| !state.positions.contains(&step(head, *dir)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn result(&self, state: &Self::State, dir: &Self::Action) -> (Self::State, Self::Cost) {
+        let depth = state.depth();
+        let pos = step(state.head(), *dir);
+        let mut cost = 0;
+        if self.aminoacids[depth + 1] == AminoAcid::H {
+            let contacts = state.positions[..depth]
+                .iter()
+                .enumerate()
+                .filter(|(i, p)| {
+                    self.aminoacids[*i] == AminoAcid::H
+                        && p.0.abs_diff(pos.0) + p.1.abs_diff(pos.1) == 1
+                })
+                .count() as u32;
+            cost = 3 - contacts;
+        }
+        let mut next = state.clone();
+        next.positions.push(pos);
+        next.has_turned |= *dir == Dir::Left || *dir == Dir::Right;
+        if self.aminoacids[depth + 1] == AminoAcid::H && cost != 3 {
+            next.contacts += 1;
+        }
+        (next, cost)
+    }
+}
+
+impl SuitableState for SharedFolding {
+    fn is_suitable(&self, state: &Self::State) -> bool {
+        self.aminoacids.len() - 1 == state.depth()
+    }
+}
+
+impl Utility for SharedFolding {
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        self.h_number - state.contacts
+    }
+}
+
+impl InitState for SharedFolding {
+    fn init_state(&self) -> Self::State {
+        Fold {
+            positions: vec![(0, 0)],
+            has_turned: false,
+            contacts: 0,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Errors reported by the fallible (`try_*`) entry points of explorers and algorithms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The problem cannot evaluate the state.
+    InvalidState(String),
+    /// A node has been created with a parent and no action, or vice versa.
+    InvalidNode,
+    /// The population cannot be sampled, e.g. because every weight is zero or not finite.
+    InvalidWeights(String),
+    EmptyPopulation,
+    /// An `InitialStateGenerator` could not build a state.
+    NoInitialState(String),
+    /// An internal invariant has been violated.
+    Invariant(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidState(reason) => write!(f, "invalid state: {}", reason),
+            Error::InvalidNode => write!(f, "a node needs both a parent and an action, or neither"),
+            Error::InvalidWeights(reason) => write!(f, "cannot sample the population: {}", reason),
+            Error::EmptyPopulation => write!(f, "the population is empty"),
+            Error::NoInitialState(reason) => {
+                write!(f, "cannot generate an initial state: {}", reason)
+            }
+            Error::Invariant(what) => write!(f, "invariant violated: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,23 +1,178 @@
-use std::{cmp::Reverse, collections::BinaryHeap, fmt, ops::Sub};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt,
+    hash::Hash,
+    ops::Sub,
+};
 
-use ordered_float::OrderedFloat;
-use rand::Rng;
+use libm::exp;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 use rand_distr::{
     num_traits::{Inv, Signed},
     weighted::WeightedIndex,
     Distribution,
 };
 
+use crate::error::{Error, Result};
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::observer::{diversity, IterationStats, LogObserver, NoObserver, SearchObserver};
 use crate::problem::*;
 
-pub trait ImprovingAlgorithm<P>
+use super::initial::{GreedyConstruction, InitialStateGenerator};
+use super::resolver::{AttemptOutcome, AttemptResult, Progress};
+
+pub trait ImprovingAlgorithm<P>: LimitSearch
+where
+    P: Utility,
+{
+    fn attempt(&mut self, problem: &P) -> AttemptResult<P> {
+        self.attempt_observed(problem, &mut NoObserver)
+    }
+
+    fn attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> AttemptResult<P> {
+        self.try_attempt_observed(problem, observer)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_attempt(&mut self, problem: &P) -> Result<AttemptResult<P>> {
+        self.try_attempt_observed(problem, &mut NoObserver)
+    }
+
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>>;
+
+    /// Continues an attempt interrupted by a limit from the `progress` it returned.
+    fn resume(&mut self, problem: &P, progress: Progress<P::State, P::Cost>) -> AttemptResult<P> {
+        self.try_resume_observed(problem, progress, &mut NoObserver)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>>;
+
+    /// States an attempt starts from, 1 for the single state algorithms.
+    fn population_size(&self) -> usize {
+        1
+    }
+
+    /// An attempt starting from `population_size` states built by `generator` instead of `RandomState`.
+    fn attempt_from(
+        &mut self,
+        problem: &P,
+        generator: &mut dyn InitialStateGenerator<P>,
+    ) -> AttemptResult<P> {
+        self.try_attempt_from(problem, generator, &mut NoObserver)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_attempt_from(
+        &mut self,
+        problem: &P,
+        generator: &mut dyn InitialStateGenerator<P>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let population = (0..self.population_size().max(1))
+            .map(|_| generator.generate(problem))
+            .collect::<Result<_>>()?;
+        self.try_resume_observed(problem, Progress::new(population), observer)
+    }
+}
+
+/// The current state of a single state algorithm.
+fn current_state<State, Cost>(progress: Progress<State, Cost>) -> Result<State> {
+    progress
+        .population
+        .into_iter()
+        .next()
+        .ok_or(Error::EmptyPopulation)
+}
+
+/// The heuristic of `state` after `action`, with the new state when it had to be built because
+/// the problem has no delta evaluation.
+fn evaluate<P>(
+    problem: &P,
+    state: &P::State,
+    h: P::Cost,
+    action: &P::Perturbation,
+) -> Result<(P::Cost, Option<P::State>)>
+where
+    P: StatePerturbation + Utility,
+{
+    match problem.delta_heuristic(state, h, action) {
+        Some(next_h) => Ok((next_h?, None)),
+        None => {
+            let next = problem.perturb(state, action);
+            Ok((problem.try_heuristic(&next)?, Some(next)))
+        }
+    }
+}
+
+/// Moves `state` along `action`, reusing the state built by `evaluate`.
+fn advance<P>(problem: &P, state: &mut P::State, action: &P::Perturbation, next: Option<P::State>)
 where
-    P: CostructSolution,
+    P: StatePerturbation,
 {
-    fn attempt(&mut self, problem: &P) -> AttemptResult<P>;
+    match next {
+        Some(next) => *state = next,
+        None => problem.perturb_in_place(state, action),
+    }
+}
+
+/// Saves and restores a seedable random generator, for the algorithms built `with_rng_checkpoints`.
+pub(crate) struct RngCheckpoint<R> {
+    save: fn(&mut R) -> u64,
+    restore: fn(&mut R, u64),
+}
+
+impl<R> Clone for RngCheckpoint<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for RngCheckpoint<R> {}
+
+impl<R: Rng + SeedableRng> RngCheckpoint<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            save: |rng| {
+                let seed = rng.random();
+                *rng = R::seed_from_u64(seed);
+                seed
+            },
+            restore: |rng, seed| *rng = R::seed_from_u64(seed),
+        }
+    }
 }
 
-#[derive(PartialEq, Eq)]
+/// Resets `rng` to a seed drawn from it, saved in the `Progress` of a stopped attempt so that
+/// `resume` draws the same numbers whatever the generator it is given. Without `checkpoint` the
+/// generator is left alone and no seed is saved.
+pub(crate) fn reseed<R>(checkpoint: Option<RngCheckpoint<R>>, rng: &mut R) -> Option<u64> {
+    checkpoint.map(|checkpoint| (checkpoint.save)(rng))
+}
+
+/// Restores the generator saved by `reseed`.
+pub(crate) fn restore<R>(checkpoint: Option<RngCheckpoint<R>>, rng: &mut R, seed: Option<u64>) {
+    if let (Some(checkpoint), Some(seed)) = (checkpoint, seed) {
+        (checkpoint.restore)(rng, seed);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Verbosity {
     Low,
     Max,
@@ -25,7 +180,7 @@ pub enum Verbosity {
 
 pub trait VerbosityLevel<P>: ImprovingAlgorithm<P>
 where
-    P: CostructSolution,
+    P: Utility,
 {
     fn set_verbosity(v: Verbosity);
 }
@@ -33,6 +188,7 @@ where
 pub struct SteepestDescend<R: Rng> {
     verb: Verbosity,
     rng: R,
+    limits: SearchLimits,
 }
 
 impl<R: Rng> SteepestDescend<R> {
@@ -40,6 +196,7 @@ impl<R: Rng> SteepestDescend<R> {
         Self {
             rng: rng,
             verb: Verbosity::Low,
+            limits: SearchLimits::default(),
         }
     }
 
@@ -47,51 +204,103 @@ impl<R: Rng> SteepestDescend<R> {
         Self {
             verb: verb,
             rng: rng,
+            limits: SearchLimits::default(),
         }
     }
 }
 
-impl<R, P> ImprovingAlgorithm<P> for SteepestDescend<R>
-where
-    R: Rng,
-    P: StatePerturbation + Utility + RandomState<State: Clone + fmt::Debug, Cost: fmt::Debug>,
-{
-    fn attempt(&mut self, problem: &P) -> AttemptResult<P> {
-        let mut iterations = 0;
-        let mut curr_state = problem.random_state(&mut self.rng);
-        let mut curr_h = problem.heuristic(&curr_state);
+impl<R: Rng> SteepestDescend<R> {
+    fn descend<P>(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>>
+    where
+        P: StatePerturbation + Utility + RandomState<State: Clone>,
+    {
+        let mut iterations = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        let limits = self.limits.start().offset(iterations, evaluated);
+        let mut curr_state = current_state(progress)?;
+        let mut curr_h = problem.try_heuristic(&curr_state)?;
+        if iterations == 0 {
+            evaluated += 1;
+        }
         loop {
-            iterations += 1;
-            if self.verb == Verbosity::Max {
-                eprintln!(
-                    "\nIteration: {}\ncurrent state:\n{:?}\nh:{:?}",
-                    iterations, curr_state, curr_h
+            if let Some(limit) = limits.check(iterations, evaluated, 0) {
+                let progress = Progress {
+                    iteration: iterations,
+                    evaluated,
+                    ..Progress::new(vec![curr_state.clone()])
+                };
+                return Ok(
+                    AttemptResult::stopped(curr_state, curr_h, iterations, limit)
+                        .with_evaluated(evaluated)
+                        .with_progress(progress),
                 );
             }
-            let mut new_curr_state = curr_state.clone();
+            iterations += 1;
+            observer.on_iteration(iterations, &curr_state, curr_h);
+            observer.on_statistics(&IterationStats::new(iterations, evaluated, curr_h, curr_h));
+            let mut best_move = None;
             let mut new_curr_h = curr_h;
             for a in problem.perturbations(&curr_state) {
-                let new_state = problem.perturb(&curr_state, &a);
-                let new_h = problem.heuristic(&new_state);
+                let (new_h, next) = evaluate(problem, &curr_state, curr_h, &a)?;
+                evaluated += 1;
                 if new_h < new_curr_h {
-                    new_curr_state = new_state;
+                    best_move = Some((a, next));
                     new_curr_h = new_h;
                 }
             }
-            if curr_h > new_curr_h {
-                curr_state = new_curr_state;
+            if let Some((a, next)) = best_move {
+                advance(problem, &mut curr_state, &a, next);
                 curr_h = new_curr_h;
+                observer.on_improvement(iterations, &curr_state, curr_h);
             } else {
-                let result = AttemptResult::new(curr_state, curr_h, iterations);
-                return result;
+                if curr_h <= Default::default() {
+                    observer.on_goal(iterations, &curr_state, curr_h);
+                }
+                let result =
+                    AttemptResult::new(curr_state, curr_h, iterations).with_evaluated(evaluated);
+                return Ok(result);
             }
         }
     }
 }
 
+impl<R, P> ImprovingAlgorithm<P> for SteepestDescend<R>
+where
+    R: Rng,
+    P: StatePerturbation + Utility + RandomState<State: Clone + fmt::Debug, Cost: fmt::Debug>,
+{
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let progress = Progress::new(vec![problem.try_random_state(&mut self.rng)?]);
+        self.try_resume_observed(problem, progress, observer)
+    }
+
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        if self.verb == Verbosity::Max {
+            self.descend(problem, progress, &mut (LogObserver, observer))
+        } else {
+            self.descend(problem, progress, observer)
+        }
+    }
+}
+
 pub struct HillClimbing<R: Rng> {
     rng: R,
     max_lateral: Option<usize>,
+    limits: SearchLimits,
 }
 
 impl<R: Rng> HillClimbing<R> {
@@ -99,6 +308,7 @@ impl<R: Rng> HillClimbing<R> {
         Self {
             rng: rng,
             max_lateral: None,
+            limits: SearchLimits::default(),
         }
     }
 
@@ -106,35 +316,44 @@ impl<R: Rng> HillClimbing<R> {
         Self {
             rng: rng,
             max_lateral: max_lateral.into(),
+            limits: SearchLimits::default(),
         }
     }
 }
 
+/// A move chosen by `HillClimbing`, with the heuristic and the state it leads to, see `evaluate`.
+type Neighbor<P> = (
+    <P as StatePerturbation>::Perturbation,
+    <P as CostructSolution>::Cost,
+    Option<<P as Problem>::State>,
+);
+
 impl<R> HillClimbing<R>
 where
     R: Rng,
 {
     fn get_next_state<P: Utility + StatePerturbation>(
         lateral: &mut usize,
+        evaluated: &mut usize,
         problem: &P,
         state: &P::State,
         curr_h: P::Cost,
         max_lateral: Option<usize>,
-    ) -> Option<(P::State, P::Cost)> {
+    ) -> Result<Option<Neighbor<P>>> {
         let mut actions = problem.perturbations(state);
         while let Some(a) = actions.next() {
-            let next_state = problem.perturb(state, &a);
-            let next_h = problem.heuristic(&next_state);
-            if max_lateral.map_or(true, |x| x > *lateral) && next_h == curr_h {
+            let (next_h, next) = evaluate(problem, state, curr_h, &a)?;
+            *evaluated += 1;
+            if max_lateral.is_none_or(|x| x > *lateral) && next_h == curr_h {
                 *lateral += 1;
-                return (next_state, next_h).into();
+                return Ok(Some((a, next_h, next)));
             }
             if next_h < curr_h {
                 *lateral = 0;
-                return (next_state, next_h).into();
+                return Ok(Some((a, next_h, next)));
             }
         }
-        None
+        Ok(None)
     }
 }
 
@@ -143,275 +362,1899 @@ where
     P: StatePerturbation + Utility + RandomState<State: Clone>,
     R: Rng,
 {
-    fn attempt(&mut self, problem: &P) -> AttemptResult<P> {
-        let mut curr_state = problem.random_state(&mut self.rng);
-        let mut curr_h = problem.heuristic(&curr_state);
-        let mut iterations = 0;
-        let mut lateral = 0;
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let progress = Progress::new(vec![problem.try_random_state(&mut self.rng)?]);
+        self.try_resume_observed(problem, progress, observer)
+    }
+
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut iterations = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        let mut lateral = progress.lateral;
+        let limits = self.limits.start().offset(iterations, evaluated);
+        let mut curr_state = current_state(progress)?;
+        let mut curr_h = problem.try_heuristic(&curr_state)?;
+        if iterations == 0 {
+            evaluated += 1;
+        }
         loop {
+            if let Some(limit) = limits.check(iterations, evaluated, 0) {
+                let progress = Progress {
+                    iteration: iterations,
+                    evaluated,
+                    lateral,
+                    ..Progress::new(vec![curr_state.clone()])
+                };
+                return Ok(
+                    AttemptResult::stopped(curr_state, curr_h, iterations, limit)
+                        .with_evaluated(evaluated)
+                        .with_progress(progress),
+                );
+            }
             iterations += 1;
-            let to_assign =
-                Self::get_next_state(&mut lateral, problem, &curr_state, curr_h, self.max_lateral);
-            if let Some((next_state, next_h)) = to_assign {
-                curr_state = next_state;
+            observer.on_iteration(iterations, &curr_state, curr_h);
+            observer.on_statistics(&IterationStats::new(iterations, evaluated, curr_h, curr_h));
+            let to_assign = Self::get_next_state(
+                &mut lateral,
+                &mut evaluated,
+                problem,
+                &curr_state,
+                curr_h,
+                self.max_lateral,
+            )?;
+            if let Some((next_move, next_h, next)) = to_assign {
+                let improved = next_h < curr_h;
+                advance(problem, &mut curr_state, &next_move, next);
                 curr_h = next_h;
+                if improved {
+                    observer.on_improvement(iterations, &curr_state, curr_h);
+                }
             } else {
-                return AttemptResult::new(curr_state, curr_h, iterations);
+                if curr_h <= Default::default() {
+                    observer.on_goal(iterations, &curr_state, curr_h);
+                }
+                return Ok(
+                    AttemptResult::new(curr_state, curr_h, iterations).with_evaluated(evaluated)
+                );
             }
         }
     }
 }
 
-pub struct SimulatedAnnealing<R: Rng> {
+/// The temperature of `SimulatedAnnealing` at the iteration t of the schedule.
+pub trait CoolingSchedule {
+    fn temperature(&self, t: usize) -> f64;
+}
+
+impl<F> CoolingSchedule for F
+where
+    F: Fn(usize) -> f64,
+{
+    fn temperature(&self, t: usize) -> f64 {
+        self(t)
+    }
+}
+
+/// Cooling schedules of `SimulatedAnnealing` that can be stored and chosen by name.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cooling {
+    /// T(t) = 1 / t
+    #[default]
+    Inverse,
+    /// T(t) = t0 * alpha^t
+    Exponential { t0: f64, alpha: f64 },
+    /// T(t) = t0 / (1 + ln(1 + t))
+    Logarithmic { t0: f64 },
+    /// T(t) = t0 * (1 - t / steps)
+    Linear { t0: f64, steps: usize },
+    /// T(t + 1) = T(t) / (1 + beta * T(t)), that is T(t) = t0 / (1 + beta * t0 * t)
+    LundyMees { t0: f64, beta: f64 },
+    /// A schedule given as a function, it cannot be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(usize) -> f64),
+}
+
+impl Cooling {
+    pub fn temperature(&self, t: usize) -> f64 {
+        let t_f = t as f64;
+        match *self {
+            Cooling::Inverse => 1.0 / t_f,
+            Cooling::Exponential { t0, alpha } => t0 * alpha.powf(t_f),
+            Cooling::Logarithmic { t0 } => t0 / (1.0 + t_f.ln_1p()),
+            Cooling::Linear { t0, steps } => t0 * (1.0 - t_f / steps as f64).max(0.0),
+            Cooling::LundyMees { t0, beta } => t0 / (1.0 + beta * t0 * t_f),
+            Cooling::Custom(f) => f(t),
+        }
+    }
+}
+
+impl CoolingSchedule for Cooling {
+    fn temperature(&self, t: usize) -> f64 {
+        Cooling::temperature(self, t)
+    }
+}
+
+/// Restarts the schedule from its first iteration when the best state does not improve for `after` iterations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reheat {
+    pub after: usize,
+    /// Reheats allowed in an attempt.
+    pub max: usize,
+}
+
+/// Scales the temperature so that about `target` of the uphill moves are accepted, the ratio is
+/// measured every `window` uphill moves. The scale stays within a factor `ADAPTIVE_RANGE`
+/// of the initial one, so that the schedule still cools down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveAcceptance {
+    pub target: f64,
+    pub window: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnnealingConfig {
+    /// The attempt ends when the temperature drops to this value.
+    pub precision: f64,
+    pub reheat: Option<Reheat>,
+    /// Estimates the initial temperature from `ESTIMATE_SAMPLES` random uphill moves of the start
+    /// state, so that their average is accepted with this probability.
+    pub initial_acceptance: Option<f64>,
+    pub adaptive: Option<AdaptiveAcceptance>,
+}
+
+impl Default for AnnealingConfig {
+    fn default() -> Self {
+        Self {
+            precision: 10e-6,
+            reheat: None,
+            initial_acceptance: None,
+            adaptive: None,
+        }
+    }
+}
+
+/// The temperature is `scale * schedule.temperature(clock)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnnealingProgress {
+    /// Iterations since the start of the schedule or since the last reheat.
+    pub clock: usize,
+    /// Iterations without improving the best state.
+    pub stagnation: usize,
+    pub reheats: usize,
+    pub scale: f64,
+    pub initial_scale: f64,
+    /// Uphill moves proposed and accepted in the current adaptive window.
+    pub uphill: usize,
+    pub accepted: usize,
+}
+
+impl AnnealingProgress {
+    fn new(clock: usize, scale: f64) -> Self {
+        Self {
+            clock,
+            stagnation: 0,
+            reheats: 0,
+            scale,
+            initial_scale: scale,
+            uphill: 0,
+            accepted: 0,
+        }
+    }
+}
+
+/// Random perturbations sampled to estimate the initial temperature.
+pub const ESTIMATE_SAMPLES: usize = 100;
+/// How far the adaptive mode can move the scale of the temperature from the initial one.
+pub const ADAPTIVE_RANGE: f64 = 100.0;
+
+pub struct SimulatedAnnealing<R: Rng, C = Cooling> {
     rng: R,
-    cooling: fn(usize) -> f64,
-    precision: f64,
+    checkpoint: Option<RngCheckpoint<R>>,
+    cooling: C,
+    config: AnnealingConfig,
+    limits: SearchLimits,
 }
 
 impl<R: Rng> SimulatedAnnealing<R> {
     pub fn default_cooling(t: usize) -> f64 {
-        1.0 / (t as f64)
+        Cooling::Inverse.temperature(t)
     }
 
     pub fn new(rng: R) -> Self {
+        Self::with_schedule(rng, Cooling::Inverse)
+    }
+
+    pub fn with_cooling(rng: R, cooling: fn(usize) -> f64) -> Self {
+        Self::with_schedule(rng, Cooling::Custom(cooling))
+    }
+}
+
+impl<R: Rng, C: CoolingSchedule> SimulatedAnnealing<R, C> {
+    pub fn with_schedule(rng: R, cooling: C) -> Self {
+        Self::from_config(rng, cooling, AnnealingConfig::default())
+    }
+
+    pub fn from_config(rng: R, cooling: C, config: AnnealingConfig) -> Self {
         Self {
-            rng: rng,
-            cooling: Self::default_cooling,
-            precision: 10e-6,
+            rng,
+            checkpoint: None,
+            cooling,
+            config,
+            limits: SearchLimits::default(),
         }
     }
 
-    pub fn with_cooling(rng: R, cooling: fn(usize) -> f64) -> Self {
+    pub fn cooling(&self) -> &C {
+        &self.cooling
+    }
+
+    pub fn config(&self) -> &AnnealingConfig {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Saves the random generator in the `Progress` of a stopped attempt, resetting it to a seed
+    /// drawn from it, so that `resume` continues like the interrupted attempt whatever the
+    /// generator it is given.
+    pub fn with_rng_checkpoints(self) -> Self
+    where
+        R: SeedableRng,
+    {
         Self {
-            rng: rng,
-            cooling: cooling,
-            precision: 10e-6,
+            checkpoint: Some(RngCheckpoint::new()),
+            ..self
+        }
+    }
+
+    /// The scale giving the initial temperature chosen by `AnnealingConfig::initial_acceptance`,
+    /// measured against T(0), or T(1) for the schedules that start at infinity.
+    fn estimate_scale<P>(
+        &mut self,
+        problem: &P,
+        state: &P::State,
+        h: P::Cost,
+        evaluated: &mut usize,
+    ) -> Result<f64>
+    where
+        P: RandomPerturbation + Utility<Cost: Sub<Output = P::Cost> + Into<f64>>,
+    {
+        let Some(acceptance) = self.config.initial_acceptance else {
+            return Ok(1.0);
+        };
+        let (mut total, mut uphill) = (0.0, 0);
+        for _ in 0..ESTIMATE_SAMPLES {
+            let Some(p) = problem.random_pertubation(&mut self.rng, state) else {
+                break;
+            };
+            let next_h = problem.try_heuristic(&problem.perturb(state, &p))?;
+            *evaluated += 1;
+            if next_h > h {
+                total += (next_h - h).into();
+                uphill += 1;
+            }
+        }
+        let reference = match self.cooling.temperature(0) {
+            t if t.is_finite() && t > 0.0 => t,
+            _ => self.cooling.temperature(1),
+        };
+        if uphill == 0 || reference <= 0.0 {
+            return Ok(1.0);
         }
+        let t0 = -(total / uphill as f64) / acceptance.ln();
+        Ok(t0 / reference)
     }
 }
 
-use libm::exp;
+impl<R: Rng, C> LimitSearch for SimulatedAnnealing<R, C> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
 
-use super::resolver::AttemptResult;
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
 
-impl<R, P> ImprovingAlgorithm<P> for SimulatedAnnealing<R>
+impl<R, C, P> ImprovingAlgorithm<P> for SimulatedAnnealing<R, C>
 where
-    P: RandomPerturbation + Utility + RandomState<Cost: Sub<Output = P::Cost> + Into<f64> + Signed>,
+    P: RandomPerturbation
+        + Utility
+        + RandomState<State: Clone, Cost: Sub<Output = P::Cost> + Into<f64> + Signed>,
     R: Rng,
+    C: CoolingSchedule,
 {
-    fn attempt(&mut self, problem: &P) -> AttemptResult<P> {
-        let mut curr_state = problem.random_state(&mut self.rng);
-        let mut curr_h = problem.heuristic(&curr_state);
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let progress = Progress::new(vec![problem.try_random_state(&mut self.rng)?]);
+        self.try_resume_observed(problem, progress, observer)
+    }
+
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut t = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        let best_h = progress.best_h;
+        let annealing = progress.annealing;
+        restore(self.checkpoint, &mut self.rng, progress.seed);
+        let limits = self.limits.start().offset(t, evaluated);
+        let mut curr_state = current_state(progress)?;
+        let mut curr_h = problem.try_heuristic(&curr_state)?;
+        let mut best_h = best_h.unwrap_or(curr_h);
+        let mut schedule = match annealing {
+            Some(annealing) => annealing,
+            None if t == 0 => {
+                evaluated += 1;
+                let scale = self.estimate_scale(problem, &curr_state, curr_h, &mut evaluated)?;
+                AnnealingProgress::new(0, scale)
+            }
+            None => AnnealingProgress::new(t, 1.0),
+        };
 
-        for t in 0.. {
-            let velocity = (self.cooling)(t);
+        loop {
+            if let Some(limit) = limits.check(t, evaluated, 0) {
+                let progress = Progress {
+                    iteration: t,
+                    evaluated,
+                    best_h: Some(best_h),
+                    annealing: Some(schedule),
+                    seed: reseed(self.checkpoint, &mut self.rng),
+                    ..Progress::new(vec![curr_state.clone()])
+                };
+                return Ok(AttemptResult::stopped(curr_state, curr_h, t, limit)
+                    .with_evaluated(evaluated)
+                    .with_progress(progress));
+            }
+            let velocity = schedule.scale * self.cooling.temperature(schedule.clock);
+            observer.on_iteration(t + 1, &curr_state, curr_h);
+            if curr_h < best_h {
+                best_h = curr_h;
+                schedule.stagnation = 0;
+                observer.on_improvement(t + 1, &curr_state, curr_h);
+            }
+            observer.on_statistics(&IterationStats {
+                temperature: Some(velocity),
+                acceptance: (schedule.uphill > 0)
+                    .then(|| schedule.accepted as f64 / schedule.uphill as f64),
+                ..IterationStats::new(t + 1, evaluated, best_h, curr_h)
+            });
             if curr_h <= Default::default() {
-                return AttemptResult::new(curr_state, curr_h, t + 1);
+                observer.on_goal(t + 1, &curr_state, curr_h);
+                return Ok(AttemptResult::new(curr_state, curr_h, t + 1).with_evaluated(evaluated));
             }
-            if velocity <= self.precision {
-                return AttemptResult::new(curr_state, curr_h, t + 1);
+            if velocity <= self.config.precision {
+                return Ok(AttemptResult::new(curr_state, curr_h, t + 1).with_evaluated(evaluated));
             }
             let next_action = problem.random_pertubation(&mut self.rng, &curr_state);
             if let Some(next_action) = next_action {
-                let next_state = problem.perturb(&curr_state, &next_action);
-                let next_h = problem.heuristic(&next_state);
-                if next_h <= curr_h {
-                    curr_state = next_state;
-                    curr_h = next_h;
+                let (next_h, next) = evaluate(problem, &curr_state, curr_h, &next_action)?;
+                evaluated += 1;
+                let accepted = if next_h <= curr_h {
+                    true
                 } else {
                     let diff: f64 = (curr_h - next_h).abs().into();
                     let r: f64 = self.rng.random();
-                    if r <= (1.0 / exp(diff / velocity)) {
-                        curr_state = next_state;
-                        curr_h = next_h;
+                    schedule.uphill += 1;
+                    r <= (1.0 / exp(diff / velocity))
+                };
+                if accepted {
+                    advance(problem, &mut curr_state, &next_action, next);
+                    if next_h > curr_h {
+                        schedule.accepted += 1;
                     }
+                    curr_h = next_h;
+                }
+            }
+            t += 1;
+            schedule.clock += 1;
+            schedule.stagnation += 1;
+            if let Some(adaptive) = self.config.adaptive {
+                if schedule.uphill >= adaptive.window.max(1) {
+                    let ratio = schedule.accepted as f64 / schedule.uphill as f64;
+                    let factor = if ratio > adaptive.target {
+                        0.9
+                    } else {
+                        1.0 / 0.9
+                    };
+                    schedule.scale = (schedule.scale * factor).clamp(
+                        schedule.initial_scale / ADAPTIVE_RANGE,
+                        schedule.initial_scale * ADAPTIVE_RANGE,
+                    );
+                    schedule.uphill = 0;
+                    schedule.accepted = 0;
+                }
+            }
+            if let Some(reheat) = self.config.reheat {
+                if schedule.stagnation >= reheat.after && schedule.reheats < reheat.max {
+                    schedule.clock = 0;
+                    schedule.stagnation = 0;
+                    schedule.reheats += 1;
                 }
             }
         }
-
-        unreachable!()
     }
 }
 
-struct Node<P>(Reverse<P::Cost>, P::State)
-where
-    P: CostructSolution;
+/// A successor of `LocalBeam`, ordered by its heuristic alone.
+struct Node<C, T>(Reverse<C>, T);
 
-impl<P> PartialEq for Node<P>
-where
-    P: CostructSolution,
-{
+/// A successor of the beam: the index of its parent, the move, and the state if it has been built.
+type Successor<P> = Node<
+    <P as CostructSolution>::Cost,
+    (
+        usize,
+        <P as StatePerturbation>::Perturbation,
+        Option<<P as Problem>::State>,
+    ),
+>;
+
+impl<C: Ord, T> PartialEq for Node<C, T> {
     fn eq(&self, other: &Self) -> bool {
         self.0 == other.0
     }
 }
 
-impl<P> Eq for Node<P> where P: CostructSolution {}
+impl<C: Ord, T> Eq for Node<C, T> {}
 
-impl<P> PartialOrd for Node<P>
-where
-    P: CostructSolution,
-{
+impl<C: Ord, T> PartialOrd for Node<C, T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
     }
 }
 
-impl<P> Ord for Node<P>
-where
-    P: CostructSolution,
-{
+impl<C: Ord, T> Ord for Node<C, T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.0.cmp(&other.0)
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalBeamConfig {
+    /// Number of states kept at every iteration.
+    pub k: usize,
+    pub max_iter: Option<usize>,
+}
+
 pub struct LocalBeam<R: Rng> {
     rng: R,
-    k: usize,
-    max_iter: Option<usize>,
+    config: LocalBeamConfig,
+    limits: SearchLimits,
 }
 
 impl<R: Rng> LocalBeam<R> {
     pub fn from_parts(rng: R, k: usize, max_iter: Option<usize>) -> Self {
+        Self::from_config(rng, LocalBeamConfig { k, max_iter })
+    }
+
+    pub fn from_config(rng: R, config: LocalBeamConfig) -> Self {
         Self {
-            rng: rng,
-            k: k,
-            max_iter: max_iter,
+            rng,
+            config,
+            limits: SearchLimits::default(),
         }
     }
+
+    pub fn config(&self) -> &LocalBeamConfig {
+        &self.config
+    }
 }
 
 impl<R, P> ImprovingAlgorithm<P> for LocalBeam<R>
 where
     R: Rng,
-    P: Utility + StatePerturbation + RandomState + CostructSolution + InitState,
+    P: Utility + StatePerturbation + RandomState<State: Clone> + CostructSolution,
 {
-    fn attempt(&mut self, problem: &P) -> AttemptResult<P> {
-        let mut current_pop = Vec::with_capacity(self.k);
-        for _ in 0..self.k {
-            current_pop.push(problem.random_state(&mut self.rng));
+    fn population_size(&self) -> usize {
+        self.config.k
+    }
+
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut population = Vec::with_capacity(self.config.k);
+        for _ in 0..self.config.k {
+            population.push(problem.try_random_state(&mut self.rng)?);
         }
-        let mut iter = 0;
-        let mut succ: BinaryHeap<Node<P>> = BinaryHeap::new();
+        self.try_resume_observed(problem, Progress::new(population), observer)
+    }
+
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut iter = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        let mut best_h = progress.best_h;
+        let limits = self.limits.start().offset(iter, evaluated);
+        let mut current_pop = progress.population;
+        let mut current_h = current_pop
+            .iter()
+            .map(|s| problem.try_heuristic(s))
+            .collect::<Result<Vec<_>>>()?;
+        // the best member of every population, returned when the attempt stops or the beam dies
+        let mut best = current_h
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, h)| **h)
+            .map(|(i, h)| (current_pop[i].clone(), *h));
+        // only the successors that enter the beam are built
+        let mut succ: BinaryHeap<Successor<P>> = BinaryHeap::new();
         loop {
+            if let Some(limit) = limits.check(iter, evaluated, 0) {
+                let (state, h) = best.ok_or(Error::EmptyPopulation)?;
+                let progress = Progress {
+                    iteration: iter,
+                    evaluated,
+                    best_h,
+                    ..Progress::new(current_pop)
+                };
+                return Ok(AttemptResult::stopped(state, h, iter, limit)
+                    .with_evaluated(evaluated)
+                    .with_progress(progress));
+            }
             iter += 1;
             succ.clear();
-            for s in current_pop.iter() {
+            for (i, s) in current_pop.iter().enumerate() {
                 for a in problem.perturbations(s) {
-                    let next_s = problem.perturb(s, &a);
-                    let next_h = problem.heuristic(&next_s);
+                    let (next_h, next) = evaluate(problem, s, current_h[i], &a)?;
+                    evaluated += 1;
                     if next_h <= Default::default() {
-                        return AttemptResult::new(next_s, next_h, iter);
+                        let next_s = next.unwrap_or_else(|| problem.perturb(s, &a));
+                        observer.on_goal(iter, &next_s, next_h);
+                        return Ok(
+                            AttemptResult::new(next_s, next_h, iter).with_evaluated(evaluated)
+                        );
                     } else {
-                        succ.push(Node(Reverse(next_h), next_s));
+                        succ.push(Node(Reverse(next_h), (i, a, next)));
                     }
                 }
             }
 
-            if self.max_iter.map_or(false, |max| max < iter) {
-                let node = succ
-                    .pop()
-                    .unwrap_or(Node(Default::default(), problem.init_state()));
-                return AttemptResult::new(node.1, node.0 .0, iter);
-            }
-
-            current_pop.clear();
-            for _ in 0..self.k {
-                let next_s = succ.pop().map(|n| n.1);
-                if let Some(next_s) = next_s {
-                    current_pop.push(next_s);
+            let mut next_pop = Vec::with_capacity(self.config.k);
+            let mut next_h = Vec::with_capacity(self.config.k);
+            for _ in 0..self.config.k {
+                if let Some(Node(Reverse(h), (i, a, next))) = succ.pop() {
+                    next_pop.push(next.unwrap_or_else(|| problem.perturb(&current_pop[i], &a)));
+                    next_h.push(h);
                 } else {
                     break;
                 }
             }
-            if current_pop.len() == 0 {
-                // TODO: make sure that AttemptResult returns a failure
-                return AttemptResult::new(problem.init_state(), Default::default(), iter);
+            current_pop = next_pop;
+            current_h = next_h;
+
+            if let (Some(first), Some(&h)) = (current_pop.first(), current_h.first()) {
+                observer.on_iteration(iter, first, h);
+                if best_h.is_none_or(|best_h| h < best_h) {
+                    best_h = Some(h);
+                    observer.on_improvement(iter, first, h);
+                }
+                if best.as_ref().is_none_or(|(_, best_h)| h < *best_h) {
+                    best = Some((first.clone(), h));
+                }
+                observer.on_statistics(&IterationStats {
+                    diversity: Some(diversity(&current_h)),
+                    ..IterationStats::new(iter, evaluated, best_h.unwrap_or(h), h)
+                });
+            }
+
+            if self.config.max_iter.is_some_and(|max| max < iter) {
+                return Ok(match (current_pop.into_iter().next(), current_h.first()) {
+                    (Some(state), Some(&h)) => AttemptResult::new(state, h, iter)
+                        .with_evaluated(evaluated)
+                        .with_outcome(AttemptOutcome::BudgetExhausted(Limit::Expansions)),
+                    _ => {
+                        let (state, h) = best.ok_or(Error::EmptyPopulation)?;
+                        AttemptResult::new(state, h, iter)
+                            .with_evaluated(evaluated)
+                            .with_outcome(AttemptOutcome::Failed)
+                    }
+                });
+            }
+
+            if current_pop.is_empty() {
+                let (state, h) = best.ok_or(Error::EmptyPopulation)?;
+                return Ok(AttemptResult::new(state, h, iter)
+                    .with_evaluated(evaluated)
+                    .with_outcome(AttemptOutcome::Failed));
             }
         }
     }
 }
 
-pub struct GeneticAlgorithm<R: Rng> {
-    rng: R,
-    k: usize,
-    max_iter: Option<usize>,
-    pmut: f64,
+/// How `GeneticAlgorithm` picks the parents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Selection {
+    /// Probability proportional to the fitness.
+    #[default]
+    Roulette,
+    /// Like `Roulette`, with evenly spaced pointers instead of independent spins.
+    StochasticUniversal,
+    /// The best of the given number of individuals drawn at random.
+    Tournament(usize),
+    /// Probability proportional to the rank, from 1 for the worst individual to k for the best one.
+    Rank,
+    /// Uniformly among the given number of best individuals.
+    Truncation(usize),
 }
 
-impl<R: Rng> GeneticAlgorithm<R> {
-    pub fn from_parts(rng: R, k: usize, max_iter: Option<usize>, pmut: f64) -> Self {
-        Self {
-            rng: rng,
-            k: k,
-            max_iter: max_iter,
-            pmut: pmut,
-        }
+impl Selection {
+    fn needs_fitness(&self) -> bool {
+        matches!(self, Selection::Roulette | Selection::StochasticUniversal)
     }
 }
 
-impl<R, P> ImprovingAlgorithm<P> for GeneticAlgorithm<R>
-where
-    R: Rng,
-    P: MutateGene + Utility<Cost: From<f64> + Into<f64>> + RandomState + Crossover,
-{
-    fn attempt(&mut self, problem: &P) -> AttemptResult<P> {
-        let mut current_pop = Vec::with_capacity(self.k);
-        let mut current_weights: Vec<f64> = Vec::with_capacity(self.k);
-        for _ in 0..self.k {
-            let state = problem.random_state(&mut self.rng);
-            let h = problem.heuristic(&state);
-            if h <= Default::default() {
-                return AttemptResult::new(state, h, 0);
-            }
-            current_pop.push(state);
-            current_weights.push(h.into().inv()); // TODO: aggiungi reverse
-        }
-        let mut iter = 0;
-        let mut distr =
-            WeightedIndex::new(&current_weights).expect("Failed to create WeightedIndex");
-        loop {
-            let mut new_pop = Vec::with_capacity(self.k);
-            let mut new_weights = Vec::with_capacity(self.k);
-            iter += 1;
-            while new_pop.len() < self.k {
-                let parent1 = &current_pop[distr.sample(&mut self.rng)];
-                let parent2 = &current_pop[distr.sample(&mut self.rng)];
+/// How the children enter the population.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Replacement {
+    /// Every generation the children, and the elite, replace the whole population.
+    #[default]
+    Generational,
+    /// Every generation the given number of children replace the worst individuals.
+    SteadyState(usize),
+}
+
+/// The fitness used by the roulette selections, higher is better.
+/// Closures `Fn(&Cost) -> f64` are fitness transforms too.
+pub trait FitnessTransform<C> {
+    fn fitness(&self, costs: &[C]) -> Vec<f64>;
+}
 
-                let mut child = problem.crossover(&mut self.rng, parent1, parent2);
+/// Fitness 1 / h, it needs costs convertible to `f64`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InverseFitness;
 
-                let r: f64 = self.rng.random();
+impl<C: Copy + Into<f64>> FitnessTransform<C> for InverseFitness {
+    fn fitness(&self, costs: &[C]) -> Vec<f64> {
+        costs.iter().map(|h| (*h).into().inv()).collect()
+    }
+}
 
-                if r <= self.pmut {
-                    problem.mutate_gene(&mut self.rng, &mut child);
-                }
+/// Fitness given by the rank, from 1 for the worst individual to k for the best one:
+/// it only needs costs that can be compared.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RankFitness;
 
-                let child_h = problem.heuristic(&child);
+impl<C: Ord> FitnessTransform<C> for RankFitness {
+    fn fitness(&self, costs: &[C]) -> Vec<f64> {
+        ranks(costs)
+    }
+}
 
-                if child_h <= Default::default() {
-                    return AttemptResult::new(child, child_h, iter);
-                }
+impl<C, F> FitnessTransform<C> for F
+where
+    F: Fn(&C) -> f64,
+{
+    fn fitness(&self, costs: &[C]) -> Vec<f64> {
+        costs.iter().map(self).collect()
+    }
+}
 
-                new_pop.push(child);
-                new_weights.push(child_h.into().inv());
-            }
+fn ranks<C: Ord>(costs: &[C]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..costs.len()).collect();
+    order.sort_by(|a, b| costs[*b].cmp(&costs[*a]));
+    let mut ranks = vec![0.0; costs.len()];
+    for (rank, i) in order.into_iter().enumerate() {
+        ranks[i] = (rank + 1) as f64;
+    }
+    ranks
+}
 
-            current_pop = new_pop;
-            current_weights = new_weights;
-            distr = WeightedIndex::new(&current_weights).unwrap();
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneticConfig {
+    /// Size of the population.
+    pub k: usize,
+    pub max_iter: Option<usize>,
+    /// Probability that a child is mutated.
+    pub pmut: f64,
+    pub selection: Selection,
+    /// Best individuals copied unchanged in the next generation, with the generational replacement.
+    pub elitism: usize,
+    pub replacement: Replacement,
+}
 
-            // Stop if max iterations reached
-            if self.max_iter.map_or(false, |max| max <= iter) {
-                let best_s = current_pop
-                    .into_iter()
-                    .zip(current_weights.into_iter())
-                    .min_by_key(|(_, h)| OrderedFloat(*h))
-                    .map(|(x, _)| x)
-                    .unwrap();
-                let best_h = problem.heuristic(&best_s);
+impl GeneticConfig {
+    /// Roulette selection and generational replacement without elitism.
+    pub fn new(k: usize, max_iter: Option<usize>, pmut: f64) -> Self {
+        Self {
+            k,
+            max_iter,
+            pmut,
+            selection: Selection::default(),
+            elitism: 0,
+            replacement: Replacement::default(),
+        }
+    }
+}
 
-                return AttemptResult::new(best_s, best_h, iter);
-            }
+pub struct GeneticAlgorithm<R: Rng, F = InverseFitness> {
+    rng: R,
+    checkpoint: Option<RngCheckpoint<R>>,
+    fitness: F,
+    config: GeneticConfig,
+    limits: SearchLimits,
+}
+
+impl<R: Rng> GeneticAlgorithm<R> {
+    pub fn from_parts(rng: R, k: usize, max_iter: Option<usize>, pmut: f64) -> Self {
+        Self::from_config(rng, GeneticConfig::new(k, max_iter, pmut))
+    }
+
+    pub fn from_config(rng: R, config: GeneticConfig) -> Self {
+        Self {
+            rng,
+            checkpoint: None,
+            fitness: InverseFitness,
+            config,
+            limits: SearchLimits::default(),
         }
     }
 }
+
+impl<R: Rng, F> GeneticAlgorithm<R, F> {
+    /// Replaces the fitness used by the roulette selections.
+    pub fn with_fitness<G>(self, fitness: G) -> GeneticAlgorithm<R, G> {
+        GeneticAlgorithm {
+            rng: self.rng,
+            checkpoint: self.checkpoint,
+            fitness,
+            config: self.config,
+            limits: self.limits,
+        }
+    }
+
+    pub fn config(&self) -> &GeneticConfig {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Saves the random generator in the `Progress` of a stopped attempt, resetting it to a seed
+    /// drawn from it, so that `resume` continues like the interrupted attempt whatever the
+    /// generator it is given.
+    pub fn with_rng_checkpoints(self) -> Self
+    where
+        R: SeedableRng,
+    {
+        Self {
+            checkpoint: Some(RngCheckpoint::new()),
+            ..self
+        }
+    }
+}
+
+impl<R: Rng, F> LimitSearch for GeneticAlgorithm<R, F> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+impl<R, F, P> ImprovingAlgorithm<P> for GeneticAlgorithm<R, F>
+where
+    R: Rng,
+    F: FitnessTransform<P::Cost>,
+    P: MutateGene + Utility + RandomState<State: Clone> + Crossover,
+{
+    fn population_size(&self) -> usize {
+        self.config.k
+    }
+
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut population = Vec::with_capacity(self.config.k);
+        for _ in 0..self.config.k {
+            population.push(problem.try_random_state(&mut self.rng)?);
+        }
+        self.try_resume_observed(problem, Progress::new(population), observer)
+    }
+
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        if self.config.replacement == Replacement::SteadyState(0) {
+            return Err(Error::InvalidState(
+                "the steady-state replacement needs at least one child per generation".into(),
+            ));
+        }
+        if self.config.replacement == Replacement::Generational
+            && self.config.elitism > 0
+            && self.config.elitism >= self.config.k
+        {
+            return Err(Error::InvalidState(format!(
+                "an elite of {} leaves no room for children in a population of {}",
+                self.config.elitism, self.config.k
+            )));
+        }
+        let mut iter = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        let mut best_h = progress.best_h;
+        restore(self.checkpoint, &mut self.rng, progress.seed);
+        let limits = self.limits.start().offset(iter, evaluated);
+        let mut current_pop = progress.population;
+        let mut current_costs: Vec<P::Cost> = Vec::with_capacity(current_pop.len());
+        for state in current_pop.iter() {
+            let h = problem.try_heuristic(state)?;
+            if iter == 0 {
+                evaluated += 1;
+            }
+            if h <= Default::default() {
+                observer.on_goal(iter, state, h);
+                return Ok(AttemptResult::new(state.clone(), h, iter).with_evaluated(evaluated));
+            }
+            current_costs.push(h);
+        }
+        loop {
+            if let Some(limit) = limits.check(iter, evaluated, 0) {
+                let (state, h) = Self::best_of(&current_pop, &current_costs)?;
+                let progress = Progress {
+                    iteration: iter,
+                    evaluated,
+                    best_h,
+                    seed: reseed(self.checkpoint, &mut self.rng),
+                    ..Progress::new(current_pop)
+                };
+                return Ok(AttemptResult::stopped(state, h, iter, limit)
+                    .with_evaluated(evaluated)
+                    .with_progress(progress));
+            }
+            iter += 1;
+
+            let k = current_pop.len();
+            let (elite, children) = match self.config.replacement {
+                Replacement::Generational => {
+                    let elite = self.config.elitism.min(k);
+                    (elite, k - elite)
+                }
+                Replacement::SteadyState(n) => (0, n.min(k)),
+            };
+            let parents = self.select(&current_costs, 2 * children)?;
+            let mut new_children = Vec::with_capacity(children);
+            let mut new_costs = Vec::with_capacity(children);
+            for pair in parents.chunks_exact(2) {
+                let mut child =
+                    problem.crossover(&mut self.rng, &current_pop[pair[0]], &current_pop[pair[1]]);
+
+                let r: f64 = self.rng.random();
+
+                if r <= self.config.pmut {
+                    child = problem.mutate_gene(&mut self.rng, &child);
+                }
+
+                let child_h = problem.try_heuristic(&child)?;
+                evaluated += 1;
+
+                if child_h <= Default::default() {
+                    observer.on_goal(iter, &child, child_h);
+                    return Ok(AttemptResult::new(child, child_h, iter).with_evaluated(evaluated));
+                }
+
+                new_children.push(child);
+                new_costs.push(child_h);
+            }
+
+            // the individuals that survive, from the best to the worst
+            let mut order: Vec<usize> = (0..k).collect();
+            order.sort_by_key(|i| current_costs[*i]);
+            let survivors = match self.config.replacement {
+                Replacement::Generational => elite,
+                Replacement::SteadyState(_) => k - children,
+            };
+            let mut survived = vec![false; k];
+            for i in order.into_iter().take(survivors) {
+                survived[i] = true;
+            }
+            let mut next_pop = Vec::with_capacity(k);
+            let mut next_costs = Vec::with_capacity(k);
+            for ((state, h), survived) in current_pop.into_iter().zip(current_costs).zip(survived) {
+                if survived {
+                    next_pop.push(state);
+                    next_costs.push(h);
+                }
+            }
+            next_pop.extend(new_children);
+            next_costs.extend(new_costs);
+            current_pop = next_pop;
+            current_costs = next_costs;
+
+            let (best, h) = current_costs
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, h)| **h)
+                .map(|(i, h)| (&current_pop[i], *h))
+                .ok_or(Error::EmptyPopulation)?;
+            observer.on_iteration(iter, best, h);
+            if best_h.is_none_or(|best_h| h < best_h) {
+                best_h = Some(h);
+                observer.on_improvement(iter, best, h);
+            }
+            observer.on_statistics(&IterationStats {
+                diversity: Some(diversity(&current_costs)),
+                ..IterationStats::new(iter, evaluated, best_h.unwrap_or(h), h)
+            });
+
+            // Stop if max iterations reached
+            if self.config.max_iter.is_some_and(|max| max <= iter) {
+                let (best_s, best_h) = Self::best_of(&current_pop, &current_costs)?;
+                return Ok(AttemptResult::new(best_s, best_h, iter)
+                    .with_evaluated(evaluated)
+                    .with_outcome(AttemptOutcome::BudgetExhausted(Limit::Expansions)));
+            }
+        }
+    }
+}
+
+impl<R: Rng, F> GeneticAlgorithm<R, F> {
+    /// The indices of `count` parents, two for every child.
+    fn select<C: Ord + Copy>(&mut self, costs: &[C], count: usize) -> Result<Vec<usize>>
+    where
+        F: FitnessTransform<C>,
+    {
+        let fitness = if self.config.selection.needs_fitness() {
+            self.fitness.fitness(costs)
+        } else if costs.is_empty() {
+            return Err(Error::EmptyPopulation);
+        } else {
+            Vec::new()
+        };
+        let rng = &mut self.rng;
+        Ok(match self.config.selection {
+            Selection::Roulette => {
+                let distr = Self::distribution(&fitness)?;
+                (0..count).map(|_| distr.sample(rng)).collect()
+            }
+            Selection::StochasticUniversal => {
+                // checks the weights like the roulette does
+                Self::distribution(&fitness)?;
+                let total: f64 = fitness.iter().sum();
+                let step = total / count.max(1) as f64;
+                let mut pointer = rng.random::<f64>() * step;
+                let mut cumulative = 0.0;
+                let mut parents = Vec::with_capacity(count);
+                for (i, f) in fitness.iter().enumerate() {
+                    cumulative += f;
+                    while parents.len() < count && pointer < cumulative {
+                        parents.push(i);
+                        pointer += step;
+                    }
+                }
+                // rounding errors can leave the last pointers out
+                parents.resize(count, fitness.len() - 1);
+                parents.shuffle(rng);
+                parents
+            }
+            Selection::Tournament(size) => (0..count)
+                .map(|_| {
+                    (0..size.max(1))
+                        .map(|_| rng.random_range(0..costs.len()))
+                        .min_by_key(|i| costs[*i])
+                        .unwrap_or_default()
+                })
+                .collect(),
+            Selection::Rank => {
+                let distr = Self::distribution(&ranks(costs))?;
+                (0..count).map(|_| distr.sample(rng)).collect()
+            }
+            Selection::Truncation(n) => {
+                let mut order: Vec<usize> = (0..costs.len()).collect();
+                order.sort_by_key(|i| costs[*i]);
+                let n = n.clamp(1, costs.len());
+                (0..count).map(|_| order[rng.random_range(0..n)]).collect()
+            }
+        })
+    }
+
+    fn distribution(weights: &[f64]) -> Result<WeightedIndex<f64>> {
+        WeightedIndex::new(weights).map_err(|e| Error::InvalidWeights(e.to_string()))
+    }
+
+    fn best_of<S: Clone, C: Ord + Copy>(pop: &[S], costs: &[C]) -> Result<(S, C)> {
+        costs
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, h)| **h)
+            .map(|(i, h)| (pop[i].clone(), *h))
+            .ok_or(Error::EmptyPopulation)
+    }
+}
+
+/// The attributes of the moves of `TabuSearch` from `state` to `next` with `perturbation`.
+/// Closures `Fn(&State, &Perturbation, &State) -> K` are keys too.
+pub trait TabuKey<P>
+where
+    P: StatePerturbation,
+{
+    type Key: Eq + Hash;
+
+    /// A candidate move is tabu if its key is in the tabu list.
+    fn key(&self, state: &P::State, perturbation: &P::Perturbation, next: &P::State) -> Self::Key;
+
+    /// Added to the tabu list when the move is made.
+    fn recorded(
+        &self,
+        state: &P::State,
+        perturbation: &P::Perturbation,
+        next: &P::State,
+    ) -> Self::Key {
+        self.key(state, perturbation, next)
+    }
+}
+
+/// Makes the perturbation itself tabu.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PerturbationKey;
+
+impl<P> TabuKey<P> for PerturbationKey
+where
+    P: StatePerturbation<Perturbation: Clone + Eq + Hash>,
+{
+    type Key = P::Perturbation;
+
+    fn key(&self, _: &P::State, perturbation: &P::Perturbation, _: &P::State) -> Self::Key {
+        perturbation.clone()
+    }
+}
+
+/// Makes the states left recently tabu.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StateKey;
+
+impl<P> TabuKey<P> for StateKey
+where
+    P: StatePerturbation<State: Clone + Eq + Hash>,
+{
+    type Key = P::State;
+
+    fn key(&self, _: &P::State, _: &P::Perturbation, next: &P::State) -> Self::Key {
+        next.clone()
+    }
+
+    fn recorded(&self, state: &P::State, _: &P::Perturbation, _: &P::State) -> Self::Key {
+        state.clone()
+    }
+}
+
+impl<P, F, K> TabuKey<P> for F
+where
+    P: StatePerturbation,
+    F: Fn(&P::State, &P::Perturbation, &P::State) -> K,
+    K: Eq + Hash,
+{
+    type Key = K;
+
+    fn key(&self, state: &P::State, perturbation: &P::Perturbation, next: &P::State) -> K {
+        self(state, perturbation, next)
+    }
+}
+
+/// When a tabu move is allowed anyway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Aspiration {
+    Never,
+    /// The move leads to a state better than every state found so far.
+    #[default]
+    BestSoFar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabuConfig {
+    /// Number of iterations a key stays in the tabu list.
+    pub tenure: usize,
+    pub max_iter: usize,
+    pub aspiration: Aspiration,
+}
+
+/// Moves every iteration to the best neighbour whose key is not tabu, even if it is worse
+/// than the current state, and returns the best state found.
+pub struct TabuSearch<R: Rng, K> {
+    rng: R,
+    checkpoint: Option<RngCheckpoint<R>>,
+    key: K,
+    config: TabuConfig,
+    limits: SearchLimits,
+}
+
+impl<R: Rng, K> TabuSearch<R, K> {
+    pub fn from_parts(rng: R, key: K, tenure: usize, max_iter: usize) -> Self {
+        let config = TabuConfig {
+            tenure,
+            max_iter,
+            aspiration: Aspiration::default(),
+        };
+        Self::from_config(rng, key, config)
+    }
+
+    pub fn from_config(rng: R, key: K, config: TabuConfig) -> Self {
+        Self {
+            rng,
+            checkpoint: None,
+            key,
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &TabuConfig {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Saves the random generator in the `Progress` of a stopped attempt, resetting it to a seed
+    /// drawn from it, so that `resume` continues like the interrupted attempt whatever the
+    /// generator it is given.
+    pub fn with_rng_checkpoints(self) -> Self
+    where
+        R: SeedableRng,
+    {
+        Self {
+            checkpoint: Some(RngCheckpoint::new()),
+            ..self
+        }
+    }
+}
+
+impl<R: Rng, K> LimitSearch for TabuSearch<R, K> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+/// The keys made tabu in the last `tenure` iterations, each one with the move that recorded it.
+struct TabuList<K, M> {
+    order: VecDeque<(K, M)>,
+    count: HashMap<K, usize>,
+    tenure: usize,
+}
+
+impl<K: Eq + Hash + Clone, M> TabuList<K, M> {
+    fn new(tenure: usize) -> Self {
+        Self {
+            order: VecDeque::with_capacity(tenure + 1),
+            count: HashMap::new(),
+            tenure,
+        }
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.count.contains_key(key)
+    }
+
+    fn push(&mut self, key: K, made: M) {
+        if self.tenure == 0 {
+            return;
+        }
+        *self.count.entry(key.clone()).or_insert(0) += 1;
+        self.order.push_back((key, made));
+        if self.order.len() > self.tenure {
+            let (old, _) = self.order.pop_front().unwrap();
+            if let Some(n) = self.count.get_mut(&old) {
+                *n -= 1;
+                if *n == 0 {
+                    self.count.remove(&old);
+                }
+            }
+        }
+    }
+
+    fn into_moves(self) -> Vec<M> {
+        self.order.into_iter().map(|(_, made)| made).collect()
+    }
+}
+
+impl<R, P, K> ImprovingAlgorithm<P> for TabuSearch<R, K>
+where
+    R: Rng,
+    P: StatePerturbation + Utility + RandomState<State: Clone>,
+    K: TabuKey<P, Key: Clone>,
+{
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let progress = Progress::new(vec![problem.try_random_state(&mut self.rng)?]);
+        self.try_resume_observed(problem, progress, observer)
+    }
+
+    /// The population holds the current state followed by the best one.
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut iter = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        let limits = self.limits.start().offset(iter, evaluated);
+        let mut population = progress.population.into_iter();
+        let mut curr_state = population.next().ok_or(Error::EmptyPopulation)?;
+        let mut curr_h = problem.try_heuristic(&curr_state)?;
+        let (mut best_state, mut best_h) = match population.next() {
+            Some(state) => {
+                let h = problem.try_heuristic(&state)?;
+                (state, h)
+            }
+            None => (curr_state.clone(), curr_h),
+        };
+        if iter == 0 {
+            evaluated += 1;
+        }
+        restore(self.checkpoint, &mut self.rng, progress.seed);
+        let mut tabu = TabuList::new(self.config.tenure);
+        for (state, index) in progress.tabu {
+            let a = problem.perturbations(&state).nth(index).ok_or_else(|| {
+                Error::InvalidState(format!("tabu move {} is not a perturbation", index))
+            })?;
+            let next = problem.perturb(&state, &a);
+            tabu.push(self.key.recorded(&state, &a, &next), (state, index));
+        }
+
+        loop {
+            if best_h <= Default::default() {
+                observer.on_goal(iter, &best_state, best_h);
+                return Ok(AttemptResult::new(best_state, best_h, iter).with_evaluated(evaluated));
+            }
+            if let Some(limit) = limits.check(iter, evaluated, 0) {
+                let progress = Progress {
+                    iteration: iter,
+                    evaluated,
+                    best_h: Some(best_h),
+                    seed: reseed(self.checkpoint, &mut self.rng),
+                    tabu: tabu.into_moves(),
+                    ..Progress::new(vec![curr_state, best_state.clone()])
+                };
+                return Ok(AttemptResult::stopped(best_state, best_h, iter, limit)
+                    .with_evaluated(evaluated)
+                    .with_progress(progress));
+            }
+            if iter >= self.config.max_iter {
+                return Ok(AttemptResult::new(best_state, best_h, iter)
+                    .with_evaluated(evaluated)
+                    .with_outcome(AttemptOutcome::BudgetExhausted(Limit::Expansions)));
+            }
+            iter += 1;
+            observer.on_iteration(iter, &curr_state, curr_h);
+            observer.on_statistics(&IterationStats::new(iter, evaluated, best_h, curr_h));
+
+            // best admissible neighbour, ties broken uniformly at random
+            let mut next: Option<(P::State, P::Cost, P::Perturbation)> = None;
+            let mut next_index = 0;
+            let mut ties = 0;
+            for (index, a) in problem.perturbations(&curr_state).enumerate() {
+                let (h, state) = evaluate(problem, &curr_state, curr_h, &a)?;
+                evaluated += 1;
+                // with delta evaluation the neighbours worse than the candidate are not even built
+                if next.as_ref().is_some_and(|(_, next_h, _)| h > *next_h) {
+                    continue;
+                }
+                let state = state.unwrap_or_else(|| problem.perturb(&curr_state, &a));
+                let aspiration = match self.config.aspiration {
+                    Aspiration::Never => false,
+                    Aspiration::BestSoFar => h < best_h,
+                };
+                if !aspiration && tabu.contains(&self.key.key(&curr_state, &a, &state)) {
+                    continue;
+                }
+                match next.as_ref().map(|(_, next_h, _)| h.cmp(next_h)) {
+                    Some(std::cmp::Ordering::Greater) => continue,
+                    Some(std::cmp::Ordering::Equal) => {
+                        ties += 1;
+                        if !self.rng.random_ratio(1, ties) {
+                            continue;
+                        }
+                    }
+                    _ => ties = 1,
+                }
+                next = Some((state, h, a));
+                next_index = index;
+            }
+
+            let Some((state, h, a)) = next else {
+                // every move is tabu
+                return Ok(AttemptResult::new(best_state, best_h, iter).with_evaluated(evaluated));
+            };
+            let key = self.key.recorded(&curr_state, &a, &state);
+            let left = std::mem::replace(&mut curr_state, state);
+            tabu.push(key, (left, next_index));
+            curr_h = h;
+            if curr_h < best_h {
+                best_state = curr_state.clone();
+                best_h = curr_h;
+                observer.on_improvement(iter, &best_state, best_h);
+            }
+        }
+    }
+}
+
+/// Which local optimum `IteratedLocalSearch` continues from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Acceptance {
+    /// The new local optimum only if it is better than the current one.
+    #[default]
+    Better,
+    BetterOrEqual,
+    /// Always the new local optimum, a random walk among local optima.
+    Always,
+}
+
+impl Acceptance {
+    fn accepts<C: Ord>(&self, new_h: C, curr_h: C) -> bool {
+        match self {
+            Acceptance::Better => new_h < curr_h,
+            Acceptance::BetterOrEqual => new_h <= curr_h,
+            Acceptance::Always => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IlsConfig {
+    /// Random perturbations applied to the local optimum by every kick.
+    pub kick: usize,
+    pub max_iter: usize,
+    pub acceptance: Acceptance,
+}
+
+/// Iterated local search: the local optimum found by `local` is kicked with random perturbations
+/// and optimized again, the new optimum replaces the current one according to the acceptance criterion.
+///
+/// Every iteration runs a whole attempt of `local`, whose evaluated states count for the limits.
+/// The limits of `local` are its own.
+pub struct IteratedLocalSearch<R: Rng, L> {
+    rng: R,
+    checkpoint: Option<RngCheckpoint<R>>,
+    local: L,
+    config: IlsConfig,
+    limits: SearchLimits,
+}
+
+impl<R: Rng, L> IteratedLocalSearch<R, L> {
+    pub fn from_parts(rng: R, local: L, kick: usize, max_iter: usize) -> Self {
+        let config = IlsConfig {
+            kick,
+            max_iter,
+            acceptance: Acceptance::default(),
+        };
+        Self::from_config(rng, local, config)
+    }
+
+    pub fn from_config(rng: R, local: L, config: IlsConfig) -> Self {
+        Self {
+            rng,
+            checkpoint: None,
+            local,
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &IlsConfig {
+        &self.config
+    }
+
+    pub fn local(&self) -> &L {
+        &self.local
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Saves the random generator in the `Progress` of a stopped attempt, resetting it to a seed
+    /// drawn from it, so that `resume` continues like the interrupted attempt whatever the
+    /// generator it is given.
+    pub fn with_rng_checkpoints(self) -> Self
+    where
+        R: SeedableRng,
+    {
+        Self {
+            checkpoint: Some(RngCheckpoint::new()),
+            ..self
+        }
+    }
+}
+
+impl<R: Rng, L> LimitSearch for IteratedLocalSearch<R, L> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+impl<R: Rng, L> IteratedLocalSearch<R, L> {
+    fn try_start<P>(
+        &mut self,
+        problem: &P,
+        start: P::State,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>>
+    where
+        L: ImprovingAlgorithm<P>,
+        P: RandomPerturbation + Utility + RandomState<State: Clone>,
+    {
+        let local =
+            self.local
+                .try_resume_observed(problem, Progress::new(vec![start]), &mut NoObserver)?;
+        let progress = Progress {
+            evaluated: local.evaluated,
+            ..Progress::new(vec![local.state])
+        };
+        self.try_resume_observed(problem, progress, observer)
+    }
+}
+
+impl<R, L, P> ImprovingAlgorithm<P> for IteratedLocalSearch<R, L>
+where
+    R: Rng,
+    L: ImprovingAlgorithm<P>,
+    P: RandomPerturbation + Utility + RandomState<State: Clone>,
+{
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let start = problem.try_random_state(&mut self.rng)?;
+        self.try_start(problem, start, observer)
+    }
+
+    /// The generated state goes through `local` first, like the random one.
+    fn try_attempt_from(
+        &mut self,
+        problem: &P,
+        generator: &mut dyn InitialStateGenerator<P>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let start = generator.generate(problem)?;
+        self.try_start(problem, start, observer)
+    }
+
+    /// The population holds the current local optimum followed by the best state found.
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut iter = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        restore(self.checkpoint, &mut self.rng, progress.seed);
+        let limits = self.limits.start().offset(iter, evaluated);
+        let mut population = progress.population.into_iter();
+        let mut curr_state = population.next().ok_or(Error::EmptyPopulation)?;
+        let mut curr_h = problem.try_heuristic(&curr_state)?;
+        let (mut best_state, mut best_h) = match population.next() {
+            Some(state) => {
+                let h = problem.try_heuristic(&state)?;
+                (state, h)
+            }
+            None => (curr_state.clone(), curr_h),
+        };
+
+        loop {
+            if best_h <= Default::default() {
+                observer.on_goal(iter, &best_state, best_h);
+                return Ok(AttemptResult::new(best_state, best_h, iter).with_evaluated(evaluated));
+            }
+            if let Some(limit) = limits.check(iter, evaluated, 0) {
+                let progress = Progress {
+                    iteration: iter,
+                    evaluated,
+                    best_h: Some(best_h),
+                    seed: reseed(self.checkpoint, &mut self.rng),
+                    ..Progress::new(vec![curr_state, best_state.clone()])
+                };
+                return Ok(AttemptResult::stopped(best_state, best_h, iter, limit)
+                    .with_evaluated(evaluated)
+                    .with_progress(progress));
+            }
+            if iter >= self.config.max_iter {
+                return Ok(AttemptResult::new(best_state, best_h, iter)
+                    .with_evaluated(evaluated)
+                    .with_outcome(AttemptOutcome::BudgetExhausted(Limit::Expansions)));
+            }
+            iter += 1;
+            observer.on_iteration(iter, &curr_state, curr_h);
+            observer.on_statistics(&IterationStats::new(iter, evaluated, best_h, curr_h));
+
+            let mut kicked = curr_state.clone();
+            for _ in 0..self.config.kick {
+                if let Some(p) = problem.random_pertubation(&mut self.rng, &kicked) {
+                    kicked = problem.perturb(&kicked, &p);
+                }
+            }
+            let local = self.local.try_resume_observed(
+                problem,
+                Progress::new(vec![kicked]),
+                &mut NoObserver,
+            )?;
+            evaluated += local.evaluated;
+
+            if self.config.acceptance.accepts(local.h, curr_h) {
+                curr_state = local.state;
+                curr_h = local.h;
+                if curr_h < best_h {
+                    best_state = curr_state.clone();
+                    best_h = curr_h;
+                    observer.on_improvement(iter, &best_state, best_h);
+                }
+            } else if local.h < best_h {
+                // possible only with an acceptance that rejects improvements over the current state
+                best_state = local.state;
+                best_h = local.h;
+                observer.on_improvement(iter, &best_state, best_h);
+            }
+        }
+    }
+}
+
+/// GRASP: every attempt builds a state with a `GreedyConstruction` from `InitState`, then
+/// improves it with `local`. `alpha` is the one of the construction: 0 is purely greedy, 1 purely random.
+///
+/// Run it with `Resolver::resolve_restart` to repeat the attempts. The limits are the ones of `local`.
+pub struct Grasp<R: Rng, L> {
+    construction: GreedyConstruction<R>,
+    local: L,
+}
+
+impl<R: Rng, L> Grasp<R, L> {
+    pub fn new(rng: R, local: L, alpha: f64) -> Self {
+        Self {
+            construction: GreedyConstruction::new(rng, alpha),
+            local,
+        }
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.construction.alpha()
+    }
+
+    pub fn local(&self) -> &L {
+        &self.local
+    }
+
+    pub fn rng(&self) -> &R {
+        self.construction.rng()
+    }
+
+    pub fn construct<P>(&mut self, problem: &P) -> P::State
+    where
+        P: Utility<Cost: Into<f64>> + SuitableState + InitState,
+    {
+        self.try_construct(problem)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// A suitable state built with the restricted candidate list, starting again when no action is executable.
+    pub fn try_construct<P>(&mut self, problem: &P) -> Result<P::State>
+    where
+        P: Utility<Cost: Into<f64>> + SuitableState + InitState,
+    {
+        self.construction.generate(problem)
+    }
+}
+
+impl<R: Rng, L: LimitSearch> LimitSearch for Grasp<R, L> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.local.set_limits(limits);
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        self.local.limits()
+    }
+}
+
+impl<R, L, P> ImprovingAlgorithm<P> for Grasp<R, L>
+where
+    R: Rng,
+    L: ImprovingAlgorithm<P>,
+    P: Utility<Cost: Into<f64>> + SuitableState + InitState,
+{
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let start = self.try_construct(problem)?;
+        self.local
+            .try_resume_observed(problem, Progress::new(vec![start]), observer)
+    }
+
+    /// Resumes the local search of the attempt.
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        self.local.try_resume_observed(problem, progress, observer)
+    }
+}
+
+/// How `VariableNeighborhoodSearch` optimizes the state after shaking it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Descent {
+    /// Steepest descent in a single neighbourhood (basic VNS).
+    Neighborhood(usize),
+    /// Variable neighbourhood descent: steepest descent in the first neighbourhood that
+    /// improves the state, starting again from the first one after every improvement (general VNS).
+    Variable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VnsConfig {
+    pub max_iter: usize,
+    pub descent: Descent,
+}
+
+/// Variable neighbourhood search: the state is shaken with a random move of the k-th neighbourhood
+/// and optimized again, k goes back to 0 when the result is better and moves to the next
+/// neighbourhood otherwise.
+pub struct VariableNeighborhoodSearch<R: Rng> {
+    rng: R,
+    checkpoint: Option<RngCheckpoint<R>>,
+    config: VnsConfig,
+    limits: SearchLimits,
+}
+
+impl<R: Rng> VariableNeighborhoodSearch<R> {
+    pub fn new(rng: R, max_iter: usize) -> Self {
+        Self::from_config(
+            rng,
+            VnsConfig {
+                max_iter,
+                descent: Descent::Variable,
+            },
+        )
+    }
+
+    pub fn from_config(rng: R, config: VnsConfig) -> Self {
+        Self {
+            rng,
+            checkpoint: None,
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &VnsConfig {
+        &self.config
+    }
+
+    /// Saves the random generator in the `Progress` of a stopped attempt, resetting it to a seed
+    /// drawn from it, so that `resume` continues like the interrupted attempt whatever the
+    /// generator it is given.
+    pub fn with_rng_checkpoints(self) -> Self
+    where
+        R: SeedableRng,
+    {
+        Self {
+            checkpoint: Some(RngCheckpoint::new()),
+            ..self
+        }
+    }
+
+    fn steepest<P>(
+        problem: &P,
+        k: usize,
+        state: &P::State,
+        h: P::Cost,
+        evaluated: &mut usize,
+    ) -> Result<Option<(P::State, P::Cost)>>
+    where
+        P: Neighborhoods + Utility,
+    {
+        let mut best: Option<(P::State, P::Cost)> = None;
+        for m in problem.neighbors(k, state) {
+            let next = problem.apply(state, &m);
+            let next_h = problem.try_heuristic(&next)?;
+            *evaluated += 1;
+            if next_h < best.as_ref().map_or(h, |(_, best_h)| *best_h) {
+                best = Some((next, next_h));
+            }
+        }
+        Ok(best)
+    }
+
+    fn descend<P>(
+        &self,
+        problem: &P,
+        mut state: P::State,
+        mut h: P::Cost,
+        evaluated: &mut usize,
+    ) -> Result<(P::State, P::Cost)>
+    where
+        P: Neighborhoods + Utility,
+    {
+        let ks = match self.config.descent {
+            Descent::Neighborhood(k) => k..k + 1,
+            Descent::Variable => 0..problem.neighborhoods(),
+        };
+        let mut k = ks.start;
+        while k < ks.end {
+            match Self::steepest(problem, k, &state, h, evaluated)? {
+                Some((next, next_h)) => {
+                    state = next;
+                    h = next_h;
+                    k = ks.start;
+                }
+                None => k += 1,
+            }
+        }
+        Ok((state, h))
+    }
+}
+
+impl<R, P> ImprovingAlgorithm<P> for VariableNeighborhoodSearch<R>
+where
+    R: Rng,
+    P: Neighborhoods + Utility + RandomState<State: Clone>,
+{
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let progress = Progress::new(vec![problem.try_random_state(&mut self.rng)?]);
+        self.try_resume_observed(problem, progress, observer)
+    }
+
+    /// The resumed search starts again from the first neighbourhood.
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        if let Descent::Neighborhood(k) = self.config.descent {
+            if k >= problem.neighborhoods() {
+                return Err(Error::InvalidState(format!(
+                    "the problem has no neighbourhood {}, only {}",
+                    k,
+                    problem.neighborhoods()
+                )));
+            }
+        }
+        let mut iter = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        restore(self.checkpoint, &mut self.rng, progress.seed);
+        let limits = self.limits.start().offset(iter, evaluated);
+        let mut curr_state = current_state(progress)?;
+        let mut curr_h = problem.try_heuristic(&curr_state)?;
+        if iter == 0 {
+            evaluated += 1;
+            (curr_state, curr_h) = self.descend(problem, curr_state, curr_h, &mut evaluated)?;
+        }
+        let mut k = 0;
+
+        loop {
+            if curr_h <= Default::default() {
+                observer.on_goal(iter, &curr_state, curr_h);
+                return Ok(AttemptResult::new(curr_state, curr_h, iter).with_evaluated(evaluated));
+            }
+            if let Some(limit) = limits.check(iter, evaluated, 0) {
+                let progress = Progress {
+                    iteration: iter,
+                    evaluated,
+                    seed: reseed(self.checkpoint, &mut self.rng),
+                    ..Progress::new(vec![curr_state.clone()])
+                };
+                return Ok(AttemptResult::stopped(curr_state, curr_h, iter, limit)
+                    .with_evaluated(evaluated)
+                    .with_progress(progress));
+            }
+            if iter >= self.config.max_iter {
+                return Ok(AttemptResult::new(curr_state, curr_h, iter)
+                    .with_evaluated(evaluated)
+                    .with_outcome(AttemptOutcome::BudgetExhausted(Limit::Expansions)));
+            }
+            iter += 1;
+            observer.on_iteration(iter, &curr_state, curr_h);
+            observer.on_statistics(&IterationStats::new(iter, evaluated, curr_h, curr_h));
+
+            let shaken = match problem.random_neighbor(&mut self.rng, k, &curr_state) {
+                Some(m) => problem.apply(&curr_state, &m),
+                None => curr_state.clone(),
+            };
+            let shaken_h = problem.try_heuristic(&shaken)?;
+            evaluated += 1;
+            let (next, next_h) = self.descend(problem, shaken, shaken_h, &mut evaluated)?;
+            if next_h < curr_h {
+                curr_state = next;
+                curr_h = next_h;
+                k = 0;
+                observer.on_improvement(iter, &curr_state, curr_h);
+            } else {
+                k = (k + 1) % problem.neighborhoods().max(1);
+            }
+        }
+    }
+}
+
+macro_rules! impl_limit_search {
+    ($($algo:ident),*) => {
+        $(
+            impl<R: Rng> $algo<R> {
+                /// The random generator, whose state can be saved together with a `Progress`.
+                pub fn rng(&self) -> &R {
+                    &self.rng
+                }
+            }
+
+            impl<R: Rng> LimitSearch for $algo<R> {
+                fn set_limits(&mut self, limits: SearchLimits) {
+                    self.limits = limits;
+                }
+
+                fn limits(&self) -> &SearchLimits {
+                    &self.limits
+                }
+            }
+        )*
+    };
+}
+
+impl_limit_search!(
+    SteepestDescend,
+    HillClimbing,
+    LocalBeam,
+    VariableNeighborhoodSearch
+);
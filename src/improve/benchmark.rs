@@ -0,0 +1,730 @@
+use std::{
+    io::{self, Write},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use libm::{erfc, sqrt};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::error::{Error, Result};
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::observer::SearchObserver;
+use crate::problem::{SuitableState, Utility};
+
+use super::algorithms::ImprovingAlgorithm;
+use super::resolver::{merge_restart, AttemptOutcome, AttemptResult, GoalCheck};
+
+/// Builds the algorithm of a run from the RNG seeded for it.
+pub type BenchmarkFactory<'f, P> = Box<dyn Fn(StdRng) -> Box<dyn ImprovingAlgorithm<P> + 'f> + 'f>;
+
+struct Candidate<'f, P: Utility> {
+    algorithm: String,
+    params: String,
+    factory: BenchmarkFactory<'f, P>,
+}
+
+/// Runs every algorithm, with every parameter set, on every instance once for every seed, one run
+/// after the other so that the times are comparable. A run is successful when it reaches a goal,
+/// or a state with h at most the target when one is given, and has not failed.
+pub struct Benchmark<'f, P>
+where
+    P: Utility,
+{
+    instances: Vec<(String, &'f P)>,
+    candidates: Vec<Candidate<'f, P>>,
+    seeds: Vec<u64>,
+    restarts: usize,
+    target: Option<P::Cost>,
+    confidence: f64,
+    limits: SearchLimits,
+    goal_check: Option<GoalCheck<P>>,
+}
+
+impl<'f, P> Benchmark<'f, P>
+where
+    P: Utility<Cost: Into<f64>>,
+{
+    pub fn new(seeds: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            instances: Vec::new(),
+            candidates: Vec::new(),
+            seeds: seeds.into_iter().collect(),
+            restarts: 1,
+            target: None,
+            confidence: 0.95,
+            limits: SearchLimits::default(),
+            goal_check: None,
+        }
+    }
+
+    /// Verifies the goals as in `Resolver::verified`: a run ending in a state with h = 0 that is not
+    /// suitable fails.
+    pub fn verified(mut self) -> Self
+    where
+        P: SuitableState,
+    {
+        self.goal_check = Some(P::is_suitable);
+        self
+    }
+
+    pub fn with_instance(mut self, name: impl Into<String>, problem: &'f P) -> Self {
+        self.instances.push((name.into(), problem));
+        self
+    }
+
+    /// `params` names the parameter set the factory builds the algorithm with.
+    pub fn with_algorithm<A, F>(
+        mut self,
+        name: impl Into<String>,
+        params: impl Into<String>,
+        factory: F,
+    ) -> Self
+    where
+        A: ImprovingAlgorithm<P> + 'f,
+        F: Fn(StdRng) -> A + 'f,
+    {
+        self.candidates.push(Candidate {
+            algorithm: name.into(),
+            params: params.into(),
+            factory: Box::new(move |rng| {
+                Box::new(factory(rng)) as Box<dyn ImprovingAlgorithm<P> + 'f>
+            }),
+        });
+        self
+    }
+
+    /// The same algorithm once for every named parameter set.
+    pub fn with_parameters<T, A, F>(
+        mut self,
+        name: impl Into<String>,
+        sets: impl IntoIterator<Item = (impl Into<String>, T)>,
+        factory: F,
+    ) -> Self
+    where
+        T: 'f,
+        A: ImprovingAlgorithm<P> + 'f,
+        F: Fn(&T, StdRng) -> A + 'f,
+    {
+        let name = name.into();
+        let factory = Rc::new(factory);
+        for (params, value) in sets {
+            let factory = factory.clone();
+            self = self.with_algorithm(name.clone(), params, move |rng| factory(&value, rng));
+        }
+        self
+    }
+
+    /// Attempts of every run, like `Resolver::resolve_restart`.
+    pub fn with_restarts(mut self, restarts: usize) -> Self {
+        self.restarts = restarts.max(1);
+        self
+    }
+
+    pub fn with_target(mut self, target: P::Cost) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Level of the confidence intervals of the report, 0.95 by default.
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    pub fn run(&self) -> BenchmarkReport {
+        self.try_run().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_run(&self) -> Result<BenchmarkReport> {
+        if self.instances.is_empty() {
+            return Err(Error::Invariant("the benchmark has no instance"));
+        }
+        if self.candidates.is_empty() {
+            return Err(Error::Invariant("the benchmark has no algorithm"));
+        }
+        if !(self.confidence > 0.0 && self.confidence < 1.0) {
+            return Err(Error::InvalidState(format!(
+                "the confidence level {} is not in (0, 1)",
+                self.confidence
+            )));
+        }
+        let mut runs = Vec::new();
+        for (instance, problem) in &self.instances {
+            for candidate in &self.candidates {
+                for seed in &self.seeds {
+                    let mut algo = (candidate.factory)(StdRng::seed_from_u64(*seed));
+                    algo.set_limits(self.limits.clone());
+                    let mut observer = TargetObserver::new(self.target);
+                    let mut result = algo
+                        .try_attempt_observed(*problem, &mut observer)?
+                        .verify_with(*problem, self.goal_check);
+                    let mut last_limit = result.limit;
+                    for _ in 1..self.restarts {
+                        if self.reached(&result)
+                            || matches!(last_limit, Some(Limit::Time | Limit::Cancelled))
+                        {
+                            break;
+                        }
+                        let new_result = algo
+                            .try_attempt_observed(*problem, &mut observer)?
+                            .verify_with(*problem, self.goal_check);
+                        last_limit = new_result.limit;
+                        merge_restart(&mut result, new_result);
+                    }
+                    let duration = observer.start.elapsed();
+                    let success = self.reached(&result);
+                    runs.push(Run {
+                        instance: instance.clone(),
+                        algorithm: candidate.algorithm.clone(),
+                        params: candidate.params.clone(),
+                        seed: *seed,
+                        success,
+                        h: result.h.into(),
+                        iterations: result.iterations,
+                        duration,
+                        time_to_target: observer.reached.filter(|_| success),
+                    });
+                }
+            }
+        }
+        Ok(BenchmarkReport {
+            runs,
+            confidence: self.confidence,
+        })
+    }
+
+    fn reached(&self, result: &AttemptResult<P>) -> bool {
+        result.outcome != AttemptOutcome::Failed
+            && self
+                .target
+                .map_or(result.is_goal(), |target| result.h <= target)
+    }
+}
+
+/// The limits of every attempt, in place of the ones set by the factories.
+impl<P> LimitSearch for Benchmark<'_, P>
+where
+    P: Utility,
+{
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+/// The time of the first goal, or of the first state with h at most the target.
+struct TargetObserver<Cost> {
+    start: Instant,
+    target: Option<Cost>,
+    reached: Option<Duration>,
+}
+
+impl<Cost: Ord + Copy> TargetObserver<Cost> {
+    fn new(target: Option<Cost>) -> Self {
+        Self {
+            start: Instant::now(),
+            target,
+            reached: None,
+        }
+    }
+
+    fn check(&mut self, h: Cost) {
+        if self.reached.is_none() && self.target.is_some_and(|target| h <= target) {
+            self.reached = Some(self.start.elapsed());
+        }
+    }
+}
+
+impl<P> SearchObserver<P> for TargetObserver<P::Cost>
+where
+    P: Utility,
+{
+    fn on_goal(&mut self, _iteration: usize, _state: &P::State, _cost: P::Cost) {
+        self.reached.get_or_insert(self.start.elapsed());
+    }
+
+    fn on_iteration(&mut self, _iteration: usize, _state: &P::State, h: P::Cost) {
+        self.check(h);
+    }
+
+    fn on_improvement(&mut self, _iteration: usize, _state: &P::State, h: P::Cost) {
+        self.check(h);
+    }
+}
+
+/// A single run of the grid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Run {
+    pub instance: String,
+    pub algorithm: String,
+    pub params: String,
+    pub seed: u64,
+    pub success: bool,
+    /// h of the best state found.
+    pub h: f64,
+    /// Iterations of all the attempts.
+    pub iterations: usize,
+    pub duration: Duration,
+    /// `None` for the unsuccessful runs.
+    pub time_to_target: Option<Duration>,
+}
+
+/// Location and spread of a sample, `ci` is the confidence interval of the mean (normal approximation).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Distribution {
+    pub mean: f64,
+    /// Sample standard deviation, 0 with a single value.
+    pub std_dev: f64,
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+    pub ci: (f64, f64),
+}
+
+impl Distribution {
+    /// `None` for an empty sample.
+    pub fn of(values: &[f64], confidence: f64) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let n = values.len() as f64;
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mean = values.iter().sum::<f64>() / n;
+        let std_dev = if values.len() > 1 {
+            sqrt(values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0))
+        } else {
+            0.0
+        };
+        let half = normal_quantile((1.0 + confidence) / 2.0) * std_dev / sqrt(n);
+        Some(Self {
+            mean,
+            std_dev,
+            min: sorted[0],
+            median: median(&sorted),
+            max: sorted[sorted.len() - 1],
+            ci: (mean - half, mean + half),
+        })
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// The standard normal CDF.
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// The inverse of the standard normal CDF, by bisection.
+fn normal_quantile(p: f64) -> f64 {
+    let (mut low, mut high) = (-40.0, 40.0);
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if normal_cdf(mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Wilson score interval of a success rate, `(0, 1)` without trials.
+pub fn wilson_interval(successes: usize, trials: usize, confidence: f64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z = normal_quantile((1.0 + confidence) / 2.0);
+    let denominator = 1.0 + z * z / n;
+    let center = (p + z * z / (2.0 * n)) / denominator;
+    let half = z * sqrt(p * (1.0 - p) / n + z * z / (4.0 * n * n)) / denominator;
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankTest {
+    /// U statistic of the first sample.
+    pub u: f64,
+    pub z: f64,
+    /// Two-sided, 1 when the samples cannot be told apart.
+    pub p_value: f64,
+}
+
+/// Mann–Whitney U test, with the normal approximation corrected for ties and continuity.
+/// `None` if a sample is empty.
+pub fn mann_whitney(a: &[f64], b: &[f64]) -> Option<RankTest> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let mut values: Vec<(f64, bool)> = a
+        .iter()
+        .map(|v| (*v, true))
+        .chain(b.iter().map(|v| (*v, false)))
+        .collect();
+    values.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let mut rank_sum = 0.0;
+    let mut ties = 0.0;
+    let mut i = 0;
+    while i < values.len() {
+        let j = values[i..]
+            .iter()
+            .position(|v| v.0 != values[i].0)
+            .map_or(values.len(), |offset| i + offset);
+        // ranks i + 1 ..= j share their mean
+        let rank = (i + 1 + j) as f64 / 2.0;
+        rank_sum += rank * values[i..j].iter().filter(|v| v.1).count() as f64;
+        let t = (j - i) as f64;
+        ties += t * t * t - t;
+        i = j;
+    }
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let n = n1 + n2;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return Some(RankTest {
+            u,
+            z: 0.0,
+            p_value: 1.0,
+        });
+    }
+    let diff = u - mean;
+    let z = diff.signum() * (diff.abs() - 0.5).max(0.0) / sqrt(variance);
+    Some(RankTest {
+        u,
+        z,
+        p_value: erfc(z.abs() / std::f64::consts::SQRT_2).min(1.0),
+    })
+}
+
+/// The runs of an algorithm with a parameter set on an instance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    pub instance: String,
+    pub algorithm: String,
+    pub params: String,
+    pub runs: usize,
+    pub successes: usize,
+    pub success_rate: f64,
+    /// Wilson interval of the success rate.
+    pub success_ci: (f64, f64),
+    /// Seconds.
+    pub time: Distribution,
+    /// Seconds, of the successful runs only.
+    pub time_to_target: Option<Distribution>,
+    pub iterations: Distribution,
+    /// h of the best state of every run.
+    pub cost: Distribution,
+}
+
+impl Summary {
+    /// The algorithm followed by its parameter set.
+    pub fn label(&self) -> String {
+        label(&self.algorithm, &self.params)
+    }
+}
+
+fn label(algorithm: &str, params: &str) -> String {
+    if params.is_empty() {
+        algorithm.to_string()
+    } else {
+        format!("{} ({})", algorithm, params)
+    }
+}
+
+/// What `BenchmarkReport::compare` compares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Metric {
+    Cost,
+    Time,
+    Iterations,
+}
+
+impl Metric {
+    fn of(self, run: &Run) -> f64 {
+        match self {
+            Metric::Cost => run.h,
+            Metric::Time => run.duration.as_secs_f64(),
+            Metric::Iterations => run.iterations as f64,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Metric::Cost => "cost",
+            Metric::Time => "time (ms)",
+            Metric::Iterations => "iterations",
+        }
+    }
+
+    /// The unit of the reports, milliseconds for the times.
+    fn scale(self) -> f64 {
+        match self {
+            Metric::Time => 1000.0,
+            _ => 1.0,
+        }
+    }
+}
+
+/// The label of a candidate with the values of a metric in its runs.
+type Sample = (String, Vec<f64>);
+
+/// Two candidates on the same instance, `first` is better when its median is lower.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comparison {
+    pub instance: String,
+    pub metric: Metric,
+    pub first: String,
+    pub second: String,
+    pub first_median: f64,
+    pub second_median: f64,
+    pub test: RankTest,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BenchmarkReport {
+    /// In the order of the grid: instance, then algorithm and parameter set, then seed.
+    pub runs: Vec<Run>,
+    pub confidence: f64,
+}
+
+impl BenchmarkReport {
+    /// The runs grouped by instance, algorithm and parameter set, in the order of the grid.
+    fn groups(&self) -> Vec<(&str, &str, &str, Vec<&Run>)> {
+        let mut groups: Vec<(&str, &str, &str, Vec<&Run>)> = Vec::new();
+        for run in &self.runs {
+            let key = (
+                run.instance.as_str(),
+                run.algorithm.as_str(),
+                run.params.as_str(),
+            );
+            match groups.iter_mut().find(|g| (g.0, g.1, g.2) == key) {
+                Some(group) => group.3.push(run),
+                None => groups.push((key.0, key.1, key.2, vec![run])),
+            }
+        }
+        groups
+    }
+
+    pub fn summaries(&self) -> Vec<Summary> {
+        let c = self.confidence;
+        let values = |runs: &[&Run], metric: Metric| -> Vec<f64> {
+            runs.iter().map(|run| metric.of(run)).collect()
+        };
+        self.groups()
+            .into_iter()
+            .filter_map(|(instance, algorithm, params, runs)| {
+                let successes = runs.iter().filter(|run| run.success).count();
+                let to_target: Vec<f64> = runs
+                    .iter()
+                    .filter_map(|run| run.time_to_target)
+                    .map(|t| t.as_secs_f64())
+                    .collect();
+                Some(Summary {
+                    instance: instance.to_string(),
+                    algorithm: algorithm.to_string(),
+                    params: params.to_string(),
+                    runs: runs.len(),
+                    successes,
+                    success_rate: successes as f64 / runs.len() as f64,
+                    success_ci: wilson_interval(successes, runs.len(), c),
+                    time: Distribution::of(&values(&runs, Metric::Time), c)?,
+                    time_to_target: Distribution::of(&to_target, c),
+                    iterations: Distribution::of(&values(&runs, Metric::Iterations), c)?,
+                    cost: Distribution::of(&values(&runs, Metric::Cost), c)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Every pair of candidates on every instance, by the Mann–Whitney U test on `metric`.
+    pub fn compare(&self, metric: Metric) -> Vec<Comparison> {
+        let mut by_instance: Vec<(&str, Vec<Sample>)> = Vec::new();
+        for (instance, algorithm, params, runs) in self.groups() {
+            let candidate = (
+                label(algorithm, params),
+                runs.iter().map(|run| metric.of(run)).collect(),
+            );
+            match by_instance.iter_mut().find(|(name, _)| *name == instance) {
+                Some((_, candidates)) => candidates.push(candidate),
+                None => by_instance.push((instance, vec![candidate])),
+            }
+        }
+        let mut comparisons = Vec::new();
+        for (instance, candidates) in by_instance {
+            for (i, (first, a)) in candidates.iter().enumerate() {
+                for (second, b) in &candidates[i + 1..] {
+                    let Some(test) = mann_whitney(a, b) else {
+                        continue;
+                    };
+                    let median_of = |values: &[f64]| {
+                        let mut sorted = values.to_vec();
+                        sorted.sort_by(f64::total_cmp);
+                        median(&sorted)
+                    };
+                    comparisons.push(Comparison {
+                        instance: instance.to_string(),
+                        metric,
+                        first: first.clone(),
+                        second: second.clone(),
+                        first_median: median_of(a),
+                        second_median: median_of(b),
+                        test,
+                    });
+                }
+            }
+        }
+        comparisons
+    }
+
+    pub const CSV_HEADER: &'static str =
+        "instance,algorithm,params,seed,success,h,iterations,time,time_to_target";
+
+    /// One line for every run after `CSV_HEADER`, the times in seconds.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", Self::CSV_HEADER)?;
+        for run in &self.runs {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{}",
+                csv_field(&run.instance),
+                csv_field(&run.algorithm),
+                csv_field(&run.params),
+                run.seed,
+                run.success,
+                run.h,
+                run.iterations,
+                run.duration.as_secs_f64(),
+                run.time_to_target
+                    .map(|t| t.as_secs_f64().to_string())
+                    .unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+
+    pub const SUMMARY_CSV_HEADER: &'static str = "instance,algorithm,params,runs,success_rate,\
+        success_low,success_high,time_mean,time_low,time_high,time_median,time_to_target_mean,\
+        iterations_mean,iterations_median,cost_mean,cost_std_dev,cost_min,cost_median,cost_max";
+
+    /// One line for every summary after `SUMMARY_CSV_HEADER`, the times in seconds.
+    pub fn write_summary_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", Self::SUMMARY_CSV_HEADER)?;
+        for s in self.summaries() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                csv_field(&s.instance),
+                csv_field(&s.algorithm),
+                csv_field(&s.params),
+                s.runs,
+                s.success_rate,
+                s.success_ci.0,
+                s.success_ci.1,
+                s.time.mean,
+                s.time.ci.0,
+                s.time.ci.1,
+                s.time.median,
+                s.time_to_target
+                    .map(|t| t.mean.to_string())
+                    .unwrap_or_default(),
+                s.iterations.mean,
+                s.iterations.median,
+                s.cost.mean,
+                s.cost.std_dev,
+                s.cost.min,
+                s.cost.median,
+                s.cost.max
+            )?;
+        }
+        Ok(())
+    }
+
+    /// A table of the summaries followed by the comparisons of cost and time.
+    pub fn to_markdown(&self) -> String {
+        let level = self.confidence * 100.0;
+        let mut md = format!(
+            "| instance | algorithm | runs | success [{level}% CI] | mean time ms [{level}% CI] \
+             | time to target ms | iterations (median) | cost mean ± std (min, median, max) |\n\
+             |---|---|---|---|---|---|---|---|\n"
+        );
+        for s in self.summaries() {
+            let ms = |seconds: f64| seconds * 1000.0;
+            md.push_str(&format!(
+                "| {} | {} | {} | {:.3} [{:.3}, {:.3}] | {:.3} [{:.3}, {:.3}] | {} | {} | {:.3} ± {:.3} ({}, {}, {}) |\n",
+                s.instance,
+                s.label(),
+                s.runs,
+                s.success_rate,
+                s.success_ci.0,
+                s.success_ci.1,
+                ms(s.time.mean),
+                ms(s.time.ci.0),
+                ms(s.time.ci.1),
+                s.time_to_target
+                    .map_or("-".to_string(), |t| format!("{:.3}", ms(t.mean))),
+                s.iterations.median,
+                s.cost.mean,
+                s.cost.std_dev,
+                s.cost.min,
+                s.cost.median,
+                s.cost.max
+            ));
+        }
+        for metric in [Metric::Cost, Metric::Time] {
+            let comparisons = self.compare(metric);
+            if comparisons.is_empty() {
+                continue;
+            }
+            md.push_str(&format!(
+                "\nMann–Whitney U test on the {}:\n\n\
+                 | instance | first | second | median first | median second | U | p-value |\n\
+                 |---|---|---|---|---|---|---|\n",
+                metric.name()
+            ));
+            for c in comparisons {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {:.3} | {:.3} | {} | {:.4} |\n",
+                    c.instance,
+                    c.first,
+                    c.second,
+                    c.first_median * metric.scale(),
+                    c.second_median * metric.scale(),
+                    c.test.u,
+                    c.test.p_value
+                ));
+            }
+        }
+        md
+    }
+}
+
+/// Quotes a field with commas or quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
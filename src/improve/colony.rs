@@ -0,0 +1,318 @@
+use std::{collections::HashMap, hash::Hash};
+
+use rand::Rng;
+
+use crate::error::Result;
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::problem::{InitState, PheromoneFeature, SuitableState, RANDOM_WALK_STEPS};
+
+/// How the pheromone is reinforced after every iteration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AntVariant {
+    /// Every ant deposits on the steps of its plan.
+    AntSystem,
+    /// MAX-MIN Ant System: only the best ant of the iteration deposits, and the pheromone stays
+    /// between `deposit / (evaporation * (1 + best cost))` and that value divided by `ratio`.
+    MaxMin { ratio: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AntConfig {
+    /// Ants per iteration.
+    pub ants: usize,
+    /// Weight of the pheromone.
+    pub alpha: f64,
+    /// Weight of `PheromoneFeature::desirability`.
+    pub beta: f64,
+    /// Fraction of the pheromone that evaporates after every iteration.
+    pub evaporation: f64,
+    /// An ant with a plan of cost `c` deposits `deposit / (1 + c)` on every step.
+    pub deposit: f64,
+    /// Pheromone of the steps never reinforced.
+    pub initial: f64,
+    pub max_iter: Option<usize>,
+    /// Steps of an ant before it counts as stuck, e.g. when it walks in circles.
+    pub max_steps: usize,
+    pub variant: AntVariant,
+}
+
+impl Default for AntConfig {
+    fn default() -> Self {
+        Self {
+            ants: 20,
+            alpha: 1.0,
+            beta: 2.0,
+            evaporation: 0.1,
+            deposit: 1.0,
+            initial: 1.0,
+            max_iter: Some(100),
+            max_steps: RANDOM_WALK_STEPS,
+            variant: AntVariant::AntSystem,
+        }
+    }
+}
+
+/// The pheromone of every feature, the ones never reinforced share a default value that evaporates with the others.
+#[derive(Debug, Clone)]
+pub struct PheromoneTable<F> {
+    table: HashMap<F, f64>,
+    default: f64,
+}
+
+impl<F: Eq + Hash> PheromoneTable<F> {
+    fn new(initial: f64) -> Self {
+        Self {
+            table: HashMap::new(),
+            default: initial,
+        }
+    }
+
+    pub fn get(&self, feature: &F) -> f64 {
+        self.table.get(feature).copied().unwrap_or(self.default)
+    }
+
+    /// Number of features reinforced at least once.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// The pheromone of the features never reinforced.
+    pub fn default_value(&self) -> f64 {
+        self.default
+    }
+
+    fn evaporate(&mut self, rate: f64) {
+        self.default *= 1.0 - rate;
+        self.table.values_mut().for_each(|t| *t *= 1.0 - rate);
+    }
+
+    fn deposit(&mut self, feature: F, amount: f64) {
+        let default = self.default;
+        *self.table.entry(feature).or_insert(default) += amount;
+    }
+
+    fn clamp(&mut self, min: f64, max: f64) {
+        self.default = self.default.clamp(min, max);
+        self.table.values_mut().for_each(|t| *t = t.clamp(min, max));
+    }
+}
+
+/// A complete solution built by an ant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AntPlan<State, Action, Cost> {
+    pub state: State,
+    pub actions: Vec<Action>,
+    pub cost: Cost,
+}
+
+/// A plan with the features of its steps.
+struct Trail<P: PheromoneFeature> {
+    plan: AntPlan<P::State, P::Action, P::Cost>,
+    features: Vec<P::Feature>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColonyResult<State, Action, Cost> {
+    /// The plan of minimum cost, `None` if every ant got stuck before a suitable state.
+    pub best: Option<AntPlan<State, Action, Cost>>,
+    pub iterations: usize,
+    /// Ants that built a complete plan.
+    pub completed: usize,
+    /// The limit that stopped the search, `None` after `max_iter` iterations.
+    pub limit: Option<Limit>,
+}
+
+/// Ant colony optimization over a `CostructSolution`: every ant starts from the initial state and
+/// picks the executable actions with probability proportional to `pheromone^alpha * desirability^beta`
+/// until it reaches a suitable state. The plan with the minimum sum of step costs wins.
+///
+/// The expansions limit counts iterations, the generated one the ants sent.
+pub struct AntColony<R: Rng, F> {
+    rng: R,
+    config: AntConfig,
+    limits: SearchLimits,
+    pheromones: PheromoneTable<F>,
+}
+
+impl<R: Rng, F: Eq + Hash + Clone> AntColony<R, F> {
+    pub fn new(rng: R, config: AntConfig) -> Self {
+        Self {
+            rng,
+            pheromones: PheromoneTable::new(config.initial),
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &AntConfig {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// The pheromone left by the last search.
+    pub fn pheromones(&self) -> &PheromoneTable<F> {
+        &self.pheromones
+    }
+
+    pub fn solve<P>(&mut self, problem: &P) -> ColonyResult<P::State, P::Action, P::Cost>
+    where
+        P: PheromoneFeature<Feature = F, State: Clone, Action: Clone, Cost: Into<f64>>
+            + SuitableState
+            + InitState,
+    {
+        self.try_solve(problem).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_solve<P>(
+        &mut self,
+        problem: &P,
+    ) -> Result<ColonyResult<P::State, P::Action, P::Cost>>
+    where
+        P: PheromoneFeature<Feature = F, State: Clone, Action: Clone, Cost: Into<f64>>
+            + SuitableState
+            + InitState,
+    {
+        self.pheromones = PheromoneTable::new(self.config.initial);
+        let limits = self.limits.start();
+        let mut best: Option<AntPlan<P::State, P::Action, P::Cost>> = None;
+        let mut iter = 0;
+        let mut sent = 0;
+        let mut completed = 0;
+
+        loop {
+            let limit = limits.check(iter, sent, 0);
+            if limit.is_some() || self.config.max_iter.is_some_and(|max| iter >= max) {
+                return Ok(ColonyResult {
+                    best,
+                    iterations: iter,
+                    completed,
+                    limit,
+                });
+            }
+            iter += 1;
+
+            let mut trails = Vec::with_capacity(self.config.ants);
+            for _ in 0..self.config.ants {
+                sent += 1;
+                if let Some(trail) = self.walk(problem) {
+                    completed += 1;
+                    trails.push(trail);
+                }
+            }
+
+            self.pheromones.evaporate(self.config.evaporation);
+            let iteration_best = trails.iter().min_by_key(|trail| trail.plan.cost);
+            match self.config.variant {
+                AntVariant::AntSystem => {
+                    for trail in &trails {
+                        self.reinforce(trail.plan.cost, &trail.features);
+                    }
+                }
+                AntVariant::MaxMin { ratio } => {
+                    if let Some(trail) = iteration_best {
+                        self.reinforce(trail.plan.cost, &trail.features);
+                    }
+                    let best_cost = match (&best, iteration_best) {
+                        (Some(best), Some(trail)) => Some(best.cost.min(trail.plan.cost)),
+                        (Some(best), None) => Some(best.cost),
+                        (None, Some(trail)) => Some(trail.plan.cost),
+                        (None, None) => None,
+                    };
+                    if let Some(cost) = best_cost {
+                        let max =
+                            self.config.deposit / (self.config.evaporation * (1.0 + cost.into()));
+                        self.pheromones.clamp(max / ratio, max);
+                    }
+                }
+            }
+
+            if let Some(Trail { plan, .. }) = iteration_best {
+                if best.as_ref().is_none_or(|best| plan.cost < best.cost) {
+                    best = Some(plan.clone());
+                }
+            }
+        }
+    }
+
+    fn reinforce(&mut self, cost: impl Into<f64>, features: &[F]) {
+        let amount = self.config.deposit / (1.0 + cost.into());
+        for feature in features {
+            self.pheromones.deposit(feature.clone(), amount);
+        }
+    }
+
+    /// The plan of an ant with the features of its steps, `None` if it gets stuck or takes more
+    /// than `max_steps` steps.
+    fn walk<P>(&mut self, problem: &P) -> Option<Trail<P>>
+    where
+        P: PheromoneFeature<Feature = F, Action: Clone> + SuitableState + InitState,
+    {
+        let mut state = problem.init_state();
+        let mut actions = Vec::new();
+        let mut features = Vec::new();
+        let mut cost = P::Cost::default();
+        while !problem.is_suitable(&state) {
+            if actions.len() >= self.config.max_steps {
+                return None;
+            }
+            let candidates: Vec<(P::Action, P::Feature, f64)> = problem
+                .executable_actions(&state)
+                .map(|action| {
+                    let feature = problem.feature(&state, &action);
+                    let weight = self.pheromones.get(&feature).powf(self.config.alpha)
+                        * problem.desirability(&state, &action).powf(self.config.beta);
+                    (action, feature, weight)
+                })
+                .collect();
+            if candidates.is_empty() {
+                return None;
+            }
+            let total: f64 = candidates.iter().map(|c| c.2).sum();
+            let chosen = if total > 0.0 && total.is_finite() {
+                let mut r = self.rng.random_range(0.0..total);
+                candidates
+                    .iter()
+                    .position(|c| {
+                        r -= c.2;
+                        r < 0.0
+                    })
+                    .unwrap_or(candidates.len() - 1)
+            } else {
+                self.rng.random_range(0..candidates.len())
+            };
+            let (action, feature, _) = candidates.into_iter().nth(chosen)?;
+            let (next, step) = problem.result(&state, &action);
+            state = next;
+            cost = cost + step;
+            actions.push(action);
+            features.push(feature);
+        }
+        Some(Trail {
+            plan: AntPlan {
+                state,
+                actions,
+                cost,
+            },
+            features,
+        })
+    }
+}
+
+impl<R: Rng, F> LimitSearch for AntColony<R, F> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
@@ -0,0 +1,756 @@
+use rand::{seq::index, Rng};
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::error::{Error, Result};
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::problem::ContinuousProblem;
+
+/// The best point found by a continuous optimizer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Minimum {
+    pub x: Vec<f64>,
+    pub value: f64,
+    pub iterations: usize,
+    pub evaluated: usize,
+    /// The limit that stopped the search, `None` after `max_iter` iterations or at convergence.
+    pub limit: Option<Limit>,
+}
+
+/// The expansions limit counts iterations, the generated one evaluations of the objective.
+pub trait ContinuousOptimizer<P>: LimitSearch
+where
+    P: ContinuousProblem,
+{
+    fn minimize(&mut self, problem: &P) -> Minimum {
+        self.try_minimize(problem)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_minimize(&mut self, problem: &P) -> Result<Minimum>;
+}
+
+/// The bounds of the problem, one for every coordinate.
+fn checked_bounds<P: ContinuousProblem>(problem: &P) -> Result<Vec<(f64, f64)>> {
+    let bounds = problem.bounds();
+    if bounds.len() != problem.dimension() || bounds.is_empty() {
+        return Err(Error::InvalidState(format!(
+            "{} bounds for dimension {}",
+            bounds.len(),
+            problem.dimension()
+        )));
+    }
+    if bounds
+        .iter()
+        .any(|(lo, hi)| lo.is_nan() || hi.is_nan() || lo > hi)
+    {
+        return Err(Error::InvalidState(
+            "a lower bound is above its upper bound".into(),
+        ));
+    }
+    Ok(bounds)
+}
+
+fn clamp(x: &mut [f64], bounds: &[(f64, f64)]) {
+    for (v, (lo, hi)) in x.iter_mut().zip(bounds) {
+        *v = v.clamp(*lo, *hi);
+    }
+}
+
+fn random_point<R: Rng + ?Sized>(rng: &mut R, bounds: &[(f64, f64)]) -> Vec<f64> {
+    bounds
+        .iter()
+        .map(|(lo, hi)| {
+            if lo < hi {
+                rng.random_range(*lo..*hi)
+            } else {
+                *lo
+            }
+        })
+        .collect()
+}
+
+/// The index of the smallest value.
+fn argmin(values: &[f64]) -> usize {
+    (0..values.len())
+        .min_by(|a, b| values[*a].total_cmp(&values[*b]))
+        .unwrap_or(0)
+}
+
+/// Difference between the largest and the smallest value.
+fn spread(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    max - min
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeConfig {
+    /// Size of the population, at least 4: every mutation needs three other individuals.
+    pub population: usize,
+    /// Differential weight.
+    pub f: f64,
+    /// Crossover probability.
+    pub cr: f64,
+    pub max_iter: Option<usize>,
+    /// Stop when the values of the population are all within this distance.
+    pub tolerance: f64,
+}
+
+impl Default for DeConfig {
+    fn default() -> Self {
+        Self {
+            population: 40,
+            f: 0.5,
+            cr: 0.9,
+            max_iter: Some(1000),
+            tolerance: 1e-12,
+        }
+    }
+}
+
+/// Differential evolution, DE/rand/1/bin.
+pub struct DifferentialEvolution<R: Rng> {
+    rng: R,
+    config: DeConfig,
+    limits: SearchLimits,
+}
+
+impl<R: Rng> DifferentialEvolution<R> {
+    pub fn new(rng: R, config: DeConfig) -> Self {
+        Self {
+            rng,
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &DeConfig {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+}
+
+impl<R: Rng> LimitSearch for DifferentialEvolution<R> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+impl<R: Rng, P: ContinuousProblem> ContinuousOptimizer<P> for DifferentialEvolution<R> {
+    fn try_minimize(&mut self, problem: &P) -> Result<Minimum> {
+        let bounds = checked_bounds(problem)?;
+        let n = bounds.len();
+        let np = self.config.population;
+        if np < 4 {
+            return Err(Error::InvalidState(format!(
+                "a population of {} leaves no three other individuals to mutate",
+                np
+            )));
+        }
+        let limits = self.limits.start();
+        let mut population: Vec<Vec<f64>> = (0..np)
+            .map(|_| random_point(&mut self.rng, &bounds))
+            .collect();
+        let mut values = population
+            .iter()
+            .map(|x| problem.try_objective(x))
+            .collect::<Result<Vec<_>>>()?;
+        let mut evaluated = np;
+        let mut iter = 0;
+
+        loop {
+            let limit = limits.check(iter, evaluated, 0);
+            if limit.is_some()
+                || self.config.max_iter.is_some_and(|max| iter >= max)
+                || spread(&values) <= self.config.tolerance
+            {
+                let best = argmin(&values);
+                return Ok(Minimum {
+                    x: population.swap_remove(best),
+                    value: values[best],
+                    iterations: iter,
+                    evaluated,
+                    limit,
+                });
+            }
+            iter += 1;
+
+            for i in 0..np {
+                // three distinct individuals other than i
+                let picked: Vec<usize> = index::sample(&mut self.rng, np - 1, 3)
+                    .into_iter()
+                    .map(|j| if j >= i { j + 1 } else { j })
+                    .collect();
+                let (a, b, c) = (picked[0], picked[1], picked[2]);
+                let forced = self.rng.random_range(0..n);
+                let mut trial = population[i].clone();
+                for (j, v) in trial.iter_mut().enumerate() {
+                    if j == forced || self.rng.random::<f64>() < self.config.cr {
+                        *v = population[a][j]
+                            + self.config.f * (population[b][j] - population[c][j]);
+                    }
+                }
+                clamp(&mut trial, &bounds);
+                let value = problem.try_objective(&trial)?;
+                evaluated += 1;
+                if value <= values[i] {
+                    population[i] = trial;
+                    values[i] = value;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PsoConfig {
+    pub particles: usize,
+    pub inertia: f64,
+    /// Attraction towards the best position of the particle.
+    pub cognitive: f64,
+    /// Attraction towards the best position of the swarm.
+    pub social: f64,
+    pub max_iter: Option<usize>,
+    /// Stop when the best values of the particles are all within this distance.
+    pub tolerance: f64,
+}
+
+impl Default for PsoConfig {
+    /// The constriction coefficients of Clerc and Kennedy.
+    fn default() -> Self {
+        Self {
+            particles: 40,
+            inertia: 0.7298,
+            cognitive: 1.49618,
+            social: 1.49618,
+            max_iter: Some(1000),
+            tolerance: 1e-12,
+        }
+    }
+}
+
+/// Particle swarm optimization with a global best, velocities bounded by the size of the box.
+pub struct ParticleSwarm<R: Rng> {
+    rng: R,
+    config: PsoConfig,
+    limits: SearchLimits,
+}
+
+impl<R: Rng> ParticleSwarm<R> {
+    pub fn new(rng: R, config: PsoConfig) -> Self {
+        Self {
+            rng,
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &PsoConfig {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+}
+
+impl<R: Rng> LimitSearch for ParticleSwarm<R> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+impl<R: Rng, P: ContinuousProblem> ContinuousOptimizer<P> for ParticleSwarm<R> {
+    fn try_minimize(&mut self, problem: &P) -> Result<Minimum> {
+        let bounds = checked_bounds(problem)?;
+        let particles = self.config.particles.max(1);
+        let limits = self.limits.start();
+        let mut positions: Vec<Vec<f64>> = (0..particles)
+            .map(|_| random_point(&mut self.rng, &bounds))
+            .collect();
+        let mut velocities: Vec<Vec<f64>> = (0..particles)
+            .map(|_| {
+                bounds
+                    .iter()
+                    .map(|(lo, hi)| (hi - lo) * self.rng.random_range(-1.0..=1.0))
+                    .collect()
+            })
+            .collect();
+        let mut best_values = positions
+            .iter()
+            .map(|x| problem.try_objective(x))
+            .collect::<Result<Vec<_>>>()?;
+        let mut best_positions = positions.clone();
+        let mut global = argmin(&best_values);
+        let mut evaluated = particles;
+        let mut iter = 0;
+
+        loop {
+            let limit = limits.check(iter, evaluated, 0);
+            if limit.is_some()
+                || self.config.max_iter.is_some_and(|max| iter >= max)
+                || spread(&best_values) <= self.config.tolerance
+            {
+                return Ok(Minimum {
+                    x: best_positions.swap_remove(global),
+                    value: best_values[global],
+                    iterations: iter,
+                    evaluated,
+                    limit,
+                });
+            }
+            iter += 1;
+
+            for i in 0..particles {
+                for (j, (lo, hi)) in bounds.iter().enumerate() {
+                    let r1: f64 = self.rng.random();
+                    let r2: f64 = self.rng.random();
+                    let v = self.config.inertia * velocities[i][j]
+                        + self.config.cognitive * r1 * (best_positions[i][j] - positions[i][j])
+                        + self.config.social * r2 * (best_positions[global][j] - positions[i][j]);
+                    let range = hi - lo;
+                    velocities[i][j] = v.clamp(-range, range);
+                    positions[i][j] += velocities[i][j];
+                }
+                clamp(&mut positions[i], &bounds);
+                let value = problem.try_objective(&positions[i])?;
+                evaluated += 1;
+                if value < best_values[i] {
+                    best_values[i] = value;
+                    best_positions[i].clone_from(&positions[i]);
+                    if value < best_values[global] {
+                        global = i;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CmaEsConfig {
+    /// Offspring per generation, `4 + 3 ln(n)` when `None`.
+    pub lambda: Option<usize>,
+    /// Initial step size, as a fraction of the mean width of the box.
+    pub sigma: f64,
+    pub max_iter: Option<usize>,
+    /// Stop when the step size along every axis is below this value.
+    pub tolerance: f64,
+}
+
+impl Default for CmaEsConfig {
+    fn default() -> Self {
+        Self {
+            lambda: None,
+            sigma: 0.3,
+            max_iter: Some(1000),
+            tolerance: 1e-12,
+        }
+    }
+}
+
+/// Covariance matrix adaptation evolution strategy, with the weighted recombination of the
+/// best half of the offspring and cumulative step-size adaptation. Points outside the box are
+/// clamped before the evaluation.
+pub struct CmaEs<R: Rng> {
+    rng: R,
+    config: CmaEsConfig,
+    limits: SearchLimits,
+}
+
+impl<R: Rng> CmaEs<R> {
+    pub fn new(rng: R, config: CmaEsConfig) -> Self {
+        Self {
+            rng,
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &CmaEsConfig {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+}
+
+impl<R: Rng> LimitSearch for CmaEs<R> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+impl<R: Rng, P: ContinuousProblem> ContinuousOptimizer<P> for CmaEs<R> {
+    fn try_minimize(&mut self, problem: &P) -> Result<Minimum> {
+        let bounds = checked_bounds(problem)?;
+        let n = bounds.len();
+        let nf = n as f64;
+        let lambda = self
+            .config
+            .lambda
+            .unwrap_or(4 + (3.0 * nf.ln()) as usize)
+            .max(2);
+        let mu = lambda / 2;
+        let mut weights: Vec<f64> = (0..mu)
+            .map(|i| (mu as f64 + 0.5).ln() - ((i + 1) as f64).ln())
+            .collect();
+        let total: f64 = weights.iter().sum();
+        weights.iter_mut().for_each(|w| *w /= total);
+        let mueff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let cc = (4.0 + mueff / nf) / (nf + 4.0 + 2.0 * mueff / nf);
+        let cs = (mueff + 2.0) / (nf + mueff + 5.0);
+        let c1 = 2.0 / ((nf + 1.3).powi(2) + mueff);
+        let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((nf + 2.0).powi(2) + mueff));
+        let damps = 1.0 + 2.0 * (((mueff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+        let limits = self.limits.start();
+        let mut mean = random_point(&mut self.rng, &bounds);
+        let width = bounds.iter().map(|(lo, hi)| hi - lo).sum::<f64>() / nf;
+        let mut sigma = self.config.sigma * width;
+        let mut pc = vec![0.0; n];
+        let mut ps = vec![0.0; n];
+        let mut c = identity(n);
+        let mut b = identity(n);
+        let mut d = vec![1.0; n];
+
+        let mut best_value = problem.try_objective(&mean)?;
+        let mut best = mean.clone();
+        let mut evaluated = 1;
+        let mut iter = 0;
+
+        loop {
+            let limit = limits.check(iter, evaluated, 0);
+            let step = sigma * d.iter().copied().fold(0.0, f64::max);
+            if limit.is_some()
+                || self.config.max_iter.is_some_and(|max| iter >= max)
+                || step <= self.config.tolerance
+            {
+                return Ok(Minimum {
+                    x: best,
+                    value: best_value,
+                    iterations: iter,
+                    evaluated,
+                    limit,
+                });
+            }
+            iter += 1;
+
+            // offspring as (value, point, step from the mean divided by sigma)
+            let mut offspring = Vec::with_capacity(lambda);
+            for _ in 0..lambda {
+                let z: Vec<f64> = (0..n)
+                    .map(|i| {
+                        let z: f64 = StandardNormal.sample(&mut self.rng);
+                        d[i] * z
+                    })
+                    .collect();
+                let mut x: Vec<f64> = (0..n)
+                    .map(|i| mean[i] + sigma * (0..n).map(|j| b[i][j] * z[j]).sum::<f64>())
+                    .collect();
+                clamp(&mut x, &bounds);
+                let y: Vec<f64> = x.iter().zip(&mean).map(|(x, m)| (x - m) / sigma).collect();
+                let value = problem.try_objective(&x)?;
+                evaluated += 1;
+                if value < best_value {
+                    best_value = value;
+                    best.clone_from(&x);
+                }
+                offspring.push((value, x, y));
+            }
+            offspring.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let selected = &offspring[..mu];
+
+            let yw: Vec<f64> = (0..n)
+                .map(|i| selected.iter().zip(&weights).map(|(o, w)| w * o.2[i]).sum())
+                .collect();
+            for (m, y) in mean.iter_mut().zip(&yw) {
+                *m += sigma * y;
+            }
+
+            // C^(-1/2) yw = B D^-1 B^T yw
+            let bt: Vec<f64> = (0..n)
+                .map(|j| (0..n).map(|i| b[i][j] * yw[i]).sum::<f64>() / d[j])
+                .collect();
+            let whitened: Vec<f64> = (0..n)
+                .map(|i| (0..n).map(|j| b[i][j] * bt[j]).sum())
+                .collect();
+            let norm = (cs * (2.0 - cs) * mueff).sqrt();
+            for (p, w) in ps.iter_mut().zip(&whitened) {
+                *p = (1.0 - cs) * *p + norm * w;
+            }
+            let ps_norm = ps.iter().map(|p| p * p).sum::<f64>().sqrt();
+            let hsig = ps_norm / (1.0 - (1.0 - cs).powi(2 * iter as i32)).sqrt() / chi
+                < 1.4 + 2.0 / (nf + 1.0);
+            let hsig = if hsig { 1.0 } else { 0.0 };
+            let norm = (cc * (2.0 - cc) * mueff).sqrt();
+            for (p, y) in pc.iter_mut().zip(&yw) {
+                *p = (1.0 - cc) * *p + hsig * norm * y;
+            }
+
+            for i in 0..n {
+                for j in 0..=i {
+                    let rank_mu: f64 = selected
+                        .iter()
+                        .zip(&weights)
+                        .map(|(o, w)| w * o.2[i] * o.2[j])
+                        .sum();
+                    let value = (1.0 - c1 - cmu) * c[i][j]
+                        + c1 * (pc[i] * pc[j] + (1.0 - hsig) * cc * (2.0 - cc) * c[i][j])
+                        + cmu * rank_mu;
+                    c[i][j] = value;
+                    c[j][i] = value;
+                }
+            }
+            sigma *= ((cs / damps) * (ps_norm / chi - 1.0)).exp();
+
+            let (eigenvalues, eigenvectors) = symmetric_eigen(&c);
+            b = eigenvectors;
+            d = eigenvalues.iter().map(|e| e.max(1e-20).sqrt()).collect();
+        }
+    }
+}
+
+fn identity(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+/// Eigenvalues and eigenvectors (the columns of the matrix) of a symmetric matrix, with the cyclic Jacobi method.
+fn symmetric_eigen(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = matrix.len();
+    let mut a = matrix.to_vec();
+    let mut v = identity(n);
+    for _ in 0..50 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-30 {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let cos = 1.0 / (t * t + 1.0).sqrt();
+                let sin = t * cos;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = cos * akp - sin * akq;
+                    row[q] = sin * akp + cos * akq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    (*apk, *aqk) = (cos * *apk - sin * *aqk, sin * *apk + cos * *aqk);
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = cos * vkp - sin * vkq;
+                    row[q] = sin * vkp + cos * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NelderMeadConfig {
+    /// Size of the initial simplex, as a fraction of the width of the box along every axis.
+    pub step: f64,
+    pub max_iter: Option<usize>,
+    /// Stop when the values of the vertices are all within this distance.
+    pub tolerance: f64,
+}
+
+impl Default for NelderMeadConfig {
+    fn default() -> Self {
+        Self {
+            step: 0.1,
+            max_iter: Some(5000),
+            tolerance: 1e-12,
+        }
+    }
+}
+
+/// Nelder–Mead simplex method, with the usual reflection, expansion, contraction and shrink
+/// coefficients. Deterministic: `minimize` starts from the centre of the box, `refine` from a
+/// given point, e.g. the result of a global optimizer.
+pub struct NelderMead {
+    config: NelderMeadConfig,
+    limits: SearchLimits,
+}
+
+impl NelderMead {
+    pub fn new(config: NelderMeadConfig) -> Self {
+        Self {
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &NelderMeadConfig {
+        &self.config
+    }
+
+    pub fn refine<P: ContinuousProblem>(&mut self, problem: &P, start: Vec<f64>) -> Minimum {
+        self.try_refine(problem, start)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_refine<P: ContinuousProblem>(
+        &mut self,
+        problem: &P,
+        mut start: Vec<f64>,
+    ) -> Result<Minimum> {
+        let bounds = checked_bounds(problem)?;
+        if start.len() != bounds.len() {
+            return Err(Error::InvalidState(format!(
+                "starting point of dimension {} instead of {}",
+                start.len(),
+                bounds.len()
+            )));
+        }
+        clamp(&mut start, &bounds);
+        let n = bounds.len();
+        let limits = self.limits.start();
+
+        let mut simplex = vec![start.clone()];
+        for (i, (lo, hi)) in bounds.iter().enumerate() {
+            let mut vertex = start.clone();
+            let step = self.config.step * (hi - lo);
+            vertex[i] = if vertex[i] + step <= *hi {
+                vertex[i] + step
+            } else {
+                vertex[i] - step
+            };
+            simplex.push(vertex);
+        }
+        let mut values = simplex
+            .iter()
+            .map(|x| problem.try_objective(x))
+            .collect::<Result<Vec<_>>>()?;
+        let mut evaluated = n + 1;
+        let mut iter = 0;
+
+        loop {
+            let mut order: Vec<usize> = (0..=n).collect();
+            order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+            simplex = order.iter().map(|i| simplex[*i].clone()).collect();
+            values = order.iter().map(|i| values[*i]).collect();
+
+            let limit = limits.check(iter, evaluated, 0);
+            if limit.is_some()
+                || self.config.max_iter.is_some_and(|max| iter >= max)
+                || values[n] - values[0] <= self.config.tolerance
+            {
+                return Ok(Minimum {
+                    x: simplex.swap_remove(0),
+                    value: values[0],
+                    iterations: iter,
+                    evaluated,
+                    limit,
+                });
+            }
+            iter += 1;
+
+            let centroid: Vec<f64> = (0..n)
+                .map(|j| simplex[..n].iter().map(|x| x[j]).sum::<f64>() / n as f64)
+                .collect();
+            let towards = |target: &[f64], t: f64| {
+                let mut x: Vec<f64> = centroid
+                    .iter()
+                    .zip(target)
+                    .map(|(c, x)| c + t * (x - c))
+                    .collect();
+                clamp(&mut x, &bounds);
+                x
+            };
+
+            let reflected = towards(&simplex[n], -1.0);
+            let reflected_value = problem.try_objective(&reflected)?;
+            evaluated += 1;
+            if reflected_value < values[0] {
+                let expanded = towards(&simplex[n], -2.0);
+                let expanded_value = problem.try_objective(&expanded)?;
+                evaluated += 1;
+                (simplex[n], values[n]) = if expanded_value < reflected_value {
+                    (expanded, expanded_value)
+                } else {
+                    (reflected, reflected_value)
+                };
+                continue;
+            }
+            if reflected_value < values[n - 1] {
+                (simplex[n], values[n]) = (reflected, reflected_value);
+                continue;
+            }
+            let (contracted, bound) = if reflected_value < values[n] {
+                (towards(&simplex[n], -0.5), reflected_value)
+            } else {
+                (towards(&simplex[n], 0.5), values[n])
+            };
+            let contracted_value = problem.try_objective(&contracted)?;
+            evaluated += 1;
+            if contracted_value < bound {
+                (simplex[n], values[n]) = (contracted, contracted_value);
+                continue;
+            }
+            let best = simplex[0].clone();
+            for i in 1..=n {
+                for (x, b) in simplex[i].iter_mut().zip(&best) {
+                    *x = b + 0.5 * (*x - b);
+                }
+                values[i] = problem.try_objective(&simplex[i])?;
+                evaluated += 1;
+            }
+        }
+    }
+}
+
+impl LimitSearch for NelderMead {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+impl<P: ContinuousProblem> ContinuousOptimizer<P> for NelderMead {
+    fn try_minimize(&mut self, problem: &P) -> Result<Minimum> {
+        let center = checked_bounds(problem)?
+            .iter()
+            .map(|(lo, hi)| (lo + hi) / 2.0)
+            .collect();
+        self.try_refine(problem, center)
+    }
+}
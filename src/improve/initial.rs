@@ -0,0 +1,270 @@
+use std::{fs, path::Path};
+
+use rand::Rng;
+
+use crate::error::{Error, Result};
+use crate::limits::{LimitSearch, SearchLimits};
+use crate::observer::SearchObserver;
+use crate::problem::{
+    random_walk, InitState, Problem, RandomAction, SuitableState, Utility, RANDOM_WALK_RESTARTS,
+    RANDOM_WALK_STEPS,
+};
+
+use super::algorithms::ImprovingAlgorithm;
+use super::resolver::{AttemptResult, Progress};
+
+/// Constructions that may get stuck before `GreedyConstruction` gives up.
+pub const CONSTRUCTION_RETRIES: usize = 100;
+
+/// Builds the states an `ImprovingAlgorithm` starts from, see `ImprovingAlgorithm::attempt_from`.
+///
+/// Closures `FnMut(&P) -> Result<P::State>` are generators too, e.g. a sampler that knows the
+/// structure of the states.
+pub trait InitialStateGenerator<P: Problem> {
+    fn generate(&mut self, problem: &P) -> Result<P::State>;
+}
+
+impl<P, F> InitialStateGenerator<P> for F
+where
+    P: Problem,
+    F: FnMut(&P) -> Result<P::State>,
+{
+    fn generate(&mut self, problem: &P) -> Result<P::State> {
+        self(problem)
+    }
+}
+
+/// Random actions from `InitState` until a suitable state, like `RandomState`, with a cap on the
+/// restarts and on the length of every walk.
+pub struct RandomWalk<R: Rng> {
+    rng: R,
+    max_restarts: usize,
+    max_steps: Option<usize>,
+}
+
+impl<R: Rng> RandomWalk<R> {
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng,
+            max_restarts: RANDOM_WALK_RESTARTS,
+            max_steps: Some(RANDOM_WALK_STEPS),
+        }
+    }
+
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Starts again a walk that has not reached a suitable state after `max_steps` actions.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+}
+
+impl<R, P> InitialStateGenerator<P> for RandomWalk<R>
+where
+    R: Rng,
+    P: SuitableState + RandomAction + InitState,
+{
+    fn generate(&mut self, problem: &P) -> Result<P::State> {
+        random_walk(problem, &mut self.rng, self.max_restarts, self.max_steps)
+    }
+}
+
+/// Greedy randomized construction from `InitState`: at every step the actions are ranked by the
+/// cost of `result` plus the heuristic of the new state, and one is picked at random among the
+/// ones within `alpha` of the range from the best. 0 is purely greedy, 1 purely random.
+/// Actions with a NaN or infinite score are picked only when no score is finite.
+pub struct GreedyConstruction<R: Rng> {
+    rng: R,
+    alpha: f64,
+    max_restarts: usize,
+    max_steps: Option<usize>,
+}
+
+impl<R: Rng> GreedyConstruction<R> {
+    pub fn new(rng: R, alpha: f64) -> Self {
+        Self {
+            rng,
+            alpha,
+            max_restarts: CONSTRUCTION_RETRIES,
+            max_steps: Some(RANDOM_WALK_STEPS),
+        }
+    }
+
+    /// Constructions that may end in a state without executable actions before giving up.
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Starts again a construction that has not reached a suitable state after `max_steps` actions.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+}
+
+impl<R, P> InitialStateGenerator<P> for GreedyConstruction<R>
+where
+    R: Rng,
+    P: Utility<Cost: Into<f64>> + SuitableState + InitState,
+{
+    fn generate(&mut self, problem: &P) -> Result<P::State> {
+        'construction: for _ in 0..self.max_restarts {
+            let mut state = problem.init_state();
+            let mut steps = 0;
+            while !problem.is_suitable(&state) {
+                if self.max_steps.is_some_and(|max| steps >= max) {
+                    continue 'construction;
+                }
+                steps += 1;
+                let mut candidates = Vec::new();
+                for action in problem.executable_actions(&state) {
+                    let (next, cost) = problem.result(&state, &action);
+                    let score: f64 = (cost + problem.try_heuristic(&next)?).into();
+                    candidates.push((next, score));
+                }
+                if candidates.is_empty() {
+                    continue 'construction;
+                }
+                let finite = candidates.iter().map(|c| c.1).filter(|s| s.is_finite());
+                let min = finite.clone().fold(f64::INFINITY, f64::min);
+                let max = finite.fold(f64::NEG_INFINITY, f64::max);
+                let threshold = min + self.alpha * (max - min);
+                let mut restricted: Vec<usize> = (0..candidates.len())
+                    .filter(|i| candidates[*i].1.is_finite() && candidates[*i].1 <= threshold)
+                    .collect();
+                if restricted.is_empty() {
+                    restricted = (0..candidates.len()).collect();
+                }
+                let chosen = restricted[self.rng.random_range(0..restricted.len())];
+                state = candidates.swap_remove(chosen).0;
+            }
+            return Ok(state);
+        }
+        Err(Error::NoInitialState(format!(
+            "no suitable state after {} constructions",
+            self.max_restarts
+        )))
+    }
+}
+
+/// The given states in turn, e.g. the solutions of a previous run.
+pub struct FromStates<S> {
+    states: Vec<S>,
+    next: usize,
+}
+
+impl<S> FromStates<S> {
+    pub fn new(states: Vec<S>) -> Self {
+        Self { states, next: 0 }
+    }
+
+    /// One state for every non empty line of the file, read by `parse`.
+    pub fn from_file<F>(path: impl AsRef<Path>, mut parse: F) -> Result<Self>
+    where
+        F: FnMut(&str) -> Result<S>,
+    {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| Error::NoInitialState(format!("{}: {}", path.display(), e)))?;
+        let states = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(&mut parse)
+            .collect::<Result<_>>()?;
+        Ok(Self::new(states))
+    }
+
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+}
+
+impl<P> InitialStateGenerator<P> for FromStates<P::State>
+where
+    P: Problem<State: Clone>,
+{
+    fn generate(&mut self, _: &P) -> Result<P::State> {
+        if self.states.is_empty() {
+            return Err(Error::NoInitialState("no state given".into()));
+        }
+        let state = self.states[self.next % self.states.len()].clone();
+        self.next += 1;
+        Ok(state)
+    }
+}
+
+/// An algorithm whose attempts start from the states of `generator`, e.g. to restart it with a `Resolver`.
+pub struct WithGenerator<A, G> {
+    algo: A,
+    generator: G,
+}
+
+impl<A, G> WithGenerator<A, G> {
+    pub fn new(algo: A, generator: G) -> Self {
+        Self { algo, generator }
+    }
+
+    pub fn algo(&self) -> &A {
+        &self.algo
+    }
+
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+}
+
+impl<A: LimitSearch, G> LimitSearch for WithGenerator<A, G> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.algo.set_limits(limits);
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        self.algo.limits()
+    }
+}
+
+impl<A, G, P> ImprovingAlgorithm<P> for WithGenerator<A, G>
+where
+    A: ImprovingAlgorithm<P>,
+    G: InitialStateGenerator<P>,
+    P: Utility,
+{
+    fn population_size(&self) -> usize {
+        self.algo.population_size()
+    }
+
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        self.algo
+            .try_attempt_from(problem, &mut self.generator, observer)
+    }
+
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        self.algo.try_resume_observed(problem, progress, observer)
+    }
+}
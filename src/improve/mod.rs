@@ -1,2 +1,9 @@
 pub mod algorithms;
+pub mod benchmark;
+pub mod colony;
+pub mod continuous;
+pub mod initial;
+pub mod operators;
+pub mod parallel;
+pub mod pareto;
 pub mod resolver;
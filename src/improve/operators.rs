@@ -0,0 +1,363 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+use crate::error::Result;
+use crate::problem::{CostructSolution, Crossover, MutateGene, Problem, RandomState, Utility};
+
+/// Builds a child from two parents of the same length.
+pub trait CrossoverOperator<T> {
+    fn crossover<R: Rng + ?Sized>(&self, rng: &mut R, state: &T, other: &T) -> T;
+}
+
+/// Changes a state in place.
+pub trait MutationOperator<T> {
+    fn mutate<R: Rng + ?Sized>(&self, rng: &mut R, state: &mut T);
+}
+
+/// Two random positions, in order.
+fn cut_points<R: Rng + ?Sized>(rng: &mut R, len: usize) -> (usize, usize) {
+    let i = rng.random_range(0..len);
+    let j = rng.random_range(0..len);
+    (i.min(j), i.max(j))
+}
+
+/// Partially mapped crossover: a random segment of the first parent, the rest of the genes
+/// from the second one, mapped through the segment where they would repeat.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pmx;
+
+impl<G: Clone + Eq + Hash> CrossoverOperator<Vec<G>> for Pmx {
+    fn crossover<R: Rng + ?Sized>(&self, rng: &mut R, state: &Vec<G>, other: &Vec<G>) -> Vec<G> {
+        if state.len() < 2 {
+            return state.clone();
+        }
+        let (i, j) = cut_points(rng, state.len());
+        let segment: HashMap<&G, usize> = (i..=j).map(|k| (&state[k], k)).collect();
+        let mut child = other.clone();
+        child[i..=j].clone_from_slice(&state[i..=j]);
+        for k in (0..i).chain(j + 1..state.len()) {
+            let mut gene = &other[k];
+            while let Some(&p) = segment.get(gene) {
+                gene = &other[p];
+            }
+            child[k] = gene.clone();
+        }
+        child
+    }
+}
+
+/// Order crossover (OX): a random segment of the first parent, the other genes in the order
+/// they have in the second one, starting after the segment.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrderCrossover;
+
+impl<G: Clone + Eq + Hash> CrossoverOperator<Vec<G>> for OrderCrossover {
+    fn crossover<R: Rng + ?Sized>(&self, rng: &mut R, state: &Vec<G>, other: &Vec<G>) -> Vec<G> {
+        let n = state.len();
+        if n < 2 {
+            return state.clone();
+        }
+        let (i, j) = cut_points(rng, n);
+        let segment: HashSet<&G> = state[i..=j].iter().collect();
+        let mut child = state.clone();
+        let mut fill = (j + 1..n).chain(0..i);
+        for k in (j + 1..n).chain(0..=j) {
+            if !segment.contains(&other[k]) {
+                if let Some(pos) = fill.next() {
+                    child[pos] = other[k].clone();
+                }
+            }
+        }
+        child
+    }
+}
+
+/// Cycle crossover (CX): every gene keeps the position it has in one of the parents,
+/// taken from the two parents in turn along the cycles of positions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CycleCrossover;
+
+impl<G: Clone + Eq + Hash> CrossoverOperator<Vec<G>> for CycleCrossover {
+    fn crossover<R: Rng + ?Sized>(&self, _: &mut R, state: &Vec<G>, other: &Vec<G>) -> Vec<G> {
+        let position: HashMap<&G, usize> = state.iter().enumerate().map(|(k, g)| (g, k)).collect();
+        let mut child = state.clone();
+        let mut visited = vec![false; state.len()];
+        let mut from_other = false;
+        for start in 0..state.len() {
+            if visited[start] {
+                continue;
+            }
+            let mut k = start;
+            while !visited[k] {
+                visited[k] = true;
+                if from_other {
+                    child[k] = other[k].clone();
+                }
+                match position.get(&other[k]) {
+                    Some(&next) => k = next,
+                    None => break,
+                }
+            }
+            from_other = !from_other;
+        }
+        child
+    }
+}
+
+/// Swaps two random genes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwapMutation;
+
+impl<G> MutationOperator<Vec<G>> for SwapMutation {
+    fn mutate<R: Rng + ?Sized>(&self, rng: &mut R, state: &mut Vec<G>) {
+        if state.len() >= 2 {
+            let (i, j) = cut_points(rng, state.len());
+            state.swap(i, j);
+        }
+    }
+}
+
+/// Moves a random gene to another position.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InsertMutation;
+
+impl<G> MutationOperator<Vec<G>> for InsertMutation {
+    fn mutate<R: Rng + ?Sized>(&self, rng: &mut R, state: &mut Vec<G>) {
+        if state.len() >= 2 {
+            let gene = state.remove(rng.random_range(0..state.len()));
+            state.insert(rng.random_range(0..=state.len()), gene);
+        }
+    }
+}
+
+/// Reverses a random segment.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InversionMutation;
+
+impl<G> MutationOperator<Vec<G>> for InversionMutation {
+    fn mutate<R: Rng + ?Sized>(&self, rng: &mut R, state: &mut Vec<G>) {
+        if state.len() >= 2 {
+            let (i, j) = cut_points(rng, state.len());
+            state[i..=j].reverse();
+        }
+    }
+}
+
+/// The genes before a random point from the first parent, the others from the second one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OnePoint;
+
+impl<G: Clone> CrossoverOperator<Vec<G>> for OnePoint {
+    fn crossover<R: Rng + ?Sized>(&self, rng: &mut R, state: &Vec<G>, other: &Vec<G>) -> Vec<G> {
+        if state.len() < 2 {
+            return state.clone();
+        }
+        let point = rng.random_range(1..state.len());
+        let mut child = state[..point].to_vec();
+        child.extend_from_slice(&other[point..]);
+        child
+    }
+}
+
+/// A random segment from the second parent, the rest from the first one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TwoPoint;
+
+impl<G: Clone> CrossoverOperator<Vec<G>> for TwoPoint {
+    fn crossover<R: Rng + ?Sized>(&self, rng: &mut R, state: &Vec<G>, other: &Vec<G>) -> Vec<G> {
+        if state.len() < 2 {
+            return state.clone();
+        }
+        let (i, j) = cut_points(rng, state.len());
+        let mut child = state.clone();
+        child[i..=j].clone_from_slice(&other[i..=j]);
+        child
+    }
+}
+
+/// Every gene from one of the parents with the same probability.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+impl<G: Clone> CrossoverOperator<Vec<G>> for Uniform {
+    fn crossover<R: Rng + ?Sized>(&self, rng: &mut R, state: &Vec<G>, other: &Vec<G>) -> Vec<G> {
+        state
+            .iter()
+            .zip(other)
+            .map(|(a, b)| if rng.random() { a.clone() } else { b.clone() })
+            .collect()
+    }
+}
+
+/// BLX-alpha: every gene uniformly in the interval of the parents, widened by `alpha` times its length on both sides.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendCrossover {
+    pub alpha: f64,
+}
+
+impl CrossoverOperator<Vec<f64>> for BlendCrossover {
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        state: &Vec<f64>,
+        other: &Vec<f64>,
+    ) -> Vec<f64> {
+        state
+            .iter()
+            .zip(other)
+            .map(|(a, b)| {
+                let (lo, hi) = (a.min(*b), a.max(*b));
+                let d = (hi - lo) * self.alpha;
+                if hi - lo > 0.0 {
+                    rng.random_range(lo - d..=hi + d)
+                } else {
+                    lo
+                }
+            })
+            .collect()
+    }
+}
+
+/// Simulated binary crossover: children near the parents, closer the higher the distribution index `eta`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sbx {
+    pub eta: f64,
+}
+
+impl CrossoverOperator<Vec<f64>> for Sbx {
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        state: &Vec<f64>,
+        other: &Vec<f64>,
+    ) -> Vec<f64> {
+        let exponent = 1.0 / (self.eta + 1.0);
+        state
+            .iter()
+            .zip(other)
+            .map(|(a, b)| {
+                let u: f64 = rng.random();
+                let beta = if u <= 0.5 {
+                    (2.0 * u).powf(exponent)
+                } else {
+                    (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+                };
+                0.5 * ((1.0 + beta) * a + (1.0 - beta) * b)
+            })
+            .collect()
+    }
+}
+
+/// Adds to every gene, with probability `rate`, a normal noise with standard deviation `sigma`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GaussianMutation {
+    pub sigma: f64,
+    pub rate: f64,
+}
+
+impl MutationOperator<Vec<f64>> for GaussianMutation {
+    fn mutate<R: Rng + ?Sized>(&self, rng: &mut R, state: &mut Vec<f64>) {
+        let Ok(noise) = Normal::new(0.0, self.sigma) else {
+            return;
+        };
+        for gene in state.iter_mut() {
+            if rng.random::<f64>() < self.rate {
+                *gene += noise.sample(rng);
+            }
+        }
+    }
+}
+
+/// A problem whose `Crossover` and `MutateGene` are the given operators, to run
+/// `GeneticAlgorithm` without implementing them.
+pub struct WithOperators<'p, P, C, M> {
+    problem: &'p P,
+    crossover: C,
+    mutation: M,
+}
+
+impl<'p, P, C, M> WithOperators<'p, P, C, M> {
+    pub fn new(problem: &'p P, crossover: C, mutation: M) -> Self {
+        Self {
+            problem,
+            crossover,
+            mutation,
+        }
+    }
+
+    pub fn problem(&self) -> &P {
+        self.problem
+    }
+}
+
+impl<P: Problem, C, M> Problem for WithOperators<'_, P, C, M> {
+    type State = P::State;
+}
+
+impl<P: CostructSolution, C, M> CostructSolution for WithOperators<'_, P, C, M> {
+    type Action = P::Action;
+    type Cost = P::Cost;
+
+    fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+        self.problem.executable_actions(state)
+    }
+
+    fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+        self.problem.result(state, action)
+    }
+}
+
+impl<P: Utility, C, M> Utility for WithOperators<'_, P, C, M> {
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        self.problem.heuristic(state)
+    }
+
+    fn try_heuristic(&self, state: &Self::State) -> Result<Self::Cost> {
+        self.problem.try_heuristic(state)
+    }
+}
+
+impl<P: RandomState, C, M> RandomState for WithOperators<'_, P, C, M> {
+    fn random_state<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::State {
+        self.problem.random_state(rng)
+    }
+
+    fn try_random_state<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Self::State> {
+        self.problem.try_random_state(rng)
+    }
+}
+
+impl<P, C, M> Crossover for WithOperators<'_, P, C, M>
+where
+    P: Problem,
+    C: CrossoverOperator<P::State>,
+{
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        state: &Self::State,
+        other: &Self::State,
+    ) -> Self::State {
+        self.crossover.crossover(rng, state, other)
+    }
+}
+
+impl<P, C, M> MutateGene for WithOperators<'_, P, C, M>
+where
+    P: Problem<State: Clone>,
+    M: MutationOperator<P::State>,
+{
+    fn mutate_gene<R: Rng + ?Sized>(&self, rng: &mut R, state: &Self::State) -> Self::State {
+        let mut state = state.clone();
+        self.mutation.mutate(rng, &mut state);
+        state
+    }
+}
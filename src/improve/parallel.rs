@@ -0,0 +1,586 @@
+use std::{
+    cmp::Reverse,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::error::{Error, Result};
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::observer::{diversity, IterationStats, NoObserver, SearchObserver};
+use crate::problem::{Crossover, MutateGene, RandomState, SuitableState, Utility};
+
+use super::algorithms::{
+    reseed, restore, FitnessTransform, GeneticAlgorithm, GeneticConfig, ImprovingAlgorithm,
+    InverseFitness, RngCheckpoint,
+};
+use super::resolver::{
+    merge_restart, AttemptOutcome, AttemptResult, GoalCheck, Progress, ResolverResult,
+};
+
+/// Builds the algorithm of a worker from the RNG seeded for it.
+pub type AlgorithmFactory<'f, P> =
+    Box<dyn Fn(StdRng) -> Box<dyn ImprovingAlgorithm<P> + Send + 'f> + Sync + 'f>;
+
+/// Runs independent restarts on several threads. Every worker builds its own algorithm, with an
+/// RNG seeded from the seed of the resolver and the index of the worker, and the workers take the
+/// algorithms of the portfolio in turn: with a single algorithm they are plain parallel restarts.
+/// All the workers stop as soon as one of them reaches a goal.
+pub struct ParallelResolver<'f, P>
+where
+    P: Utility,
+{
+    portfolio: Vec<AlgorithmFactory<'f, P>>,
+    threads: usize,
+    seed: u64,
+    limits: SearchLimits,
+    goal_check: Option<GoalCheck<P>>,
+}
+
+impl<'f, P> ParallelResolver<'f, P>
+where
+    P: Utility<State: Send, Cost: Send> + Sync,
+{
+    /// Parallel restarts of the algorithm built by `factory`, on one worker for every available core.
+    pub fn new<A, F>(seed: u64, factory: F) -> Self
+    where
+        A: ImprovingAlgorithm<P> + Send + 'f,
+        F: Fn(StdRng) -> A + Sync + 'f,
+    {
+        Self::portfolio(seed).with_algorithm(factory)
+    }
+
+    /// An empty portfolio, filled with `with_algorithm`.
+    pub fn portfolio(seed: u64) -> Self {
+        Self {
+            portfolio: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            seed,
+            limits: SearchLimits::default(),
+            goal_check: None,
+        }
+    }
+
+    pub fn with_algorithm<A, F>(mut self, factory: F) -> Self
+    where
+        A: ImprovingAlgorithm<P> + Send + 'f,
+        F: Fn(StdRng) -> A + Sync + 'f,
+    {
+        self.portfolio.push(Box::new(move |rng| {
+            Box::new(factory(rng)) as Box<dyn ImprovingAlgorithm<P> + Send + 'f>
+        }));
+        self
+    }
+
+    /// Verifies the goals as in `Resolver::verified`.
+    pub fn verified(mut self) -> Self
+    where
+        P: SuitableState,
+    {
+        self.goal_check = Some(P::is_suitable);
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn resolve(&mut self, problem: &P, max_restarts: usize) -> ResolverResult<P> {
+        self.try_resolve(problem, max_restarts)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Runs at most `max_restarts` attempts among all the workers. The result has the best state found,
+    /// the iterations of every attempt and the wall-clock duration of the whole run.
+    pub fn try_resolve(&mut self, problem: &P, max_restarts: usize) -> Result<ResolverResult<P>> {
+        if self.portfolio.is_empty() {
+            return Err(Error::Invariant("the portfolio has no algorithm"));
+        }
+        let start = Instant::now();
+        let solved = Arc::new(AtomicBool::new(false));
+        let mut limits = self.limits.start();
+        let user_cancel = limits.cancel.replace(solved.clone());
+        let cancelled = || {
+            user_cancel
+                .as_ref()
+                .is_some_and(|token| token.load(Ordering::Relaxed))
+        };
+        // cancelled in advance, the first attempt of every worker stops at once
+        if cancelled() {
+            solved.store(true, Ordering::SeqCst);
+        }
+        let restarts = AtomicUsize::new(0);
+        let error: Mutex<Option<Error>> = Mutex::new(None);
+
+        let results: Vec<Option<AttemptResult<P>>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.threads)
+                .map(|worker| {
+                    let factory = &self.portfolio[worker % self.portfolio.len()];
+                    let rng = StdRng::seed_from_u64(self.seed.wrapping_add(worker as u64));
+                    let (limits, solved, restarts, error) = (&limits, &solved, &restarts, &error);
+                    let goal_check = self.goal_check;
+                    scope.spawn(move || {
+                        let mut algo = factory(rng);
+                        algo.set_limits(limits.clone());
+                        let result = run(
+                            &mut *algo,
+                            problem,
+                            goal_check,
+                            max_restarts,
+                            restarts,
+                            solved,
+                        );
+                        result.unwrap_or_else(|e| {
+                            error.lock().unwrap().get_or_insert(e);
+                            solved.store(true, Ordering::SeqCst);
+                            None
+                        })
+                    })
+                })
+                .collect();
+            // the workers only see the internal token, the one of the user is forwarded here
+            while !handles.iter().all(|handle| handle.is_finished()) {
+                if cancelled() {
+                    solved.store(true, Ordering::SeqCst);
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .expect("a worker of the parallel resolver panicked")
+                })
+                .collect()
+        });
+
+        if let Some(error) = error.into_inner().unwrap() {
+            return Err(error);
+        }
+        // the workers are ranked like the restarts of `Resolver::resolve_restart`
+        let best = results
+            .into_iter()
+            .flatten()
+            .reduce(|mut best, result| {
+                merge_restart(&mut best, result);
+                best
+            })
+            .ok_or(Error::Invariant("no attempt has been run"))?;
+        Ok(ResolverResult::from_inner(start, best))
+    }
+}
+
+/// The restarts of one worker, merged like `Resolver::resolve_restart` does.
+fn run<P>(
+    algo: &mut (dyn ImprovingAlgorithm<P> + Send + '_),
+    problem: &P,
+    goal_check: Option<GoalCheck<P>>,
+    max_restarts: usize,
+    restarts: &AtomicUsize,
+    solved: &AtomicBool,
+) -> Result<Option<AttemptResult<P>>>
+where
+    P: Utility,
+{
+    let mut result: Option<AttemptResult<P>> = None;
+    // like `Resolver::resolve_restart`, at least one attempt is run
+    while restarts.fetch_add(1, Ordering::SeqCst) < max_restarts.max(1) {
+        let new_result = algo.try_attempt(problem)?.verify_with(problem, goal_check);
+        if new_result.is_goal() {
+            solved.store(true, Ordering::SeqCst);
+        }
+        let stop = matches!(new_result.limit, Some(Limit::Time | Limit::Cancelled))
+            || solved.load(Ordering::SeqCst);
+        match result.as_mut() {
+            Some(result) => merge_restart(result, new_result),
+            None => result = Some(new_result),
+        }
+        if stop {
+            break;
+        }
+    }
+    Ok(result)
+}
+
+/// The limits apply to every attempt, the time budget and the cancel token to the whole run.
+impl<P> LimitSearch for ParallelResolver<'_, P>
+where
+    P: Utility,
+{
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+/// Where the migrants of every island go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// To the next island, the last one sends them to the first.
+    #[default]
+    Ring,
+    /// To every other island.
+    FullyConnected,
+}
+
+/// Which individuals leave an island and which ones they replace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MigrationPolicy {
+    #[default]
+    BestReplaceWorst,
+    BestReplaceRandom,
+    RandomReplaceWorst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IslandConfig {
+    pub islands: usize,
+    /// Generations between two migrations.
+    pub interval: usize,
+    /// Individuals sent by every island to each of its neighbours.
+    pub migrants: usize,
+    pub topology: Topology,
+    pub policy: MigrationPolicy,
+}
+
+/// The state of an island at the end of an epoch.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IslandReport<Cost> {
+    pub best_h: Cost,
+    /// Fraction of distinct heuristic values in the population, from 1 / k to 1.
+    pub diversity: f64,
+}
+
+/// The islands after `generations` generations, before the migration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EpochReport<Cost> {
+    pub generations: usize,
+    pub islands: Vec<IslandReport<Cost>>,
+}
+
+/// Island model: a `GeneticAlgorithm` for every island, each one on its own thread, exchanging
+/// individuals every `interval` generations. `GeneticConfig::k` is the size of every island and
+/// `GeneticConfig::max_iter` the generations of the whole attempt.
+///
+/// The expansions limit counts generations, every island runs the same number of them.
+pub struct IslandModel<Cost, F = InverseFitness> {
+    rng: StdRng,
+    fitness: F,
+    genetic: GeneticConfig,
+    config: IslandConfig,
+    limits: SearchLimits,
+    history: Vec<EpochReport<Cost>>,
+}
+
+impl<Cost> IslandModel<Cost> {
+    pub fn new(seed: u64, genetic: GeneticConfig, config: IslandConfig) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            fitness: InverseFitness,
+            genetic,
+            config,
+            limits: SearchLimits::default(),
+            history: Vec::new(),
+        }
+    }
+}
+
+impl<Cost, F> IslandModel<Cost, F> {
+    /// See `GeneticAlgorithm::with_fitness`.
+    pub fn with_fitness<G>(self, fitness: G) -> IslandModel<Cost, G> {
+        IslandModel {
+            rng: self.rng,
+            fitness,
+            genetic: self.genetic,
+            config: self.config,
+            limits: self.limits,
+            history: self.history,
+        }
+    }
+
+    pub fn config(&self) -> &IslandConfig {
+        &self.config
+    }
+
+    pub fn genetic(&self) -> &GeneticConfig {
+        &self.genetic
+    }
+
+    /// The generator of the seeds of the islands.
+    pub fn rng(&self) -> &StdRng {
+        &self.rng
+    }
+
+    /// The epochs of the last attempt.
+    pub fn history(&self) -> &[EpochReport<Cost>] {
+        &self.history
+    }
+}
+
+impl<Cost, F> LimitSearch for IslandModel<Cost, F> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+/// An island after an epoch, with the heuristic of every individual and the states evaluated
+/// in the epoch, the ones evaluated again included.
+struct Island<P: Utility> {
+    result: AttemptResult<P>,
+    costs: Vec<P::Cost>,
+    evaluated: usize,
+}
+
+impl<F, P> ImprovingAlgorithm<P> for IslandModel<P::Cost, F>
+where
+    F: FitnessTransform<P::Cost> + Clone + Send,
+    P: MutateGene + Utility<State: Send, Cost: Send> + RandomState<State: Clone> + Crossover + Sync,
+{
+    fn population_size(&self) -> usize {
+        self.genetic.k * self.config.islands
+    }
+
+    fn try_attempt_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let size = self.genetic.k * self.config.islands;
+        let population = (0..size)
+            .map(|_| problem.try_random_state(&mut self.rng))
+            .collect::<Result<_>>()?;
+        self.try_resume_observed(problem, Progress::new(population), observer)
+    }
+
+    /// The population holds the individuals of all the islands, one island after the other.
+    fn try_resume_observed(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut generations = progress.iteration;
+        let mut evaluated = progress.evaluated;
+        let mut best_h = progress.best_h;
+        restore(Some(RngCheckpoint::new()), &mut self.rng, progress.seed);
+        let limits = self.limits.start().offset(generations, evaluated);
+        self.history.clear();
+        let islands = self.config.islands.max(1);
+        let size = progress.population.len().div_ceil(islands).max(1);
+        let mut population = progress.population.into_iter();
+        let mut populations: Vec<Vec<P::State>> = (0..islands)
+            .map(|_| population.by_ref().take(size).collect())
+            .collect();
+        let mut best: Option<(P::State, P::Cost)> = None;
+
+        loop {
+            let stop = limits.check(generations, evaluated, 0).or_else(|| {
+                self.genetic
+                    .max_iter
+                    .is_some_and(|max| generations >= max)
+                    .then_some(Limit::Expansions)
+            });
+            if let Some(limit) = stop {
+                let exhausted = limits.check(generations, evaluated, 0).is_none();
+                let (state, h) = match best {
+                    Some(best) => best,
+                    None => {
+                        let state = populations
+                            .iter()
+                            .flatten()
+                            .next()
+                            .cloned()
+                            .ok_or(Error::EmptyPopulation)?;
+                        let h = problem.try_heuristic(&state)?;
+                        evaluated += 1;
+                        (state, h)
+                    }
+                };
+                if exhausted {
+                    return Ok(AttemptResult::new(state, h, generations)
+                        .with_evaluated(evaluated)
+                        .with_outcome(AttemptOutcome::BudgetExhausted(limit)));
+                }
+                let progress = Progress {
+                    iteration: generations,
+                    evaluated,
+                    best_h,
+                    seed: reseed(Some(RngCheckpoint::new()), &mut self.rng),
+                    ..Progress::new(populations.into_iter().flatten().collect())
+                };
+                return Ok(AttemptResult::stopped(state, h, generations, limit)
+                    .with_evaluated(evaluated)
+                    .with_progress(progress));
+            }
+
+            let interval = self.config.interval.max(1);
+            let interval = self
+                .genetic
+                .max_iter
+                .map_or(interval, |max| interval.min(max - generations));
+            let mut island_limits = limits.clone();
+            island_limits.max_expansions = Some(interval);
+            island_limits.max_generated = None;
+            let seeds: Vec<StdRng> = (0..islands)
+                .map(|_| StdRng::from_rng(&mut self.rng))
+                .collect();
+            let genetic = GeneticConfig {
+                max_iter: None,
+                ..self.genetic
+            };
+
+            let results: Vec<Result<Island<P>>> = thread::scope(|scope| {
+                let handles: Vec<_> = populations
+                    .drain(..)
+                    .zip(seeds)
+                    .map(|(population, rng)| {
+                        let mut algo = GeneticAlgorithm::from_config(rng, genetic)
+                            .with_fitness(self.fitness.clone());
+                        algo.set_limits(island_limits.clone());
+                        // every epoch starts a new search, so the population is evaluated and
+                        // counted again
+                        let progress = Progress::new(population);
+                        scope.spawn(move || -> Result<Island<P>> {
+                            let result =
+                                algo.try_resume_observed(problem, progress, &mut NoObserver)?;
+                            let costs: Vec<P::Cost> = match &result.progress {
+                                Some(progress) => progress
+                                    .population
+                                    .iter()
+                                    .map(|state| problem.try_heuristic(state))
+                                    .collect::<Result<_>>()?,
+                                None => Vec::new(),
+                            };
+                            let evaluated = result.evaluated + costs.len();
+                            Ok(Island {
+                                result,
+                                costs,
+                                evaluated,
+                            })
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("an island panicked"))
+                    .collect()
+            });
+
+            let mut report = EpochReport {
+                generations: generations + interval,
+                islands: Vec::with_capacity(islands),
+            };
+            let mut island_costs = Vec::with_capacity(islands);
+            let mut interrupted = None;
+            let results = results.into_iter().collect::<Result<Vec<_>>>()?;
+            evaluated += results.iter().map(|island| island.evaluated).sum::<usize>();
+            for Island { result, costs, .. } in results {
+                if result.is_goal() {
+                    let iterations = generations + result.iterations;
+                    observer.on_goal(iterations, &result.state, result.h);
+                    return Ok(AttemptResult::new(result.state, result.h, iterations)
+                        .with_evaluated(evaluated));
+                }
+                let progress = result.progress.ok_or(Error::EmptyPopulation)?;
+                if matches!(result.limit, Some(Limit::Time | Limit::Cancelled)) {
+                    interrupted = result.limit;
+                }
+                report.islands.push(IslandReport {
+                    best_h: result.h,
+                    diversity: diversity(&costs),
+                });
+                if best.as_ref().is_none_or(|(_, h)| result.h < *h) {
+                    best = Some((result.state, result.h));
+                }
+                populations.push(progress.population);
+                island_costs.push(costs);
+            }
+            generations += interval;
+            self.history.push(report);
+
+            if let Some((state, h)) = &best {
+                observer.on_iteration(generations, state, *h);
+                if best_h.is_none_or(|best_h| *h < best_h) {
+                    best_h = Some(*h);
+                    observer.on_improvement(generations, state, *h);
+                }
+                observer.on_statistics(&IterationStats {
+                    diversity: Some(diversity(&island_costs.concat())),
+                    ..IterationStats::new(generations, evaluated, best_h.unwrap_or(*h), *h)
+                });
+            }
+            if interrupted.is_none() {
+                self.migrate(&mut populations, &mut island_costs);
+            }
+        }
+    }
+}
+
+impl<Cost: Ord + Copy, F> IslandModel<Cost, F> {
+    /// Moves the migrants of every island to its neighbours, all the migrants leave before any arrives.
+    fn migrate<S: Clone>(&mut self, populations: &mut [Vec<S>], costs: &mut [Vec<Cost>]) {
+        let islands = populations.len();
+        if islands < 2 || self.config.migrants == 0 {
+            return;
+        }
+        let mut arrivals: Vec<Vec<(S, Cost)>> = vec![Vec::new(); islands];
+        for from in 0..islands {
+            let mut order: Vec<usize> = (0..populations[from].len()).collect();
+            match self.config.policy {
+                MigrationPolicy::BestReplaceWorst | MigrationPolicy::BestReplaceRandom => {
+                    order.sort_by_key(|i| costs[from][*i])
+                }
+                MigrationPolicy::RandomReplaceWorst => order.shuffle(&mut self.rng),
+            }
+            let migrants: Vec<(S, Cost)> = order
+                .into_iter()
+                .take(self.config.migrants)
+                .map(|i| (populations[from][i].clone(), costs[from][i]))
+                .collect();
+            let targets: Vec<usize> = match self.config.topology {
+                Topology::Ring => vec![(from + 1) % islands],
+                Topology::FullyConnected => (0..islands).filter(|to| *to != from).collect(),
+            };
+            for to in targets {
+                arrivals[to].extend(migrants.iter().cloned());
+            }
+        }
+        for (to, arrivals) in arrivals.into_iter().enumerate() {
+            let mut order: Vec<usize> = (0..populations[to].len()).collect();
+            match self.config.policy {
+                MigrationPolicy::BestReplaceWorst | MigrationPolicy::RandomReplaceWorst => {
+                    order.sort_by_key(|i| Reverse(costs[to][*i]))
+                }
+                MigrationPolicy::BestReplaceRandom => order.shuffle(&mut self.rng),
+            }
+            for (i, (state, h)) in order.into_iter().zip(arrivals) {
+                populations[to][i] = state;
+                costs[to][i] = h;
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+
+use crate::error::{Error, Result};
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::problem::{Crossover, MultiObjective, MutateGene, RandomState};
+
+/// True if `a` is no worse than `b` in every objective and better in at least one.
+pub fn dominates(a: &[f64], b: &[f64]) -> bool {
+    let mut better = false;
+    for (x, y) in a.iter().zip(b) {
+        if x > y {
+            return false;
+        }
+        better |= x < y;
+    }
+    better
+}
+
+/// The indices of the costs grouped in fronts: the first one is not dominated by anything,
+/// every other one only by the fronts before it.
+pub fn non_dominated_sort(costs: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let n = costs.len();
+    let mut dominated: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut counts = vec![0usize; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if dominates(&costs[i], &costs[j]) {
+                dominated[i].push(j);
+                counts[j] += 1;
+            } else if dominates(&costs[j], &costs[i]) {
+                dominated[j].push(i);
+                counts[i] += 1;
+            }
+        }
+    }
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..n).filter(|i| counts[*i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for i in &front {
+            for j in &dominated[*i] {
+                counts[*j] -= 1;
+                if counts[*j] == 0 {
+                    next.push(*j);
+                }
+            }
+        }
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+/// The crowding distance of every index of `front`, in the same order: the sum over the objectives
+/// of the normalized distance between the two neighbours, infinite at the extremes.
+pub fn crowding_distance(costs: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+    let mut distance = vec![0.0; front.len()];
+    if front.len() < 3 {
+        distance.fill(f64::INFINITY);
+        return distance;
+    }
+    let objectives = costs[front[0]].len();
+    let mut order: Vec<usize> = (0..front.len()).collect();
+    let columns = (0..objectives).map(|m| front.iter().map(|i| costs[*i][m]).collect::<Vec<_>>());
+    for value in columns {
+        order.sort_by(|a, b| value[*a].total_cmp(&value[*b]));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distance[first] = f64::INFINITY;
+        distance[last] = f64::INFINITY;
+        let range = value[last] - value[first];
+        if range <= 0.0 {
+            continue;
+        }
+        for w in order.windows(3) {
+            distance[w[1]] += (value[w[2]] - value[w[0]]) / range;
+        }
+    }
+    distance
+}
+
+/// The volume dominated by the points and bounded by `reference`, which every useful point must
+/// dominate. Exact, by slicing along the last objective.
+pub fn hypervolume(points: &[Vec<f64>], reference: &[f64]) -> f64 {
+    let points: Vec<&[f64]> = points
+        .iter()
+        .map(|p| p.as_slice())
+        .filter(|p| p.iter().zip(reference).all(|(x, r)| x < r))
+        .collect();
+    slice_volume(points, reference)
+}
+
+fn slice_volume(mut points: Vec<&[f64]>, reference: &[f64]) -> f64 {
+    let d = reference.len();
+    if points.is_empty() || d == 0 {
+        return 0.0;
+    }
+    if d == 1 {
+        let min = points.iter().map(|p| p[0]).fold(f64::INFINITY, f64::min);
+        return reference[0] - min;
+    }
+    points.sort_by(|a, b| a[d - 1].total_cmp(&b[d - 1]));
+    let mut volume = 0.0;
+    for i in 0..points.len() {
+        let upper = points.get(i + 1).map_or(reference[d - 1], |p| p[d - 1]);
+        let height = upper - points[i][d - 1];
+        if height > 0.0 {
+            volume += height * slice_volume(points[..=i].to_vec(), &reference[..d - 1]);
+        }
+    }
+    volume
+}
+
+/// Inverted generational distance: the mean distance of every point of the reference front
+/// from the closest point of `points`, zero when the reference front is covered.
+pub fn igd(points: &[Vec<f64>], reference_front: &[Vec<f64>]) -> f64 {
+    if reference_front.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = reference_front
+        .iter()
+        .map(|r| {
+            points
+                .iter()
+                .map(|p| {
+                    p.iter()
+                        .zip(r)
+                        .map(|(x, y)| (x - y).powi(2))
+                        .sum::<f64>()
+                        .sqrt()
+                })
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum();
+    total / reference_front.len() as f64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution<S> {
+    pub state: S,
+    pub costs: Vec<f64>,
+}
+
+/// The non-dominated individuals of the last population.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoFront<S> {
+    pub solutions: Vec<Solution<S>>,
+    pub generations: usize,
+    pub evaluated: usize,
+    /// The limit that stopped the search, `None` after `max_iter` generations.
+    pub limit: Option<Limit>,
+}
+
+impl<S> ParetoFront<S> {
+    /// The costs of the solutions, for `hypervolume` and `igd`.
+    pub fn costs(&self) -> Vec<Vec<f64>> {
+        self.solutions.iter().map(|s| s.costs.clone()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Nsga2Config {
+    /// Size of the population.
+    pub k: usize,
+    pub max_iter: Option<usize>,
+    /// Probability that a child is mutated.
+    pub pmut: f64,
+}
+
+/// NSGA-II: parents and children ranked together by non-dominated sorting, ties in the last
+/// front broken by crowding distance.
+///
+/// The expansions limit counts generations, the generated one evaluations.
+pub struct Nsga2<R: Rng> {
+    rng: R,
+    config: Nsga2Config,
+    limits: SearchLimits,
+}
+
+impl<R: Rng> Nsga2<R> {
+    pub fn new(rng: R, config: Nsga2Config) -> Self {
+        Self {
+            rng,
+            config,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn config(&self) -> &Nsga2Config {
+        &self.config
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    pub fn run<P>(&mut self, problem: &P) -> ParetoFront<P::State>
+    where
+        P: MultiObjective + RandomState<State: Clone> + Crossover + MutateGene,
+    {
+        self.try_run(problem).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_run<P>(&mut self, problem: &P) -> Result<ParetoFront<P::State>>
+    where
+        P: MultiObjective + RandomState<State: Clone> + Crossover + MutateGene,
+    {
+        let population = (0..self.config.k)
+            .map(|_| problem.try_random_state(&mut self.rng))
+            .collect::<Result<_>>()?;
+        self.try_evolve(problem, population)
+    }
+
+    /// Starts from the given population instead of a random one.
+    pub fn evolve<P>(&mut self, problem: &P, population: Vec<P::State>) -> ParetoFront<P::State>
+    where
+        P: MultiObjective<State: Clone> + Crossover + MutateGene,
+    {
+        self.try_evolve(problem, population)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_evolve<P>(
+        &mut self,
+        problem: &P,
+        mut population: Vec<P::State>,
+    ) -> Result<ParetoFront<P::State>>
+    where
+        P: MultiObjective<State: Clone> + Crossover + MutateGene,
+    {
+        if population.is_empty() {
+            return Err(Error::EmptyPopulation);
+        }
+        let k = population.len();
+        let limits = self.limits.start();
+        let mut costs = population
+            .iter()
+            .map(|state| problem.try_objectives(state))
+            .collect::<Result<Vec<_>>>()?;
+        let mut evaluated = k;
+        let mut generations = 0;
+        let (mut rank, mut crowding) = Self::rank(&costs, k).1;
+
+        loop {
+            let limit = limits.check(generations, evaluated, 0);
+            if limit.is_some() || self.config.max_iter.is_some_and(|max| generations >= max) {
+                let solutions = population
+                    .into_iter()
+                    .zip(costs)
+                    .zip(rank)
+                    .filter(|(_, rank)| *rank == 0)
+                    .map(|((state, costs), _)| Solution { state, costs })
+                    .collect();
+                return Ok(ParetoFront {
+                    solutions,
+                    generations,
+                    evaluated,
+                    limit,
+                });
+            }
+            generations += 1;
+
+            for _ in 0..k {
+                let a = self.tournament(&rank, &crowding);
+                let b = self.tournament(&rank, &crowding);
+                let mut child = problem.crossover(&mut self.rng, &population[a], &population[b]);
+                if self.rng.random::<f64>() <= self.config.pmut {
+                    child = problem.mutate_gene(&mut self.rng, &child);
+                }
+                costs.push(problem.try_objectives(&child)?);
+                population.push(child);
+                evaluated += 1;
+            }
+
+            let (survivors, next) = Self::rank(&costs, k);
+            (rank, crowding) = next;
+            let mut survived = vec![false; population.len()];
+            for i in &survivors {
+                survived[*i] = true;
+            }
+            let mut kept = survived.iter();
+            population.retain(|_| *kept.next().unwrap_or(&false));
+            let mut kept = survived.iter();
+            costs.retain(|_| *kept.next().unwrap_or(&false));
+        }
+    }
+
+    /// The `k` best indices, in increasing order, with the rank and the crowding distance of each.
+    fn rank(costs: &[Vec<f64>], k: usize) -> (Vec<usize>, (Vec<usize>, Vec<f64>)) {
+        let mut chosen: Vec<(usize, usize, f64)> = Vec::with_capacity(k);
+        for (r, front) in non_dominated_sort(costs).into_iter().enumerate() {
+            if chosen.len() >= k {
+                break;
+            }
+            let distance = crowding_distance(costs, &front);
+            let mut members: Vec<(usize, usize, f64)> = front
+                .into_iter()
+                .zip(distance)
+                .map(|(i, d)| (i, r, d))
+                .collect();
+            if chosen.len() + members.len() > k {
+                members.sort_by(|a, b| b.2.total_cmp(&a.2));
+                members.truncate(k - chosen.len());
+            }
+            chosen.extend(members);
+        }
+        chosen.sort_by_key(|(i, _, _)| *i);
+        let indices = chosen.iter().map(|(i, _, _)| *i).collect();
+        let rank = chosen.iter().map(|(_, r, _)| *r).collect();
+        let crowding = chosen.iter().map(|(_, _, d)| *d).collect();
+        (indices, (rank, crowding))
+    }
+
+    /// Binary tournament: the lower rank wins, then the larger crowding distance.
+    fn tournament(&mut self, rank: &[usize], crowding: &[f64]) -> usize {
+        let a = self.rng.random_range(0..rank.len());
+        let b = self.rng.random_range(0..rank.len());
+        match rank[a]
+            .cmp(&rank[b])
+            .then(crowding[b].total_cmp(&crowding[a]))
+        {
+            Ordering::Greater => b,
+            _ => a,
+        }
+    }
+}
+
+impl<R: Rng> LimitSearch for Nsga2<R> {
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
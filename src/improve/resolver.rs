@@ -4,10 +4,72 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::problem::{CostructSolution, RandomState, Utility};
+use crate::error::Result;
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::observer::{NoObserver, SearchObserver};
+use crate::problem::{CostructSolution, RandomState, SuitableState, Utility};
 
-use super::algorithms::ImprovingAlgorithm;
+use super::algorithms::{AnnealingProgress, ImprovingAlgorithm};
 
+/// Where an attempt stopped: returned when a limit interrupts it, and accepted by `ImprovingAlgorithm::resume`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Progress<State, Cost> {
+    /// The current state of the single state algorithms, the population of the others.
+    pub population: Vec<State>,
+    pub iteration: usize,
+    /// States evaluated so far.
+    pub evaluated: usize,
+    pub best_h: Option<Cost>,
+    /// Consecutive lateral moves of `HillClimbing`.
+    pub lateral: usize,
+    /// Schedule of `SimulatedAnnealing`, without it the schedule restarts from `iteration`.
+    pub annealing: Option<AnnealingProgress>,
+    /// Seed the random generator was reset to when the attempt stopped, `resume` starts from it.
+    pub seed: Option<u64>,
+    /// Moves of `TabuSearch` still in the tabu list, oldest first: the state each one left and
+    /// the position of its perturbation in `perturbations`.
+    pub tabu: Vec<(State, usize)>,
+}
+
+impl<State, Cost> Progress<State, Cost> {
+    /// The progress of an attempt starting from `population`.
+    pub fn new(population: Vec<State>) -> Self {
+        Self {
+            population,
+            iteration: 0,
+            evaluated: 0,
+            best_h: None,
+            lateral: 0,
+            annealing: None,
+            seed: None,
+            tabu: Vec::new(),
+        }
+    }
+}
+
+/// How an attempt ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttemptOutcome {
+    /// The state has h = 0 and, when the goal has been verified, it is suitable.
+    GoalReached,
+    /// No neighbour improves the state, or the temperature of `SimulatedAnnealing` dropped to zero.
+    LocalOptimum,
+    /// A limit, or the `max_iter` of the population algorithms, stopped the attempt.
+    BudgetExhausted(Limit),
+    /// The population died, the state is its best member, or a state with h = 0 is not suitable.
+    Failed,
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "P::State: serde::Serialize, P::Cost: serde::Serialize",
+        deserialize = "P::State: serde::Deserialize<'de>, P::Cost: serde::Deserialize<'de>"
+    ))
+)]
 pub struct AttemptResult<P>
 where
     P: CostructSolution,
@@ -15,21 +77,88 @@ where
     pub state: P::State,
     pub h: P::Cost,
     pub iterations: usize,
+    /// States whose heuristic has been computed, as counted by the limits.
+    pub evaluated: usize,
+    /// The limit that interrupted the attempt, `state` is the best one found until then.
+    pub limit: Option<Limit>,
+    /// Set with `limit`, to resume the attempt.
+    pub progress: Option<Progress<P::State, P::Cost>>,
+    pub outcome: AttemptOutcome,
 }
 
 impl<P> AttemptResult<P>
 where
     P: CostructSolution,
 {
+    /// An attempt ended in `state`: a goal if h = 0, a local optimum otherwise.
     pub fn new(state: P::State, h: P::Cost, iterations: usize) -> Self {
+        let outcome = if h <= P::Cost::default() {
+            AttemptOutcome::GoalReached
+        } else {
+            AttemptOutcome::LocalOptimum
+        };
         Self {
             state: state,
             h: h,
             iterations: iterations,
+            evaluated: 0,
+            limit: None,
+            progress: None,
+            outcome,
         }
     }
+
+    pub fn stopped(state: P::State, h: P::Cost, iterations: usize, limit: Limit) -> Self {
+        Self {
+            limit: Some(limit),
+            outcome: AttemptOutcome::BudgetExhausted(limit),
+            ..Self::new(state, h, iterations)
+        }
+    }
+
+    pub fn with_outcome(mut self, outcome: AttemptOutcome) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    pub fn is_goal(&self) -> bool {
+        self.outcome == AttemptOutcome::GoalReached
+    }
+
+    /// Downgrades a goal whose state is not suitable to `AttemptOutcome::Failed`.
+    pub fn verify(self, problem: &P) -> Self
+    where
+        P: SuitableState,
+    {
+        self.verify_with(problem, Some(P::is_suitable))
+    }
+
+    pub(crate) fn verify_with(mut self, problem: &P, goal_check: Option<GoalCheck<P>>) -> Self {
+        if self.is_goal() && goal_check.is_some_and(|is_goal| !is_goal(problem, &self.state)) {
+            self.outcome = AttemptOutcome::Failed;
+        }
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Progress<P::State, P::Cost>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn with_evaluated(mut self, evaluated: usize) -> Self {
+        self.evaluated = evaluated;
+        self
+    }
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "P::State: serde::Serialize, P::Cost: serde::Serialize",
+        deserialize = "P::State: serde::Deserialize<'de>, P::Cost: serde::Deserialize<'de>"
+    ))
+)]
 pub struct ResolverResult<P>
 where
     P: CostructSolution,
@@ -37,7 +166,12 @@ where
     pub state: P::State,
     pub h: P::Cost,
     pub iterations: usize,
+    /// States whose heuristic has been computed, in every attempt.
+    pub evaluated: usize,
     pub duration: Duration,
+    pub limit: Option<Limit>,
+    pub progress: Option<Progress<P::State, P::Cost>>,
+    pub outcome: AttemptOutcome,
 }
 
 impl<P> fmt::Debug for ResolverResult<P>
@@ -48,7 +182,12 @@ where
         writeln!(f, "state:\n{:?}", self.state)?;
         writeln!(f, "h: {:?}", self.h)?;
         writeln!(f, "iterations: {:?}", self.iterations)?;
-        write!(f, "duration: {:?}", self.duration)
+        writeln!(f, "duration: {:?}", self.duration)?;
+        write!(f, "outcome: {:?}", self.outcome)?;
+        if let Some(limit) = self.limit {
+            write!(f, "\n{}", limit)?;
+        }
+        Ok(())
     }
 }
 
@@ -61,17 +200,29 @@ where
             state: inner.state,
             h: inner.h,
             iterations: inner.iterations,
+            evaluated: inner.evaluated,
             duration: start.elapsed(),
+            limit: inner.limit,
+            progress: inner.progress,
+            outcome: inner.outcome,
         }
     }
+
+    pub fn is_goal(&self) -> bool {
+        self.outcome == AttemptOutcome::GoalReached
+    }
 }
 
+/// Tells whether a state with h = 0 is really a goal, see `Resolver::verified`.
+pub type GoalCheck<P> = fn(&P, &<P as crate::problem::Problem>::State) -> bool;
+
 pub struct Resolver<I, P>
 where
-    P: CostructSolution,
+    P: Utility,
     I: ImprovingAlgorithm<P>,
 {
     algo: I,
+    goal_check: Option<GoalCheck<P>>,
     _problem: PhantomData<P>,
 }
 
@@ -80,9 +231,11 @@ where
     P: RandomState + Utility,
     I: ImprovingAlgorithm<P>,
 {
+    /// Reports every state with h = 0 as a goal, see `verified`.
     pub fn new(algo: I) -> Self {
         Self {
             algo: algo,
+            goal_check: None,
             _problem: PhantomData,
         }
     }
@@ -90,34 +243,170 @@ where
 
 impl<I, P> Resolver<I, P>
 where
-    P: CostructSolution,
+    P: Utility,
     I: ImprovingAlgorithm<P>,
 {
+    /// Checks with `SuitableState::is_suitable` every state with h = 0 before reporting it as a goal,
+    /// the others end as `AttemptOutcome::Failed`.
+    pub fn verified(mut self) -> Self
+    where
+        P: SuitableState,
+    {
+        self.goal_check = Some(P::is_suitable);
+        self
+    }
+
     pub fn resolve(&mut self, problem: &P) -> ResolverResult<P> {
+        self.resolve_observed(problem, &mut NoObserver)
+    }
+
+    pub fn resolve_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> ResolverResult<P> {
+        self.try_resolve_observed(problem, observer)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_resolve(&mut self, problem: &P) -> Result<ResolverResult<P>> {
+        self.try_resolve_observed(problem, &mut NoObserver)
+    }
+
+    pub fn try_resolve_observed(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<ResolverResult<P>> {
+        let start = Instant::now();
+        let inner = self.attempt(problem, observer)?;
+        Ok(ResolverResult::from_inner(start, inner))
+    }
+
+    /// Continues an attempt interrupted by a limit, see `ResolverResult::progress`.
+    pub fn resume(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+    ) -> ResolverResult<P> {
+        self.try_resume(problem, progress)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_resume(
+        &mut self,
+        problem: &P,
+        progress: Progress<P::State, P::Cost>,
+    ) -> Result<ResolverResult<P>> {
         let start = Instant::now();
-        let inner = self.algo.attempt(problem);
-        return ResolverResult::from_inner(start, inner);
+        let inner = self
+            .algo
+            .try_resume_observed(problem, progress, &mut NoObserver)?
+            .verify_with(problem, self.goal_check);
+        Ok(ResolverResult::from_inner(start, inner))
     }
 
     pub fn resolve_restart(&mut self, problem: &P, max_restarts: usize) -> ResolverResult<P> {
+        self.resolve_restart_observed(problem, max_restarts, &mut NoObserver)
+    }
+
+    pub fn resolve_restart_observed(
+        &mut self,
+        problem: &P,
+        max_restarts: usize,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> ResolverResult<P> {
+        self.try_resolve_restart_observed(problem, max_restarts, observer)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_resolve_restart(
+        &mut self,
+        problem: &P,
+        max_restarts: usize,
+    ) -> Result<ResolverResult<P>> {
+        self.try_resolve_restart_observed(problem, max_restarts, &mut NoObserver)
+    }
+
+    pub fn try_resolve_restart_observed(
+        &mut self,
+        problem: &P,
+        max_restarts: usize,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<ResolverResult<P>> {
         let start = Instant::now();
-        let mut result = self.algo.attempt(problem);
+        let limits = self.algo.limits().clone();
+        self.algo.set_limits(limits.start());
+        let result = self.restart(problem, max_restarts, observer);
+        self.algo.set_limits(limits);
+        Ok(ResolverResult::from_inner(start, result?))
+    }
+
+    fn attempt(
+        &mut self,
+        problem: &P,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let result = self.algo.try_attempt_observed(problem, observer)?;
+        Ok(result.verify_with(problem, self.goal_check))
+    }
+
+    fn restart(
+        &mut self,
+        problem: &P,
+        max_restarts: usize,
+        observer: &mut dyn SearchObserver<P>,
+    ) -> Result<AttemptResult<P>> {
+        let mut result = self.attempt(problem, observer)?;
+        let mut last_limit = result.limit;
         for _ in 1..max_restarts {
-            let new_result = self.algo.attempt(problem);
-            if new_result.h <= P::Cost::default() {
-                // TODO: check if it is a goal state
-                result.state = new_result.state;
-                result.h = new_result.h;
-                result.iterations += new_result.iterations;
-                let result = ResolverResult::from_inner(start, result);
-                return result;
-            }
-            if new_result.h < result.h {
-                result.state = new_result.state;
-                result.h = new_result.h;
+            if result.is_goal() || matches!(last_limit, Some(Limit::Time | Limit::Cancelled)) {
+                break;
             }
-            result.iterations += new_result.iterations;
+            let new_result = self.attempt(problem, observer)?;
+            last_limit = new_result.limit;
+            merge_restart(&mut result, new_result);
         }
-        return ResolverResult::from_inner(start, result);
+        Ok(result)
+    }
+}
+
+/// Adds a new attempt to the result of the previous restarts: the best state is kept, preferring goals
+/// and putting failed attempts last, with the outcome, the limit and the progress of its attempt.
+pub(crate) fn merge_restart<P>(result: &mut AttemptResult<P>, new_result: AttemptResult<P>)
+where
+    P: CostructSolution,
+{
+    let rank = |result: &AttemptResult<P>| match result.outcome {
+        AttemptOutcome::GoalReached => 2,
+        AttemptOutcome::Failed => 0,
+        _ => 1,
+    };
+    let iterations = result.iterations + new_result.iterations;
+    let evaluated = result.evaluated + new_result.evaluated;
+    let better = match rank(&new_result).cmp(&rank(result)) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => new_result.h < result.h,
+    };
+    if better {
+        *result = new_result;
+    }
+    result.iterations = iterations;
+    result.evaluated = evaluated;
+}
+
+/// The limits apply to every attempt, the time budget and the cancel token to all the restarts together.
+impl<I, P> LimitSearch for Resolver<I, P>
+where
+    P: Utility,
+    I: ImprovingAlgorithm<P>,
+{
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.algo.set_limits(limits);
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        self.algo.limits()
     }
 }
@@ -1,3 +1,6 @@
+pub mod error;
 pub mod improve;
+pub mod limits;
+pub mod observer;
 pub mod problem;
 pub mod statexplorer;
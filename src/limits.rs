@@ -0,0 +1,134 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// The limit that stopped a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Limit {
+    /// Expanded nodes for the explorers, iterations for the improving algorithms.
+    Expansions,
+    /// Generated nodes for the explorers, evaluated states for the improving algorithms.
+    Generated,
+    Memory,
+    Time,
+    Cancelled,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Limit::Expansions => "expansion limit reached",
+            Limit::Generated => "generation limit reached",
+            Limit::Memory => "memory limit reached",
+            Limit::Time => "time limit reached",
+            Limit::Cancelled => "search cancelled",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Budget of a search. Every limit is disabled by default.
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    pub max_expansions: Option<usize>,
+    pub max_generated: Option<usize>,
+    /// Bytes allocated in the arena, only the explorers can honor it.
+    pub max_memory: Option<usize>,
+    /// Wall-clock time available from the start of the search.
+    pub time_budget: Option<Duration>,
+    pub deadline: Option<Instant>,
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl SearchLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_expansions(mut self, max: usize) -> Self {
+        self.max_expansions = Some(max);
+        self
+    }
+
+    pub fn with_max_generated(mut self, max: usize) -> Self {
+        self.max_generated = Some(max);
+        self
+    }
+
+    pub fn with_max_memory(mut self, bytes: usize) -> Self {
+        self.max_memory = Some(bytes);
+        self
+    }
+
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// The search stops as soon as it sees the token set to true.
+    pub fn with_cancel_token(mut self, token: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|token| token.load(Ordering::Relaxed))
+    }
+
+    /// The limits of a search starting now: the time budget becomes a deadline.
+    pub fn start(&self) -> Self {
+        let mut limits = self.clone();
+        if let Some(budget) = limits.time_budget.take() {
+            let deadline = Instant::now() + budget;
+            limits.deadline = Some(limits.deadline.map_or(deadline, |d| d.min(deadline)));
+        }
+        limits
+    }
+
+    /// Moves the expansion and generation limits after counters already spent by a resumed search.
+    pub fn offset(mut self, expanded: usize, generated: usize) -> Self {
+        self.max_expansions = self.max_expansions.map(|max| max.saturating_add(expanded));
+        self.max_generated = self.max_generated.map(|max| max.saturating_add(generated));
+        self
+    }
+
+    /// The first limit exceeded by a search with these counters, if any.
+    pub fn check(&self, expanded: usize, generated: usize, memory: usize) -> Option<Limit> {
+        if self.is_cancelled() {
+            return Some(Limit::Cancelled);
+        }
+        if self.max_expansions.is_some_and(|max| expanded >= max) {
+            return Some(Limit::Expansions);
+        }
+        if self.max_generated.is_some_and(|max| generated >= max) {
+            return Some(Limit::Generated);
+        }
+        if self.max_memory.is_some_and(|max| memory >= max) {
+            return Some(Limit::Memory);
+        }
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Some(Limit::Time);
+        }
+        None
+    }
+}
+
+/// Explorers and algorithms whose searches can be bounded by `SearchLimits`.
+pub trait LimitSearch {
+    fn set_limits(&mut self, limits: SearchLimits);
+
+    fn limits(&self) -> &SearchLimits;
+}
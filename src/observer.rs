@@ -0,0 +1,432 @@
+use std::fmt::{Debug, Display};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::problem::Utility;
+use crate::statexplorer::node::Node;
+
+/// Receives the events produced by the explorers and by the improving algorithms.
+/// Every hook does nothing by default, so an observer only implements the events it needs.
+pub trait SearchObserver<P>
+where
+    P: Utility,
+{
+    /// A node has been taken from the frontier and is going to be expanded.
+    fn on_expand(&mut self, _iteration: usize, _node: &Node<P>) {}
+    /// A successor has been added to the frontier.
+    fn on_generate(&mut self, _node: &Node<P>) {}
+    /// `new` has taken the place of `old` in the frontier because it reaches the same state with a lower cost.
+    fn on_replace(&mut self, _old: &Node<P>, _new: &Node<P>) {}
+    /// A successor of `parent` has been discarded because its state was already reached with a lower cost.
+    fn on_duplicate(&mut self, _parent: &Node<P>, _state: &P::State, _g: P::Cost) {}
+    /// A goal state has been found, `cost` is the path cost for the explorers and h for the improving algorithms.
+    fn on_goal(&mut self, _iteration: usize, _state: &P::State, _cost: P::Cost) {}
+    /// An improving algorithm starts a new iteration from `state`.
+    fn on_iteration(&mut self, _iteration: usize, _state: &P::State, _h: P::Cost) {}
+    /// An improving algorithm has found a state better than every previous one.
+    fn on_improvement(&mut self, _iteration: usize, _state: &P::State, _h: P::Cost) {}
+    /// The quantities an improving algorithm tracks, once per iteration after `on_iteration`.
+    fn on_statistics(&mut self, _stats: &IterationStats<P::Cost>) {}
+}
+
+impl<P, O> SearchObserver<P> for &mut O
+where
+    P: Utility,
+    O: SearchObserver<P> + ?Sized,
+{
+    fn on_expand(&mut self, iteration: usize, node: &Node<P>) {
+        (**self).on_expand(iteration, node)
+    }
+
+    fn on_generate(&mut self, node: &Node<P>) {
+        (**self).on_generate(node)
+    }
+
+    fn on_replace(&mut self, old: &Node<P>, new: &Node<P>) {
+        (**self).on_replace(old, new)
+    }
+
+    fn on_duplicate(&mut self, parent: &Node<P>, state: &P::State, g: P::Cost) {
+        (**self).on_duplicate(parent, state, g)
+    }
+
+    fn on_goal(&mut self, iteration: usize, state: &P::State, cost: P::Cost) {
+        (**self).on_goal(iteration, state, cost)
+    }
+
+    fn on_iteration(&mut self, iteration: usize, state: &P::State, h: P::Cost) {
+        (**self).on_iteration(iteration, state, h)
+    }
+
+    fn on_improvement(&mut self, iteration: usize, state: &P::State, h: P::Cost) {
+        (**self).on_improvement(iteration, state, h)
+    }
+
+    fn on_statistics(&mut self, stats: &IterationStats<P::Cost>) {
+        (**self).on_statistics(stats)
+    }
+}
+
+/// Forwards every event to both observers.
+impl<P, A, B> SearchObserver<P> for (A, B)
+where
+    P: Utility,
+    A: SearchObserver<P>,
+    B: SearchObserver<P>,
+{
+    fn on_expand(&mut self, iteration: usize, node: &Node<P>) {
+        self.0.on_expand(iteration, node);
+        self.1.on_expand(iteration, node);
+    }
+
+    fn on_generate(&mut self, node: &Node<P>) {
+        self.0.on_generate(node);
+        self.1.on_generate(node);
+    }
+
+    fn on_replace(&mut self, old: &Node<P>, new: &Node<P>) {
+        self.0.on_replace(old, new);
+        self.1.on_replace(old, new);
+    }
+
+    fn on_duplicate(&mut self, parent: &Node<P>, state: &P::State, g: P::Cost) {
+        self.0.on_duplicate(parent, state, g);
+        self.1.on_duplicate(parent, state, g);
+    }
+
+    fn on_goal(&mut self, iteration: usize, state: &P::State, cost: P::Cost) {
+        self.0.on_goal(iteration, state, cost);
+        self.1.on_goal(iteration, state, cost);
+    }
+
+    fn on_iteration(&mut self, iteration: usize, state: &P::State, h: P::Cost) {
+        self.0.on_iteration(iteration, state, h);
+        self.1.on_iteration(iteration, state, h);
+    }
+
+    fn on_improvement(&mut self, iteration: usize, state: &P::State, h: P::Cost) {
+        self.0.on_improvement(iteration, state, h);
+        self.1.on_improvement(iteration, state, h);
+    }
+
+    fn on_statistics(&mut self, stats: &IterationStats<P::Cost>) {
+        self.0.on_statistics(stats);
+        self.1.on_statistics(stats);
+    }
+}
+
+/// An iteration of an improving algorithm, the quantities it does not track are `None`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IterationStats<Cost> {
+    pub iteration: usize,
+    /// States evaluated since the start of the attempt.
+    pub evaluated: usize,
+    pub best_h: Cost,
+    /// h of the current state, of the best individual for the population algorithms.
+    pub current_h: Cost,
+    pub temperature: Option<f64>,
+    /// Uphill moves accepted over the ones proposed.
+    pub acceptance: Option<f64>,
+    /// Fraction of distinct heuristic values in the population, see `diversity`.
+    pub diversity: Option<f64>,
+}
+
+impl<Cost> IterationStats<Cost> {
+    pub fn new(iteration: usize, evaluated: usize, best_h: Cost, current_h: Cost) -> Self {
+        Self {
+            iteration,
+            evaluated,
+            best_h,
+            current_h,
+            temperature: None,
+            acceptance: None,
+            diversity: None,
+        }
+    }
+}
+
+/// Fraction of distinct heuristic values in a population, from 1 / k to 1.
+pub fn diversity<Cost: Ord + Copy>(costs: &[Cost]) -> f64 {
+    let mut distinct = costs.to_vec();
+    distinct.sort();
+    distinct.dedup();
+    distinct.len() as f64 / costs.len().max(1) as f64
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoObserver;
+
+impl<P> SearchObserver<P> for NoObserver where P: Utility {}
+
+/// Prints the expanded nodes and the iterations of the improving algorithms on stderr.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogObserver;
+
+impl<P> SearchObserver<P> for LogObserver
+where
+    P: Utility<State: Debug, Cost: Debug>,
+{
+    fn on_expand(&mut self, iteration: usize, node: &Node<P>) {
+        eprintln!(
+            "I: {} cost: {:?} current state:\n{:?}",
+            iteration,
+            node.get_g_cost(),
+            node.get_state()
+        );
+    }
+
+    fn on_iteration(&mut self, iteration: usize, state: &P::State, h: P::Cost) {
+        eprintln!(
+            "\nIteration: {}\ncurrent state:\n{:?}\nh:{:?}",
+            iteration, state, h
+        );
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountingObserver {
+    pub expanded: usize,
+    pub generated: usize,
+    pub replaced: usize,
+    pub duplicates: usize,
+    pub goals: usize,
+    pub iterations: usize,
+    pub improvements: usize,
+}
+
+impl<P> SearchObserver<P> for CountingObserver
+where
+    P: Utility,
+{
+    fn on_expand(&mut self, _iteration: usize, _node: &Node<P>) {
+        self.expanded += 1;
+    }
+
+    fn on_generate(&mut self, _node: &Node<P>) {
+        self.generated += 1;
+    }
+
+    fn on_replace(&mut self, _old: &Node<P>, _new: &Node<P>) {
+        self.replaced += 1;
+    }
+
+    fn on_duplicate(&mut self, _parent: &Node<P>, _state: &P::State, _g: P::Cost) {
+        self.duplicates += 1;
+    }
+
+    fn on_goal(&mut self, _iteration: usize, _state: &P::State, _cost: P::Cost) {
+        self.goals += 1;
+    }
+
+    fn on_iteration(&mut self, _iteration: usize, _state: &P::State, _h: P::Cost) {
+        self.iterations += 1;
+    }
+
+    fn on_improvement(&mut self, _iteration: usize, _state: &P::State, _h: P::Cost) {
+        self.improvements += 1;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SearchEvent<State, Cost> {
+    Expand {
+        iteration: usize,
+        state: State,
+        g: Cost,
+        h: Cost,
+        depth: usize,
+    },
+    Generate {
+        state: State,
+        g: Cost,
+        h: Cost,
+        depth: usize,
+    },
+    Replace {
+        state: State,
+        old_g: Cost,
+        new_g: Cost,
+    },
+    Duplicate {
+        state: State,
+        g: Cost,
+    },
+    Goal {
+        iteration: usize,
+        state: State,
+        cost: Cost,
+    },
+    Iteration {
+        iteration: usize,
+        state: State,
+        h: Cost,
+    },
+    Improvement {
+        iteration: usize,
+        state: State,
+        h: Cost,
+    },
+}
+
+/// Collects every event, in order, cloning the states involved.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceObserver<State, Cost> {
+    pub events: Vec<SearchEvent<State, Cost>>,
+}
+
+impl<State, Cost> TraceObserver<State, Cost> {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+impl<State, Cost> Default for TraceObserver<State, Cost> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> SearchObserver<P> for TraceObserver<P::State, P::Cost>
+where
+    P: Utility<State: Clone>,
+{
+    fn on_expand(&mut self, iteration: usize, node: &Node<P>) {
+        self.events.push(SearchEvent::Expand {
+            iteration,
+            state: node.get_state().clone(),
+            g: node.get_g_cost(),
+            h: node.get_h_cost(),
+            depth: node.get_depth(),
+        });
+    }
+
+    fn on_generate(&mut self, node: &Node<P>) {
+        self.events.push(SearchEvent::Generate {
+            state: node.get_state().clone(),
+            g: node.get_g_cost(),
+            h: node.get_h_cost(),
+            depth: node.get_depth(),
+        });
+    }
+
+    fn on_replace(&mut self, old: &Node<P>, new: &Node<P>) {
+        self.events.push(SearchEvent::Replace {
+            state: new.get_state().clone(),
+            old_g: old.get_g_cost(),
+            new_g: new.get_g_cost(),
+        });
+    }
+
+    fn on_duplicate(&mut self, _parent: &Node<P>, state: &P::State, g: P::Cost) {
+        self.events.push(SearchEvent::Duplicate {
+            state: state.clone(),
+            g,
+        });
+    }
+
+    fn on_goal(&mut self, iteration: usize, state: &P::State, cost: P::Cost) {
+        self.events.push(SearchEvent::Goal {
+            iteration,
+            state: state.clone(),
+            cost,
+        });
+    }
+
+    fn on_iteration(&mut self, iteration: usize, state: &P::State, h: P::Cost) {
+        self.events.push(SearchEvent::Iteration {
+            iteration,
+            state: state.clone(),
+            h,
+        });
+    }
+
+    fn on_improvement(&mut self, iteration: usize, state: &P::State, h: P::Cost) {
+        self.events.push(SearchEvent::Improvement {
+            iteration,
+            state: state.clone(),
+            h,
+        });
+    }
+}
+
+/// `IterationStats` with the time elapsed since the creation of the `HistoryObserver`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryRecord<Cost> {
+    pub elapsed: Duration,
+    pub stats: IterationStats<Cost>,
+}
+
+/// Records the statistics of every iteration, to plot the convergence of an improving algorithm.
+#[derive(Debug, Clone)]
+pub struct HistoryObserver<Cost> {
+    start: Instant,
+    pub records: Vec<HistoryRecord<Cost>>,
+}
+
+impl<Cost> HistoryObserver<Cost> {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            records: Vec::new(),
+        }
+    }
+}
+
+impl<Cost> Default for HistoryObserver<Cost> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Cost: Display> HistoryObserver<Cost> {
+    pub const CSV_HEADER: &'static str =
+        "iteration,elapsed,evaluated,best_h,current_h,temperature,acceptance,diversity";
+
+    /// One line for every record after `CSV_HEADER`, the elapsed time in seconds and the
+    /// quantities not tracked left empty.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        fn optional(value: Option<f64>) -> String {
+            value.map(|v| v.to_string()).unwrap_or_default()
+        }
+        writeln!(writer, "{}", Self::CSV_HEADER)?;
+        for HistoryRecord { elapsed, stats } in &self.records {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{}",
+                stats.iteration,
+                elapsed.as_secs_f64(),
+                stats.evaluated,
+                stats.best_h,
+                stats.current_h,
+                optional(stats.temperature),
+                optional(stats.acceptance),
+                optional(stats.diversity)
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = Vec::new();
+        self.write_csv(&mut csv)
+            .expect("writing to a Vec does not fail");
+        String::from_utf8(csv).expect("the records are valid UTF-8")
+    }
+}
+
+impl<P> SearchObserver<P> for HistoryObserver<P::Cost>
+where
+    P: Utility,
+{
+    fn on_statistics(&mut self, stats: &IterationStats<P::Cost>) {
+        self.records.push(HistoryRecord {
+            elapsed: self.start.elapsed(),
+            stats: *stats,
+        });
+    }
+}
@@ -1,7 +1,9 @@
-use std::ops::Add;
+use std::{hash::Hash, ops::Add};
 
 use rand::{seq::IteratorRandom, Rng};
 
+use crate::error::{Error, Result};
+
 pub trait Problem {
     type State;
 }
@@ -29,17 +31,82 @@ pub trait CostructSolution: Problem {
 
 pub trait Utility: CostructSolution {
     fn heuristic(&self, state: &Self::State) -> Self::Cost;
+
+    /// Used by the `try_*` entry points, problems that can reject a state override it.
+    fn try_heuristic(&self, state: &Self::State) -> Result<Self::Cost> {
+        Ok(self.heuristic(state))
+    }
+}
+
+/// Several costs to minimize at once, compared by Pareto dominance instead of a total order.
+pub trait MultiObjective: Problem {
+    /// The costs of the state, always with the same length.
+    fn objectives(&self, state: &Self::State) -> Vec<f64>;
+
+    /// Used by the `try_*` entry points, problems that can reject a state override it.
+    fn try_objectives(&self, state: &Self::State) -> Result<Vec<f64>> {
+        Ok(self.objectives(state))
+    }
+}
+
+/// A real-valued objective to minimize inside a box, for the optimizers of `improve::continuous`.
+pub trait ContinuousProblem {
+    fn dimension(&self) -> usize;
+
+    /// Lower and upper bound of every coordinate.
+    fn bounds(&self) -> Vec<(f64, f64)>;
+
+    fn objective(&self, x: &[f64]) -> f64;
+
+    /// Used by the `try_*` entry points, problems that can reject a point override it.
+    fn try_objective(&self, x: &[f64]) -> Result<f64> {
+        Ok(self.objective(x))
+    }
 }
 
 pub trait SuitableState: Problem {
     fn is_suitable(&self, state: &Self::State) -> bool;
 }
 
+/// The key of a step of the construction in the pheromone table of `AntColony`, e.g. the pair
+/// of cities of an edge. Steps with the same feature share their pheromone.
+pub trait PheromoneFeature: CostructSolution {
+    type Feature: Eq + Hash + Clone;
+
+    fn feature(&self, state: &Self::State, action: &Self::Action) -> Self::Feature;
+
+    /// A priori attractiveness of the step, e.g. the inverse of its length, weighted by `beta`.
+    fn desirability(&self, _state: &Self::State, _action: &Self::Action) -> f64 {
+        1.0
+    }
+}
+
 pub trait StatePerturbation: Problem {
     type Perturbation;
 
     fn perturbations(&self, state: &Self::State) -> impl Iterator<Item = Self::Perturbation>;
     fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State;
+
+    /// Applies the perturbation to the state itself, problems with states expensive to copy override it.
+    fn perturb_in_place(&self, state: &mut Self::State, action: &Self::Perturbation) {
+        *state = self.perturb(state, action);
+    }
+
+    /// Delta evaluation: the heuristic of `state` after the perturbation, given `h`, the heuristic
+    /// of `state`, computed without building the new state. The local search algorithms use it
+    /// whenever it returns `Some`, and then build only the states they move to; by default every
+    /// neighbour is built and evaluated with `Utility::heuristic`.
+    fn delta_heuristic(
+        &self,
+        _state: &Self::State,
+        _h: <Self as CostructSolution>::Cost,
+        _action: &Self::Perturbation,
+    ) -> Option<Result<<Self as CostructSolution>::Cost>>
+    where
+        Self: Utility,
+    {
+        None
+    }
 }
 
 pub trait RandomPerturbation: StatePerturbation {
@@ -63,6 +130,39 @@ where
     }
 }
 
+/// Problems with several neighbourhood structures, used by `VariableNeighborhoodSearch`.
+/// The neighbourhoods are numbered from 0, usually from the smallest to the largest.
+pub trait Neighborhoods: Problem {
+    type Move;
+
+    fn neighborhoods(&self) -> usize;
+    fn neighbors(&self, k: usize, state: &Self::State) -> impl Iterator<Item = Self::Move>;
+    fn apply(&self, state: &Self::State, m: &Self::Move) -> Self::State;
+}
+
+pub trait RandomNeighbor: Neighborhoods {
+    fn random_neighbor<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        k: usize,
+        state: &Self::State,
+    ) -> Option<Self::Move>;
+}
+
+impl<T> RandomNeighbor for T
+where
+    T: Neighborhoods,
+{
+    fn random_neighbor<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        k: usize,
+        state: &Self::State,
+    ) -> Option<Self::Move> {
+        self.neighbors(k, state).choose(rng)
+    }
+}
+
 pub trait RandomAction: CostructSolution {
     fn random_action<R: Rng + ?Sized>(
         &self,
@@ -86,25 +186,68 @@ where
 
 pub trait RandomState: CostructSolution {
     fn random_state<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::State;
+
+    /// Used by the `try_*` entry points, generators that can fail override it.
+    fn try_random_state<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Self::State> {
+        Ok(self.random_state(rng))
+    }
 }
 
+/// Random walks from `InitState` ending in a dead end before `RandomState` gives up.
+pub const RANDOM_WALK_RESTARTS: usize = 1000;
+
+/// Actions of every random walk of `RandomState` before it starts again from `InitState`.
+pub const RANDOM_WALK_STEPS: usize = 10_000;
+
 impl<T> RandomState for T
 where
     T: SuitableState + RandomAction + InitState,
 {
+    /// Panics with `Error::NoInitialState` when none of the `RANDOM_WALK_RESTARTS` walks, of at most
+    /// `RANDOM_WALK_STEPS` actions, reaches a suitable state, instead of walking forever.
     fn random_state<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::State {
-        // TODO: What if the while loop runs indefinitely?
-        let mut state = self.init_state();
-        while !self.is_suitable(&state) {
-            if let Some(action) = self.random_action(rng, &state) {
-                state = self.result(&state, &action).0;
-            } else {
-                state = self.init_state();
+        self.try_random_state(rng)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_random_state<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Self::State> {
+        random_walk(self, rng, RANDOM_WALK_RESTARTS, Some(RANDOM_WALK_STEPS))
+    }
+}
+
+/// Random actions from `InitState` until a suitable state, starting again from a dead end or
+/// after `max_steps` actions, at most `max_restarts` times.
+pub fn random_walk<P, R>(
+    problem: &P,
+    rng: &mut R,
+    max_restarts: usize,
+    max_steps: Option<usize>,
+) -> Result<P::State>
+where
+    P: SuitableState + RandomAction + InitState + ?Sized,
+    R: Rng + ?Sized,
+{
+    for _ in 0..=max_restarts {
+        let mut state = problem.init_state();
+        let mut steps = 0;
+        while !problem.is_suitable(&state) {
+            if max_steps.is_some_and(|max| steps >= max) {
+                break;
             }
+            match problem.random_action(rng, &state) {
+                Some(action) => state = problem.result(&state, &action).0,
+                None => break,
+            }
+            steps += 1;
+        }
+        if problem.is_suitable(&state) {
+            return Ok(state);
         }
-
-        state
     }
+    Err(Error::NoInitialState(format!(
+        "no suitable state after {} random walks",
+        max_restarts + 1
+    )))
 }
 
 pub trait Crossover: Problem {
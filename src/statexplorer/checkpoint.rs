@@ -0,0 +1,40 @@
+use crate::statexplorer::stats::SearchStats;
+
+/// A node saved in a `Checkpoint`, linked to its parent through `id`s instead of references.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeRecord<State, Action, Cost> {
+    pub id: usize,
+    pub parent: Option<usize>,
+    pub state: State,
+    pub action: Option<Action>,
+    pub g: Cost,
+    pub h: Cost,
+    pub depth: usize,
+}
+
+/// Everything an `Explorer` needs to continue an interrupted search, without references into its arena.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint<State, Action, Cost> {
+    /// The frontier nodes and their ancestors, every parent comes before its children.
+    pub nodes: Vec<NodeRecord<State, Action, Cost>>,
+    /// Ids of the frontier nodes, in the order that rebuilds the frontier.
+    pub frontier: Vec<usize>,
+    pub explored: Vec<(State, Cost)>,
+    pub reopened: Vec<(State, usize)>,
+    pub next_id: usize,
+    pub depth_limit: Option<usize>,
+    /// Set when the search is an `iterative_search`, that `resume` deepens after `depth_limit`.
+    pub deepening: Option<Deepening<State>>,
+    pub max_frontier_size: usize,
+    pub stats: SearchStats,
+}
+
+/// The iterative deepening a `Checkpoint` was taken in.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deepening<State> {
+    pub init_state: State,
+    pub max_limit: usize,
+}
@@ -0,0 +1,343 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{Debug, Display},
+    hash::Hash,
+    io::{self, Write},
+};
+
+use crate::observer::SearchObserver;
+use crate::problem::Utility;
+use crate::statexplorer::node::Node;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeStatus {
+    /// Still waiting in the frontier when the search ended.
+    Frontier,
+    Expanded,
+    Goal,
+    /// Replaced in the frontier by a cheaper node for the same state.
+    Dead,
+    /// Discarded when generated because its state was already reached with a lower cost.
+    Duplicate,
+}
+
+impl NodeStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            NodeStatus::Frontier => "frontier",
+            NodeStatus::Expanded => "expanded",
+            NodeStatus::Goal => "goal",
+            NodeStatus::Dead => "dead",
+            NodeStatus::Duplicate => "duplicate",
+        }
+    }
+}
+
+/// A generated node, states and actions are kept in their `Debug` representation and costs in their
+/// `Display` one.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeNode {
+    pub id: usize,
+    pub parent: Option<usize>,
+    pub state: String,
+    pub action: Option<String>,
+    pub g: String,
+    pub h: Option<String>,
+    pub f: Option<String>,
+    pub depth: usize,
+    /// Iteration in which the node has been expanded.
+    pub expanded_at: Option<usize>,
+    pub status: NodeStatus,
+}
+
+/// Frontier and explored set at the beginning of an iteration, as ids of `TreeNode`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    pub iteration: usize,
+    pub frontier: Vec<usize>,
+    pub explored: Vec<usize>,
+}
+
+/// Observer that rebuilds the search tree of an explorer and exports it as Graphviz DOT or JSON.
+/// The nodes are told apart by the ids the explorer assigns to them, the explored set by the
+/// states themselves.
+pub struct TreeRecorder<S> {
+    nodes: Vec<TreeNode>,
+    snapshots: Vec<Snapshot>,
+    take_snapshots: bool,
+    by_node_id: HashMap<usize, usize>,
+    frontier: BTreeSet<usize>,
+    explored: HashMap<S, usize>,
+    expanding: Option<usize>,
+}
+
+impl<S> TreeRecorder<S> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            snapshots: Vec::new(),
+            take_snapshots: false,
+            by_node_id: HashMap::new(),
+            frontier: BTreeSet::new(),
+            explored: HashMap::new(),
+            expanding: None,
+        }
+    }
+
+    /// Also records the frontier and the explored set of every iteration.
+    pub fn with_snapshots() -> Self {
+        Self {
+            take_snapshots: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn nodes(&self) -> &[TreeNode] {
+        &self.nodes
+    }
+
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    pub fn write_dot<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "digraph search {{")?;
+        writeln!(w, "  node [shape=box, fontname=\"monospace\"];")?;
+        for node in self.nodes.iter() {
+            let mut label = format!("{}\\ng={}", dot_escape(&node.state), dot_escape(&node.g));
+            if let (Some(h), Some(f)) = (&node.h, &node.f) {
+                label += &format!(" h={} f={}", dot_escape(h), dot_escape(f));
+            }
+            if let Some(iteration) = node.expanded_at {
+                label += &format!("\\n#{}", iteration);
+            }
+            let style = match node.status {
+                NodeStatus::Frontier => "",
+                NodeStatus::Expanded => ", style=filled, fillcolor=lightgray",
+                NodeStatus::Goal => ", style=\"filled,bold\", fillcolor=palegreen, peripheries=2",
+                NodeStatus::Dead => ", style=dashed, fontcolor=gray",
+                NodeStatus::Duplicate => ", style=dotted, fontcolor=gray",
+            };
+            writeln!(w, "  n{} [label=\"{}\"{}];", node.id, label, style)?;
+            if let Some(parent) = node.parent {
+                match &node.action {
+                    Some(action) => writeln!(
+                        w,
+                        "  n{} -> n{} [label=\"{}\"];",
+                        parent,
+                        node.id,
+                        dot_escape(action)
+                    )?,
+                    None => writeln!(w, "  n{} -> n{};", parent, node.id)?,
+                }
+            }
+        }
+        writeln!(w, "}}")
+    }
+
+    pub fn write_json<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "{{")?;
+        writeln!(w, "  \"nodes\": [")?;
+        for (i, node) in self.nodes.iter().enumerate() {
+            write!(
+                w,
+                "    {{\"id\": {}, \"parent\": {}, \"state\": {}, \"action\": {}, \"g\": {}, \"h\": {}, \"f\": {}, \"depth\": {}, \"expanded_at\": {}, \"status\": \"{}\"}}",
+                node.id,
+                json_option(node.parent),
+                json_string(&node.state),
+                node.action.as_deref().map_or("null".to_string(), json_string),
+                json_number(&node.g),
+                node.h.as_deref().map_or("null".to_string(), json_number),
+                node.f.as_deref().map_or("null".to_string(), json_number),
+                node.depth,
+                json_option(node.expanded_at),
+                node.status.name()
+            )?;
+            writeln!(w, "{}", separator(i, self.nodes.len()))?;
+        }
+        writeln!(w, "  ],")?;
+        writeln!(w, "  \"snapshots\": [")?;
+        for (i, snapshot) in self.snapshots.iter().enumerate() {
+            write!(
+                w,
+                "    {{\"iteration\": {}, \"frontier\": {:?}, \"explored\": {:?}}}",
+                snapshot.iteration, snapshot.frontier, snapshot.explored
+            )?;
+            writeln!(w, "{}", separator(i, self.snapshots.len()))?;
+        }
+        writeln!(w, "  ]")?;
+        writeln!(w, "}}")
+    }
+
+    fn push<P>(&mut self, node: &Node<P>, status: NodeStatus) -> usize
+    where
+        P: Utility<State: Debug, Action: Debug, Cost: Display>,
+    {
+        let id = self.nodes.len();
+        let parent = node
+            .get_parent()
+            .and_then(|p| self.by_node_id.get(&p.get_id()).copied());
+        self.nodes.push(TreeNode {
+            id,
+            parent,
+            state: format!("{:?}", node.get_state()),
+            action: node.get_action().map(|a| format!("{:?}", a)),
+            g: node.get_g_cost().to_string(),
+            h: Some(node.get_h_cost().to_string()),
+            f: Some(node.get_f_cost().to_string()),
+            depth: node.get_depth(),
+            expanded_at: None,
+            status,
+        });
+        self.by_node_id.insert(node.get_id(), id);
+        id
+    }
+}
+
+impl<S> Default for TreeRecorder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> SearchObserver<P> for TreeRecorder<P::State>
+where
+    P: Utility<State: Debug + Eq + Hash + Clone, Action: Debug, Cost: Display>,
+{
+    fn on_expand(&mut self, iteration: usize, node: &Node<P>) {
+        self.expanding = self.by_node_id.get(&node.get_id()).copied();
+        let Some(id) = self.expanding else {
+            return;
+        };
+        if self.take_snapshots {
+            let mut explored: Vec<usize> = self.explored.values().copied().collect();
+            explored.sort();
+            self.snapshots.push(Snapshot {
+                iteration,
+                frontier: self.frontier.iter().copied().collect(),
+                explored,
+            });
+        }
+        self.frontier.remove(&id);
+        self.explored.insert(node.get_state().clone(), id);
+        self.nodes[id].expanded_at = Some(iteration);
+        self.nodes[id].status = NodeStatus::Expanded;
+    }
+
+    fn on_generate(&mut self, node: &Node<P>) {
+        let id = self.push(node, NodeStatus::Frontier);
+        // a generated state that was explored has been reopened
+        self.explored.remove(node.get_state());
+        self.frontier.insert(id);
+    }
+
+    fn on_replace(&mut self, old: &Node<P>, _new: &Node<P>) {
+        if let Some(&id) = self.by_node_id.get(&old.get_id()) {
+            self.frontier.remove(&id);
+            self.nodes[id].status = NodeStatus::Dead;
+        }
+    }
+
+    fn on_duplicate(&mut self, parent: &Node<P>, state: &P::State, g: P::Cost) {
+        let id = self.nodes.len();
+        self.nodes.push(TreeNode {
+            id,
+            parent: self.by_node_id.get(&parent.get_id()).copied(),
+            state: format!("{:?}", state),
+            action: None,
+            g: g.to_string(),
+            h: None,
+            f: None,
+            depth: parent.get_depth() + 1,
+            expanded_at: None,
+            status: NodeStatus::Duplicate,
+        });
+    }
+
+    /// The goal is the node whose expansion has just started.
+    fn on_goal(&mut self, _iteration: usize, _state: &P::State, _cost: P::Cost) {
+        if let Some(id) = self.expanding {
+            self.nodes[id].status = NodeStatus::Goal;
+        }
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Costs are written as numbers when their `Display` output is a JSON number, as strings otherwise.
+fn json_number(s: &str) -> String {
+    if is_json_number(s) {
+        s.to_string()
+    } else {
+        json_string(s)
+    }
+}
+
+/// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`, the grammar of the JSON numbers.
+fn is_json_number(s: &str) -> bool {
+    fn digits(s: &[u8]) -> usize {
+        s.iter().take_while(|c| c.is_ascii_digit()).count()
+    }
+
+    let s = s.as_bytes();
+    let mut i = usize::from(s.first() == Some(&b'-'));
+    match digits(&s[i..]) {
+        0 => return false,
+        n if n > 1 && s[i] == b'0' => return false,
+        n => i += n,
+    }
+    if s.get(i) == Some(&b'.') {
+        match digits(&s[i + 1..]) {
+            0 => return false,
+            n => i += 1 + n,
+        }
+    }
+    if matches!(s.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(s.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        match digits(&s[i..]) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+    i == s.len()
+}
+
+fn separator(i: usize, len: usize) -> &'static str {
+    if i + 1 < len {
+        ","
+    } else {
+        ""
+    }
+}
+
+fn json_option(x: Option<usize>) -> String {
+    x.map_or("null".to_string(), |x| x.to_string())
+}
@@ -6,6 +6,7 @@ use std::{
     marker::PhantomData,
 };
 
+use crate::error::{Error, Result};
 use crate::problem::*;
 use crate::statexplorer::node::Node;
 
@@ -17,6 +18,8 @@ where
     fn dequeue(&mut self) -> Option<&'a Node<'a, P>>;
     fn reset(&mut self);
     fn size(&self) -> usize;
+    /// The queued nodes, in an order that rebuilds the same backend when they are enqueued again.
+    fn nodes(&self) -> Vec<&'a Node<'a, P>>;
 }
 
 pub struct Frontier<'a, P, Backend>
@@ -41,13 +44,20 @@ where
     }
 
     pub fn enqueue_or_replace(&mut self, item: &'a Node<'a, P>) -> bool {
+        self.try_enqueue_or_replace(item)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Adds `item` unless the frontier already has a node for its state with a lower or equal cost,
+    /// returns whether `item` has been added.
+    pub fn try_enqueue_or_replace(&mut self, item: &'a Node<'a, P>) -> Result<bool> {
         let mut to_remove: Option<&P::State> = None;
         if let Some(old_node) = self.get_node.get(item.get_state()) {
             if old_node.get_g_cost() > item.get_g_cost() {
                 to_remove = old_node.get_state().into();
                 old_node.mark_dead();
             } else {
-                return false;
+                return Ok(false);
             }
         }
 
@@ -56,10 +66,14 @@ where
         }
 
         let state = item.get_state().clone();
-        assert!(self.get_node.insert(state, item).is_none());
+        if self.get_node.insert(state, item).is_some() {
+            return Err(Error::Invariant(
+                "the frontier has two nodes for the same state",
+            ));
+        }
         self.collection.enqueue(item);
 
-        true
+        Ok(true)
     }
 
     pub fn dequeue(&mut self) -> Option<&'a Node<'a, P>> {
@@ -74,6 +88,20 @@ where
         result
     }
 
+    /// The node for `state` currently waiting in the frontier, if any.
+    pub fn get(&self, state: &P::State) -> Option<&'a Node<'a, P>> {
+        self.get_node.get(state).copied()
+    }
+
+    /// The live nodes waiting in the frontier, see `FrontierBackend::nodes`.
+    pub fn nodes(&self) -> Vec<&'a Node<'a, P>> {
+        self.collection
+            .nodes()
+            .into_iter()
+            .filter(|n| !n.is_dead())
+            .collect()
+    }
+
     pub fn reset(&mut self) {
         self.collection.reset();
         self.get_node.clear();
@@ -115,6 +143,10 @@ where
     fn size(&self) -> usize {
         self.len()
     }
+
+    fn nodes(&self) -> Vec<&'a Node<'a, P>> {
+        self.iter().copied().collect()
+    }
 }
 
 pub type StackBackend<'a, P> = Vec<&'a Node<'a, P>>;
@@ -138,6 +170,10 @@ where
     fn size(&self) -> usize {
         self.len()
     }
+
+    fn nodes(&self) -> Vec<&'a Node<'a, P>> {
+        self.to_vec()
+    }
 }
 
 pub trait NodeCost<P>
@@ -274,6 +310,11 @@ where
     fn size(&self) -> usize {
         self.collection.len()
     }
+
+    fn nodes(&self) -> Vec<&'a Node<'a, P>> {
+        // pushing the heap array in order keeps every element in its place
+        self.collection.iter().map(|x| x.0).collect()
+    }
 }
 
 impl<P, Policy> Debug for PriorityBackend<'_, P, Policy>
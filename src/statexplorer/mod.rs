@@ -1,3 +1,7 @@
+pub mod checkpoint;
+pub mod export;
 pub mod frontier;
 pub mod node;
+pub mod parallel;
 pub mod resolver;
+pub mod stats;
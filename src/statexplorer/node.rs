@@ -7,7 +7,9 @@ use std::vec::Vec;
 
 use bumpalo::Bump;
 
+use crate::error::{Error, Result};
 use crate::problem::*;
+use crate::statexplorer::checkpoint::NodeRecord;
 
 #[derive(PartialEq, Eq)]
 pub struct Node<'a, P>
@@ -20,6 +22,7 @@ where
     total_cost: P::Cost,
     heuristic: P::Cost,
     depth: usize,
+    id: usize,
     dead: RefCell<bool>,
 }
 
@@ -37,6 +40,52 @@ where
     ) -> &'a Self {
         arena.alloc(Self::new(parent, problem, state, action, cost))
     }
+
+    pub fn try_in_arena(
+        parent: Option<&'a Node<'a, P>>,
+        problem: &P,
+        state: P::State,
+        action: Option<P::Action>,
+        cost: P::Cost,
+        arena: &'a Bump,
+    ) -> Result<&'a Self> {
+        Ok(arena.alloc(Self::try_new(parent, problem, state, action, cost)?))
+    }
+
+    /// Like `try_in_arena`, numbering the node with `next_id` and advancing it.
+    pub(crate) fn try_numbered_in_arena(
+        parent: Option<&'a Node<'a, P>>,
+        problem: &P,
+        state: P::State,
+        action: Option<P::Action>,
+        cost: P::Cost,
+        next_id: &mut usize,
+        arena: &'a Bump,
+    ) -> Result<&'a Self> {
+        let mut node = Self::try_new(parent, problem, state, action, cost)?;
+        node.id = *next_id;
+        *next_id += 1;
+        Ok(arena.alloc(node))
+    }
+
+    /// Rebuilds a node saved in a checkpoint, without evaluating the heuristic again.
+    pub(crate) fn restore_in_arena(
+        parent: Option<&'a Node<'a, P>>,
+        record: NodeRecord<P::State, P::Action, P::Cost>,
+        arena: &'a Bump,
+    ) -> &'a Self {
+        arena.alloc(Self {
+            state: record.state,
+            parent,
+            action: record.action,
+            total_cost: record.g,
+            heuristic: record.h,
+            depth: record.depth,
+            id: record.id,
+            dead: false.into(),
+        })
+    }
+
     pub fn new(
         parent: Option<&'a Node<'a, P>>,
         problem: &P,
@@ -44,23 +93,36 @@ where
         action: Option<P::Action>,
         cost: P::Cost,
     ) -> Self {
-        assert!((parent.is_none() && action.is_none()) || (parent.is_some() && action.is_some()));
+        Self::try_new(parent, problem, state, action, cost).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new(
+        parent: Option<&'a Node<'a, P>>,
+        problem: &P,
+        state: P::State,
+        action: Option<P::Action>,
+        cost: P::Cost,
+    ) -> Result<Self> {
+        if parent.is_some() != action.is_some() {
+            return Err(Error::InvalidNode);
+        }
         let mut total_cost = cost;
         let mut depth = 0;
         if let Some(parent_node) = parent.as_ref() {
             total_cost = total_cost + parent_node.total_cost;
             depth = parent_node.depth + 1;
         }
-        let h = problem.heuristic(&state);
-        Self {
+        let h = problem.try_heuristic(&state)?;
+        Ok(Self {
             state: state,
             parent: parent,
             action: action,
             total_cost: total_cost,
             depth: depth,
             heuristic: h,
+            id: 0,
             dead: false.into(),
-        }
+        })
     }
 
     pub fn get_plan(&self) -> Vec<P::Action> {
@@ -81,7 +143,12 @@ where
         result.reverse();
         result
     }
+}
 
+impl<'a, P> Node<'a, P>
+where
+    P: Utility,
+{
     pub fn get_g_cost(&self) -> P::Cost {
         return self.total_cost;
     }
@@ -102,6 +169,19 @@ where
         self.depth
     }
 
+    /// Generation order of the node inside the explorer that created it, 0 outside of explorers.
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+
+    pub fn get_parent(&self) -> Option<&'a Node<'a, P>> {
+        self.parent
+    }
+
+    pub fn get_action(&self) -> Option<&P::Action> {
+        self.action.as_ref()
+    }
+
     pub fn mark_dead(&self) {
         *self.dead.borrow_mut() = true;
     }
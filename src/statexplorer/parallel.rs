@@ -0,0 +1,442 @@
+use std::{
+    cmp::Ordering as CmpOrdering,
+    collections::{hash_map::DefaultHasher, BinaryHeap, HashMap},
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use crate::error::{Error, Result};
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::problem::*;
+use crate::statexplorer::resolver::{SearchOutcome, SearchResult};
+use crate::statexplorer::stats::SearchStats;
+
+/// A step of a plan, shared by all the nodes generated from the same path.
+struct Step<Action> {
+    action: Action,
+    parent: Option<Arc<Step<Action>>>,
+}
+
+fn plan<Action: Clone>(mut step: Option<&Arc<Step<Action>>>) -> Vec<Action> {
+    let mut actions = Vec::new();
+    while let Some(s) = step {
+        actions.push(s.action.clone());
+        step = s.parent.as_ref();
+    }
+    actions.reverse();
+    actions
+}
+
+/// A generated node, sent to the worker that owns its state.
+struct Message<State, Action, Cost> {
+    state: State,
+    g: Cost,
+    h: Cost,
+    depth: usize,
+    path: Option<Arc<Step<Action>>>,
+}
+
+impl<State, Action, Cost> Message<State, Action, Cost>
+where
+    Cost: Copy + std::ops::Add<Output = Cost>,
+{
+    fn f(&self) -> Cost {
+        self.g + self.h
+    }
+}
+
+/// What a worker receives: a node to own, or a wake up to check if the search is over.
+enum Mail<State, Action, Cost> {
+    Node(Message<State, Action, Cost>),
+    Wake,
+}
+
+type MailOf<P> =
+    Mail<<P as Problem>::State, <P as CostructSolution>::Action, <P as CostructSolution>::Cost>;
+
+/// Orders the open list of a worker by lowest f, then lowest h.
+struct Open<State, Action, Cost>(Message<State, Action, Cost>);
+
+impl<State, Action, Cost> Ord for Open<State, Action, Cost>
+where
+    Cost: Copy + Ord + std::ops::Add<Output = Cost>,
+{
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        other
+            .0
+            .f()
+            .cmp(&self.0.f())
+            .then_with(|| other.0.h.cmp(&self.0.h))
+    }
+}
+
+impl<State, Action, Cost> PartialOrd for Open<State, Action, Cost>
+where
+    Cost: Copy + Ord + std::ops::Add<Output = Cost>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<State, Action, Cost> PartialEq for Open<State, Action, Cost>
+where
+    Cost: Copy + Ord + std::ops::Add<Output = Cost>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl<State, Action, Cost> Eq for Open<State, Action, Cost> where
+    Cost: Copy + Ord + std::ops::Add<Output = Cost>
+{
+}
+
+struct Incumbent<State, Action, Cost> {
+    cost: Cost,
+    state: State,
+    path: Option<Arc<Step<Action>>>,
+}
+
+/// State shared by the workers of a search.
+struct Shared<State, Action, Cost> {
+    /// Nodes waiting in an open list or in a channel, plus the ones being expanded.
+    /// The search space is exhausted when it reaches zero.
+    work: AtomicUsize,
+    stop: AtomicBool,
+    expanded: AtomicUsize,
+    generated: AtomicUsize,
+    /// Incremented every time a cheaper goal is found.
+    solutions: AtomicUsize,
+    incumbent: Mutex<Option<Incumbent<State, Action, Cost>>>,
+    limit: Mutex<Option<Limit>>,
+    error: Mutex<Option<Error>>,
+}
+
+impl<State, Action, Cost> Shared<State, Action, Cost> {
+    /// Removes a node from `work`, waking the idle workers when it was the last one.
+    fn done(&self, senders: &[Sender<Mail<State, Action, Cost>>]) {
+        if self.work.fetch_sub(1, Ordering::SeqCst) == 1 {
+            wake(senders);
+        }
+    }
+
+    fn halt(&self, senders: &[Sender<Mail<State, Action, Cost>>]) {
+        self.stop.store(true, Ordering::SeqCst);
+        wake(senders);
+    }
+}
+
+fn wake<State, Action, Cost>(senders: &[Sender<Mail<State, Action, Cost>>]) {
+    for sender in senders {
+        // a closed channel belongs to a worker that has already stopped
+        let _ = sender.send(Mail::Wake);
+    }
+}
+
+struct Report {
+    stats: SearchStats,
+    max_open_size: usize,
+}
+
+/// Multi-threaded A* with hash distributed work (HDA*): every state is owned by one worker,
+/// chosen by its hash, which keeps its open list and its closed set. Generated nodes are
+/// sent to their owner through a channel.
+///
+/// The search stops when no node is left anywhere, so the plan is optimal
+/// whenever the heuristic is admissible, like `AStarExplorer` with `ReopenPolicy::Always`.
+pub struct HDAStarExplorer<'a, P> {
+    problem: &'a P,
+    threads: usize,
+    limits: SearchLimits,
+}
+
+impl<'a, P> HDAStarExplorer<'a, P>
+where
+    P: SuitableState
+        + Utility<State: Eq + Hash + Clone + Send, Action: Clone + Send + Sync, Cost: Send>
+        + Sync,
+{
+    /// Uses one worker for every available core.
+    pub fn new(problem: &'a P) -> Self {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self::with_threads(problem, threads)
+    }
+
+    pub fn with_threads(problem: &'a P, threads: usize) -> Self {
+        Self {
+            problem,
+            threads: threads.max(1),
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn search(&mut self, init_state: P::State) -> SearchResult<P::State, P::Action> {
+        self.try_search(init_state)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// When a limit stops the search the result holds the best plan found so far, if any,
+    /// which is not guaranteed to be optimal.
+    pub fn try_search(
+        &mut self,
+        init_state: P::State,
+    ) -> Result<SearchResult<P::State, P::Action>> {
+        let start = Instant::now();
+        let budget = self.limits.start();
+        let h = self.problem.try_heuristic(&init_state)?;
+        let shared = Shared {
+            work: AtomicUsize::new(1),
+            stop: AtomicBool::new(false),
+            expanded: AtomicUsize::new(0),
+            generated: AtomicUsize::new(1),
+            solutions: AtomicUsize::new(0),
+            incumbent: Mutex::new(None),
+            limit: Mutex::new(None),
+            error: Mutex::new(None),
+        };
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..self.threads).map(|_| mpsc::channel()).unzip();
+        let root = Message {
+            state: init_state,
+            g: P::Cost::default(),
+            h,
+            depth: 0,
+            path: None,
+        };
+        let _ = senders[self.owner(&root.state)].send(Mail::Node(root));
+
+        let explorer = &*self;
+        let reports: Vec<Report> = thread::scope(|scope| {
+            let handles: Vec<_> = receivers
+                .into_iter()
+                .map(|receiver| {
+                    let senders = senders.clone();
+                    let (shared, budget) = (&shared, &budget);
+                    scope.spawn(move || explorer.work(receiver, senders, shared, budget))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .expect("a worker of the parallel search panicked")
+                })
+                .collect()
+        });
+
+        if let Some(error) = shared.error.into_inner().unwrap() {
+            return Err(error);
+        }
+        let mut stats = SearchStats::default();
+        stats.count_generated(0);
+        for report in reports.iter() {
+            stats.merge(&report.stats);
+        }
+        let limit = shared.limit.into_inner().unwrap();
+        let incumbent = shared.incumbent.into_inner().unwrap();
+        let outcome = match (limit, &incumbent) {
+            (Some(limit), _) => SearchOutcome::LimitReached(limit),
+            (None, Some(_)) => SearchOutcome::Solved,
+            (None, None) => SearchOutcome::Exhausted,
+        };
+        let (state, actions) = match incumbent {
+            Some(incumbent) => (Some(incumbent.state), Some(plan(incumbent.path.as_ref()))),
+            None => (None, None),
+        };
+        Ok(SearchResult {
+            total_time: start.elapsed(),
+            state,
+            actions,
+            n_iter: stats.expanded,
+            max_frontier_size: reports.iter().map(|r| r.max_open_size).sum(),
+            stats,
+            outcome,
+        })
+    }
+
+    fn owner(&self, state: &P::State) -> usize {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        (hasher.finish() % self.threads as u64) as usize
+    }
+
+    fn work(
+        &self,
+        receiver: Receiver<MailOf<P>>,
+        senders: Vec<Sender<MailOf<P>>>,
+        shared: &Shared<P::State, P::Action, P::Cost>,
+        budget: &SearchLimits,
+    ) -> Report {
+        let mut report = Report {
+            stats: SearchStats::default(),
+            max_open_size: 0,
+        };
+        if let Err(error) = self.run(&receiver, &senders, shared, budget, &mut report) {
+            shared.error.lock().unwrap().get_or_insert(error);
+            shared.halt(&senders);
+        }
+        report
+    }
+
+    fn run(
+        &self,
+        receiver: &Receiver<MailOf<P>>,
+        senders: &[Sender<MailOf<P>>],
+        shared: &Shared<P::State, P::Action, P::Cost>,
+        budget: &SearchLimits,
+        report: &mut Report,
+    ) -> Result<()> {
+        let mut open: BinaryHeap<Open<P::State, P::Action, P::Cost>> = BinaryHeap::new();
+        // best g found for every owned state, and whether that node has been expanded
+        let mut closed: HashMap<P::State, (P::Cost, bool)> = HashMap::new();
+        let mut bound: Option<P::Cost> = None;
+        let mut seen_solutions = 0;
+
+        while !shared.stop.load(Ordering::SeqCst) {
+            while let Ok(mail) = receiver.try_recv() {
+                if let Mail::Node(message) = mail {
+                    receive(message, &mut open, &mut closed, report, shared, senders);
+                }
+            }
+            report.max_open_size = report.max_open_size.max(open.len());
+
+            let Some(Open(node)) = open.pop() else {
+                if shared.work.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                // the last node done and `halt` wake the idle workers, after changing what they check
+                match receiver.recv() {
+                    Ok(Mail::Node(message)) => {
+                        receive(message, &mut open, &mut closed, report, shared, senders)
+                    }
+                    Ok(Mail::Wake) => {}
+                    Err(_) => break,
+                }
+                continue;
+            };
+
+            let solutions = shared.solutions.load(Ordering::SeqCst);
+            if solutions != seen_solutions {
+                seen_solutions = solutions;
+                bound = shared.incumbent.lock().unwrap().as_ref().map(|i| i.cost);
+            }
+
+            let entry = closed.get_mut(&node.state);
+            let stale = match entry {
+                Some((g, expanded)) if *g == node.g && !*expanded => {
+                    *expanded = true;
+                    false
+                }
+                _ => true,
+            };
+            if stale || bound.is_some_and(|b| node.f() >= b) {
+                shared.done(senders);
+                continue;
+            }
+
+            if self.problem.is_suitable(&node.state) {
+                let mut incumbent = shared.incumbent.lock().unwrap();
+                if incumbent.as_ref().is_none_or(|i| node.g < i.cost) {
+                    bound = Some(node.g);
+                    *incumbent = Some(Incumbent {
+                        cost: node.g,
+                        state: node.state,
+                        path: node.path,
+                    });
+                    shared.solutions.fetch_add(1, Ordering::SeqCst);
+                }
+                drop(incumbent);
+                shared.done(senders);
+                continue;
+            }
+
+            let expanded = shared.expanded.fetch_add(1, Ordering::SeqCst);
+            let generated = shared.generated.load(Ordering::SeqCst);
+            if let Some(limit) = budget.check(expanded, generated, 0) {
+                shared.limit.lock().unwrap().get_or_insert(limit);
+                shared.halt(senders);
+                break;
+            }
+            report.stats.expanded += 1;
+
+            for action in self.problem.executable_actions(&node.state) {
+                let (state, cost) = self.problem.result(&node.state, &action);
+                let g = node.g + cost;
+                let h = self.problem.try_heuristic(&state)?;
+                if bound.is_some_and(|b| g + h >= b) {
+                    report.stats.discarded += 1;
+                    continue;
+                }
+                report.stats.count_generated(node.depth + 1);
+                shared.generated.fetch_add(1, Ordering::SeqCst);
+                shared.work.fetch_add(1, Ordering::SeqCst);
+                let owner = self.owner(&state);
+                let message = Message {
+                    state,
+                    g,
+                    h,
+                    depth: node.depth + 1,
+                    path: Some(Arc::new(Step {
+                        action,
+                        parent: node.path.clone(),
+                    })),
+                };
+                // a closed channel means that the search is stopping
+                let _ = senders[owner].send(Mail::Node(message));
+            }
+            shared.done(senders);
+        }
+        Ok(())
+    }
+}
+
+/// Adds a node to the open list of its owner, unless a node for the same state is at least as cheap.
+fn receive<State, Action, Cost>(
+    message: Message<State, Action, Cost>,
+    open: &mut BinaryHeap<Open<State, Action, Cost>>,
+    closed: &mut HashMap<State, (Cost, bool)>,
+    report: &mut Report,
+    shared: &Shared<State, Action, Cost>,
+    senders: &[Sender<Mail<State, Action, Cost>>],
+) where
+    State: Eq + Hash + Clone,
+    Cost: Copy + Ord + std::ops::Add<Output = Cost>,
+{
+    match closed.get(&message.state) {
+        Some((g, _)) if *g <= message.g => {
+            report.stats.duplicates += 1;
+            shared.done(senders);
+            return;
+        }
+        Some((_, true)) => report.stats.reopened += 1,
+        Some((_, false)) => report.stats.replaced += 1,
+        None => {}
+    }
+    closed.insert(message.state.clone(), (message.g, false));
+    open.push(Open(message));
+}
+
+impl<P> LimitSearch for HDAStarExplorer<'_, P> {
+    /// The memory limit is ignored, the workers do not allocate in an arena.
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
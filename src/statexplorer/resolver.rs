@@ -1,6 +1,6 @@
 use core::fmt;
 use std::{
-    collections::HashSet,
+    collections::HashMap,
     fmt::Debug,
     hash::Hash,
     time::{Duration, Instant},
@@ -8,12 +8,17 @@ use std::{
 
 use bumpalo::Bump;
 
+use crate::error::{Error, Result};
+use crate::limits::{Limit, LimitSearch, SearchLimits};
+use crate::observer::{LogObserver, NoObserver, SearchObserver};
 use crate::problem::*;
+use crate::statexplorer::checkpoint::{Checkpoint, Deepening, NodeRecord};
 use crate::statexplorer::frontier::{
     AStarBackend, BestFirstBackend, DequeBackend, Frontier, FrontierBackend, MinCostBackend,
     StackBackend,
 };
 use crate::statexplorer::node::Node;
+use crate::statexplorer::stats::SearchStats;
 
 struct InnerResult<State, Action>
 where
@@ -22,6 +27,7 @@ where
     actions: Option<Vec<Action>>,
     state: Option<State>,
     max_frontier_size: usize,
+    limit: Option<Limit>,
 }
 
 impl<State, Action> InnerResult<State, Action>
@@ -33,6 +39,7 @@ where
             state: state.into(),
             actions: actions.into(),
             max_frontier_size: max_frontier_size,
+            limit: None,
         }
     }
 
@@ -41,10 +48,29 @@ where
             state: None,
             actions: None,
             max_frontier_size: max_frontier_size,
+            limit: None,
+        }
+    }
+
+    fn stopped(limit: Limit, max_frontier_size: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::not_found(max_frontier_size)
         }
     }
 }
 
+/// How a search ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SearchOutcome {
+    Solved,
+    /// The whole (possibly depth limited) search space has been explored without finding a goal.
+    Exhausted,
+    LimitReached(Limit),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SearchResult<State, Action>
 where
     Action: Clone,
@@ -54,6 +80,8 @@ where
     pub actions: Option<Vec<Action>>,
     pub n_iter: usize,
     pub max_frontier_size: usize,
+    pub stats: SearchStats,
+    pub outcome: SearchOutcome,
 }
 
 impl<State, Action> SearchResult<State, Action>
@@ -67,22 +95,38 @@ where
             state: None,
             n_iter: 0,
             max_frontier_size: 0,
+            stats: SearchStats::default(),
+            outcome: SearchOutcome::Exhausted,
         }
     }
 
     fn from_inner_result(
         start: Instant,
         n_iter: usize,
+        stats: SearchStats,
         inner_result: InnerResult<State, Action>,
     ) -> Self {
+        let outcome = match inner_result.limit {
+            Some(limit) => SearchOutcome::LimitReached(limit),
+            None if inner_result.actions.is_some() => SearchOutcome::Solved,
+            None => SearchOutcome::Exhausted,
+        };
         Self {
             state: inner_result.state,
             total_time: start.elapsed(),
             actions: inner_result.actions,
             n_iter: n_iter,
             max_frontier_size: inner_result.max_frontier_size,
+            stats,
+            outcome,
         }
     }
+
+    /// Effective branching factor computed with the depth of the solution found.
+    pub fn effective_branching_factor(&self) -> Option<f64> {
+        self.stats
+            .effective_branching_factor(self.actions.as_ref()?.len())
+    }
 }
 
 impl<State, Action> fmt::Display for SearchResult<State, Action>
@@ -100,33 +144,59 @@ where
                 self.total_time,
                 self.n_iter,
                 self.max_frontier_size
-            )
+            )?;
         } else {
             write!(
                 f,
                 "no solution found\ntime: {:?}\niterations: {}\nmax frontier size: {}",
                 self.total_time, self.n_iter, self.max_frontier_size
-            )
+            )?;
+            if let SearchOutcome::LimitReached(limit) = self.outcome {
+                write!(f, "\n{}", limit)?;
+            }
         }
+        write!(f, "\n{}", self.stats)
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Verbosity {
     None,
     Low,
 }
 
+/// What `Explorer` does when it reaches an already explored state with a lower g cost.
+/// Reopening is needed for optimality when the heuristic is admissible but not consistent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReopenPolicy {
+    #[default]
+    Never,
+    Always,
+    /// Every state can be moved back to the frontier at most this many times.
+    Bounded(usize),
+}
+
 pub struct Explorer<'a, P, Backend>
 where
     P: Utility + SuitableState, // TODO: generalize more
     Backend: FrontierBackend<'a, P> + Debug,
 {
-    verbosity: Verbosity,
     problem: &'a P,
-    explored: HashSet<P::State>,
+    observer: Box<dyn SearchObserver<P> + 'a>,
+    explored: HashMap<P::State, P::Cost>,
     frontier: Frontier<'a, P, Backend>,
     arena: &'a Bump,
+    reopen_policy: ReopenPolicy,
+    reopened: HashMap<P::State, usize>,
+    next_id: usize,
+    stats: SearchStats,
+    limits: SearchLimits,
+    budget: SearchLimits,
+    depth_limit: Option<usize>,
+    deepening: Option<Deepening<P::State>>,
+    max_frontier_size: usize,
 }
 
 impl<'a, P, Backend> Explorer<'a, P, Backend>
@@ -135,33 +205,43 @@ where
     Backend: FrontierBackend<'a, P> + Debug,
 {
     pub fn with_verbosity(problem: &'a P, arena: &'a Bump, verbosity: Verbosity) -> Self {
+        let observer: Box<dyn SearchObserver<P> + 'a> = match verbosity {
+            Verbosity::None => Box::new(NoObserver),
+            Verbosity::Low => Box::new(LogObserver),
+        };
         Self {
             problem: problem,
-            verbosity: verbosity,
-            explored: HashSet::new(),
+            observer,
+            explored: HashMap::new(),
             frontier: Frontier::new(),
             arena: arena,
+            reopen_policy: ReopenPolicy::Never,
+            reopened: HashMap::new(),
+            next_id: 0,
+            stats: SearchStats::default(),
+            limits: SearchLimits::default(),
+            budget: SearchLimits::default(),
+            depth_limit: None,
+            deepening: None,
+            max_frontier_size: 0,
         }
     }
 
     pub fn with_low_v(problem: &'a P, arena: &'a Bump) -> Self {
-        Self {
-            problem: problem,
-            verbosity: Verbosity::Low,
-            explored: HashSet::new(),
-            frontier: Frontier::new(),
-            arena: arena,
-        }
+        Self::with_verbosity(problem, arena, Verbosity::Low)
     }
 
     pub fn new(problem: &'a P, arena: &'a Bump) -> Self {
-        Self {
-            problem: problem,
-            verbosity: Verbosity::None,
-            explored: HashSet::new(),
-            frontier: Frontier::new(),
-            arena: arena,
-        }
+        Self::with_verbosity(problem, arena, Verbosity::None)
+    }
+
+    /// Replaces the observer, including the one installed by the verbosity level.
+    pub fn set_observer(&mut self, observer: impl SearchObserver<P> + 'a) {
+        self.observer = Box::new(observer);
+    }
+
+    pub fn set_reopen_policy(&mut self, policy: ReopenPolicy) {
+        self.reopen_policy = policy;
     }
 
     pub fn iterative_search(
@@ -169,22 +249,56 @@ where
         init_state: P::State,
         max_limit: usize,
     ) -> SearchResult<P::State, P::Action> {
-        let mut lim = 1;
-        let mut result = SearchResult::new();
+        self.try_iterative_search(init_state, max_limit)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_iterative_search(
+        &mut self,
+        init_state: P::State,
+        max_limit: usize,
+    ) -> Result<SearchResult<P::State, P::Action>> {
         let start = Instant::now();
-        let mut n_iter = 0;
+        self.begin();
+        let deepening = Deepening {
+            init_state,
+            max_limit,
+        };
+        self.deepening = Some(deepening.clone());
+        self.deepen(deepening, start, 0, 1, 0)
+    }
+
+    /// The loop of `iterative_search` from the depth limit `lim`.
+    fn deepen(
+        &mut self,
+        deepening: Deepening<P::State>,
+        start: Instant,
+        mut n_iter: usize,
+        mut lim: usize,
+        max_frontier_size: usize,
+    ) -> Result<SearchResult<P::State, P::Action>> {
+        let mut result = SearchResult::new();
+        result.max_frontier_size = max_frontier_size;
         loop {
-            if max_limit < lim {
+            if deepening.max_limit < lim {
                 result.n_iter = n_iter;
                 result.total_time = start.elapsed();
-                return result;
+                result.stats = self.take_stats();
+                return Ok(result);
             }
-            let inner_result = self.inner_search(&mut n_iter, init_state.clone(), lim.into());
+            let inner_result =
+                self.inner_search(&mut n_iter, deepening.init_state.clone(), lim.into())?;
             if result.max_frontier_size < inner_result.max_frontier_size {
                 result.max_frontier_size = inner_result.max_frontier_size
             }
-            if inner_result.actions.is_some() {
-                return SearchResult::from_inner_result(start, n_iter, inner_result);
+            if inner_result.actions.is_some() || inner_result.limit.is_some() {
+                let stats = self.take_stats();
+                return Ok(SearchResult::from_inner_result(
+                    start,
+                    n_iter,
+                    stats,
+                    inner_result,
+                ));
             }
             lim += 1
         }
@@ -195,17 +309,211 @@ where
         init_state: P::State,
         max_depth: usize,
     ) -> SearchResult<P::State, P::Action> {
+        self.try_search_with_max_depth(init_state, max_depth)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_search_with_max_depth(
+        &mut self,
+        init_state: P::State,
+        max_depth: usize,
+    ) -> Result<SearchResult<P::State, P::Action>> {
         let start = Instant::now();
         let mut n_iter = 0;
-        let result = self.inner_search(&mut n_iter, init_state, max_depth.into());
-        SearchResult::from_inner_result(start, n_iter, result)
+        self.begin();
+        self.deepening = None;
+        let result = self.inner_search(&mut n_iter, init_state, max_depth.into())?;
+        Ok(SearchResult::from_inner_result(
+            start,
+            n_iter,
+            self.take_stats(),
+            result,
+        ))
     }
 
     pub fn search(&mut self, init_state: P::State) -> SearchResult<P::State, P::Action> {
+        self.try_search(init_state)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_search(
+        &mut self,
+        init_state: P::State,
+    ) -> Result<SearchResult<P::State, P::Action>> {
         let start = Instant::now();
         let mut n_iter = 0;
-        let result = self.inner_search(&mut n_iter, init_state, None);
-        SearchResult::from_inner_result(start, n_iter, result)
+        self.begin();
+        self.deepening = None;
+        let result = self.inner_search(&mut n_iter, init_state, None)?;
+        Ok(SearchResult::from_inner_result(
+            start,
+            n_iter,
+            self.take_stats(),
+            result,
+        ))
+    }
+
+    fn begin(&mut self) {
+        self.stats = SearchStats::default();
+        self.budget = self.limits.start();
+    }
+
+    fn take_stats(&mut self) -> SearchStats {
+        self.stats.arena_bytes = self.arena.allocated_bytes();
+        self.stats.clone()
+    }
+
+    /// Saves the state of the last search, typically one interrupted by a limit, so that
+    /// `resume` can continue it later, possibly in another process.
+    pub fn checkpoint(&self) -> Checkpoint<P::State, P::Action, P::Cost> {
+        let frontier = self.frontier.nodes();
+        let mut by_id: HashMap<usize, &Node<'a, P>> = HashMap::new();
+        for node in frontier.iter() {
+            let mut current = Some(*node);
+            while let Some(node) = current {
+                if by_id.insert(node.get_id(), node).is_some() {
+                    break;
+                }
+                current = node.get_parent();
+            }
+        }
+        // a parent is always generated before its children
+        let mut nodes: Vec<&Node<'a, P>> = by_id.into_values().collect();
+        nodes.sort_by_key(|node| node.get_id());
+        Checkpoint {
+            nodes: nodes
+                .into_iter()
+                .map(|node| NodeRecord {
+                    id: node.get_id(),
+                    parent: node.get_parent().map(|p| p.get_id()),
+                    state: node.get_state().clone(),
+                    action: node.get_action().cloned(),
+                    g: node.get_g_cost(),
+                    h: node.get_h_cost(),
+                    depth: node.get_depth(),
+                })
+                .collect(),
+            frontier: frontier.iter().map(|node| node.get_id()).collect(),
+            explored: self
+                .explored
+                .iter()
+                .map(|(state, g)| (state.clone(), *g))
+                .collect(),
+            reopened: self
+                .reopened
+                .iter()
+                .map(|(state, n)| (state.clone(), *n))
+                .collect(),
+            next_id: self.next_id,
+            depth_limit: self.depth_limit,
+            deepening: self.deepening.clone(),
+            max_frontier_size: self.max_frontier_size,
+            stats: self.stats.clone(),
+        }
+    }
+
+    /// Continues the search saved in `checkpoint`. The limits count from the counters of the checkpoint,
+    /// and an `iterative_search` goes on deepening after the depth limit it was interrupted at.
+    pub fn resume(
+        &mut self,
+        checkpoint: Checkpoint<P::State, P::Action, P::Cost>,
+    ) -> SearchResult<P::State, P::Action> {
+        self.try_resume(checkpoint)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_resume(
+        &mut self,
+        checkpoint: Checkpoint<P::State, P::Action, P::Cost>,
+    ) -> Result<SearchResult<P::State, P::Action>> {
+        let start = Instant::now();
+        self.frontier.reset();
+        let mut by_id: HashMap<usize, &'a Node<'a, P>> = HashMap::new();
+        for record in checkpoint.nodes {
+            let parent = match record.parent {
+                Some(id) => Some(
+                    *by_id
+                        .get(&id)
+                        .ok_or(Error::Invariant("checkpoint node saved before its parent"))?,
+                ),
+                None => None,
+            };
+            let id = record.id;
+            by_id.insert(id, Node::restore_in_arena(parent, record, self.arena));
+        }
+        for id in checkpoint.frontier {
+            let node = by_id
+                .get(&id)
+                .ok_or(Error::Invariant("checkpoint frontier node not saved"))?;
+            self.frontier.try_enqueue_or_replace(node)?;
+        }
+        self.explored = checkpoint.explored.into_iter().collect();
+        self.reopened = checkpoint.reopened.into_iter().collect();
+        self.next_id = checkpoint.next_id;
+        self.depth_limit = checkpoint.depth_limit;
+        self.deepening = checkpoint.deepening;
+        self.max_frontier_size = checkpoint.max_frontier_size;
+        self.stats = checkpoint.stats;
+        self.budget = self
+            .limits
+            .start()
+            .offset(self.stats.expanded, self.stats.generated);
+
+        let mut n_iter = self.stats.expanded;
+        let result = self.run(&mut n_iter)?;
+        if let (Some(deepening), Some(lim)) = (self.deepening.clone(), self.depth_limit) {
+            if result.actions.is_none() && result.limit.is_none() {
+                return self.deepen(deepening, start, n_iter, lim + 1, result.max_frontier_size);
+            }
+        }
+        Ok(SearchResult::from_inner_result(
+            start,
+            n_iter,
+            self.take_stats(),
+            result,
+        ))
+    }
+
+    fn check_limits(&self) -> Option<Limit> {
+        let memory = match self.budget.max_memory {
+            Some(_) => self.arena.allocated_bytes(),
+            None => 0,
+        };
+        self.budget
+            .check(self.stats.expanded, self.stats.generated, memory)
+    }
+
+    /// Decides if an explored state reached again with cost `g` goes back to the frontier,
+    /// in which case it is removed from the explored set.
+    fn reopen(&mut self, state: &P::State, g: P::Cost) -> bool {
+        let closed_g = match self.explored.get(state) {
+            Some(closed_g) => *closed_g,
+            None => return true,
+        };
+        if g >= closed_g {
+            self.stats.duplicates += 1;
+            return false;
+        }
+        let allowed = match self.reopen_policy {
+            ReopenPolicy::Never => false,
+            ReopenPolicy::Always => true,
+            ReopenPolicy::Bounded(max) => {
+                let count = self.reopened.entry(state.clone()).or_insert(0);
+                if *count < max {
+                    *count += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        if allowed {
+            self.explored.remove(state);
+            self.stats.reopened += 1;
+        } else {
+            self.stats.duplicates += 1;
+        }
+        allowed
     }
 
     fn inner_search(
@@ -213,70 +521,112 @@ where
         n_iter: &mut usize,
         init_state: P::State,
         lim: Option<usize>,
-    ) -> InnerResult<P::State, P::Action> {
+    ) -> Result<InnerResult<P::State, P::Action>> {
         self.frontier.reset();
         self.explored.clear();
-        self.frontier.enqueue_or_replace(Node::in_arena(
+        self.reopened.clear();
+        let root = Node::try_numbered_in_arena(
             None,
-            &self.problem,
+            self.problem,
             init_state,
             None,
             P::Cost::default(),
+            &mut self.next_id,
             self.arena,
-        ));
+        )?;
+        self.stats.count_generated(0);
+        self.frontier.try_enqueue_or_replace(root)?;
+        self.observer.on_generate(root);
+        self.depth_limit = lim;
+        self.max_frontier_size = 0;
+        self.run(n_iter)
+    }
 
+    fn run(&mut self, n_iter: &mut usize) -> Result<InnerResult<P::State, P::Action>> {
+        let lim = self.depth_limit;
         let result: InnerResult<P::State, P::Action>;
 
-        let mut max_frontier_size = 0;
-        while let Some(curr_node) = self.frontier.dequeue() {
+        while self.frontier.size() > 0 {
+            // checked before taking the node, so that a checkpoint still contains it
+            if let Some(limit) = self.check_limits() {
+                return Ok(InnerResult::stopped(limit, self.max_frontier_size));
+            }
+            let Some(curr_node) = self.frontier.dequeue() else {
+                break;
+            };
             *n_iter += 1;
+            self.stats.expanded += 1;
 
             let curr_state = curr_node.get_state();
 
-            self.eprint_status(curr_state, curr_node.get_g_cost(), *n_iter);
+            self.observer.on_expand(*n_iter, curr_node);
 
             if self.problem.is_suitable(&curr_state) {
+                self.observer
+                    .on_goal(*n_iter, curr_state, curr_node.get_g_cost());
                 result = InnerResult::<P::State, P::Action>::found(
                     curr_node.get_state().clone(),
                     curr_node.get_plan().into(),
-                    max_frontier_size,
+                    self.max_frontier_size,
                 );
-                return result;
+                return Ok(result);
             } else {
                 let depth = curr_node.get_depth();
                 if lim.map_or(true, |x| x > depth) {
                     for action in self.problem.executable_actions(curr_state) {
                         let (new_state, cost) = self.problem.result(curr_state, &action);
-                        if !self.explored.contains(&new_state) {
-                            let new_node = Node::in_arena(
+                        let g = curr_node.get_g_cost() + cost;
+                        if self.reopen(&new_state, g) {
+                            let new_node = Node::try_numbered_in_arena(
                                 Some(curr_node),
-                                &self.problem,
+                                self.problem,
                                 new_state,
                                 Some(action),
                                 cost,
+                                &mut self.next_id,
                                 self.arena,
-                            );
-                            self.frontier.enqueue_or_replace(new_node);
+                            )?;
+                            self.stats.count_generated(depth + 1);
+                            let old_node = self.frontier.get(new_node.get_state());
+                            if self.frontier.try_enqueue_or_replace(new_node)? {
+                                self.observer.on_generate(new_node);
+                                if let Some(old_node) = old_node {
+                                    self.stats.replaced += 1;
+                                    self.observer.on_replace(old_node, new_node);
+                                }
+                            } else {
+                                self.stats.discarded += 1;
+                                self.observer
+                                    .on_duplicate(curr_node, new_node.get_state(), g);
+                            }
+                        } else {
+                            self.observer.on_duplicate(curr_node, &new_state, g);
                         }
                     }
                 }
             }
-            self.explored.insert(curr_state.clone());
-            if max_frontier_size < self.frontier.size() {
-                max_frontier_size = self.frontier.size();
+            self.explored
+                .insert(curr_state.clone(), curr_node.get_g_cost());
+            if self.max_frontier_size < self.frontier.size() {
+                self.max_frontier_size = self.frontier.size();
             }
         }
-        result = InnerResult::<P::State, P::Action>::not_found(max_frontier_size);
-        return result;
+        result = InnerResult::<P::State, P::Action>::not_found(self.max_frontier_size);
+        Ok(result)
     }
+}
 
-    fn eprint_status(&self, curr_state: &P::State, cost: P::Cost, n_iter: usize) {
-        if self.verbosity == Verbosity::Low {
-            eprintln!(
-                "I: {} cost: {:?} current state:\n{:?}",
-                n_iter, cost, curr_state
-            );
-        }
+impl<'a, P, Backend> LimitSearch for Explorer<'a, P, Backend>
+where
+    P: Utility + SuitableState,
+    Backend: FrontierBackend<'a, P> + Debug,
+{
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
     }
 }
 
@@ -286,8 +636,13 @@ where
     Backend: FrontierBackend<'a, P>,
 {
     problem: &'a P,
+    observer: Box<dyn SearchObserver<P> + 'a>,
     frontier: Backend,
     arena: &'a Bump,
+    next_id: usize,
+    stats: SearchStats,
+    limits: SearchLimits,
+    budget: SearchLimits,
 }
 
 impl<'a, P, Backend> TreeExplorer<'a, P, Backend>
@@ -298,32 +653,58 @@ where
     pub fn new(problem: &'a P, arena: &'a Bump) -> Self {
         Self {
             problem: problem,
+            observer: Box::new(NoObserver),
             frontier: Default::default(),
             arena: arena,
+            next_id: 0,
+            stats: SearchStats::default(),
+            limits: SearchLimits::default(),
+            budget: SearchLimits::default(),
         }
     }
 
+    pub fn set_observer(&mut self, observer: impl SearchObserver<P> + 'a) {
+        self.observer = Box::new(observer);
+    }
+
     pub fn iterative_search(
         &mut self,
         init_state: P::State,
         max_limit: usize,
     ) -> SearchResult<P::State, P::Action> {
+        self.try_iterative_search(init_state, max_limit)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_iterative_search(
+        &mut self,
+        init_state: P::State,
+        max_limit: usize,
+    ) -> Result<SearchResult<P::State, P::Action>> {
         let mut lim = 1;
         let mut result = SearchResult::new();
         let start = Instant::now();
         let mut n_iter = 0;
+        self.begin();
         loop {
             if max_limit < lim {
                 result.n_iter = n_iter;
                 result.total_time = start.elapsed();
-                return result;
+                result.stats = self.take_stats();
+                return Ok(result);
             }
-            let inner_result = self.inner_search(&mut n_iter, init_state.clone(), lim.into());
+            let inner_result = self.inner_search(&mut n_iter, init_state, lim.into())?;
             if result.max_frontier_size < inner_result.max_frontier_size {
                 result.max_frontier_size = inner_result.max_frontier_size
             }
-            if inner_result.actions.is_some() {
-                return SearchResult::from_inner_result(start, n_iter, inner_result);
+            if inner_result.actions.is_some() || inner_result.limit.is_some() {
+                let stats = self.take_stats();
+                return Ok(SearchResult::from_inner_result(
+                    start,
+                    n_iter,
+                    stats,
+                    inner_result,
+                ));
             }
             lim += 1
         }
@@ -334,17 +715,65 @@ where
         init_state: P::State,
         max_depth: usize,
     ) -> SearchResult<P::State, P::Action> {
+        self.try_search_with_max_depth(init_state, max_depth)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_search_with_max_depth(
+        &mut self,
+        init_state: P::State,
+        max_depth: usize,
+    ) -> Result<SearchResult<P::State, P::Action>> {
         let start = Instant::now();
         let mut n_iter = 0;
-        let result = self.inner_search(&mut n_iter, init_state, max_depth.into());
-        SearchResult::from_inner_result(start, n_iter, result)
+        self.begin();
+        let result = self.inner_search(&mut n_iter, init_state, max_depth.into())?;
+        Ok(SearchResult::from_inner_result(
+            start,
+            n_iter,
+            self.take_stats(),
+            result,
+        ))
     }
 
     pub fn search(&mut self, init_state: P::State) -> SearchResult<P::State, P::Action> {
+        self.try_search(init_state)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_search(
+        &mut self,
+        init_state: P::State,
+    ) -> Result<SearchResult<P::State, P::Action>> {
         let start = Instant::now();
         let mut n_iter = 0;
-        let result = self.inner_search(&mut n_iter, init_state, None);
-        SearchResult::from_inner_result(start, n_iter, result)
+        self.begin();
+        let result = self.inner_search(&mut n_iter, init_state, None)?;
+        Ok(SearchResult::from_inner_result(
+            start,
+            n_iter,
+            self.take_stats(),
+            result,
+        ))
+    }
+
+    fn begin(&mut self) {
+        self.stats = SearchStats::default();
+        self.budget = self.limits.start();
+    }
+
+    fn take_stats(&mut self) -> SearchStats {
+        self.stats.arena_bytes = self.arena.allocated_bytes();
+        self.stats.clone()
+    }
+
+    fn check_limits(&self) -> Option<Limit> {
+        let memory = match self.budget.max_memory {
+            Some(_) => self.arena.allocated_bytes(),
+            None => 0,
+        };
+        self.budget
+            .check(self.stats.expanded, self.stats.generated, memory)
     }
 
     fn inner_search(
@@ -352,47 +781,62 @@ where
         n_iter: &mut usize,
         init_state: P::State,
         lim: Option<usize>,
-    ) -> InnerResult<P::State, P::Action> {
+    ) -> Result<InnerResult<P::State, P::Action>> {
         self.frontier.reset();
-        self.frontier.enqueue(Node::in_arena(
+        let root = Node::try_numbered_in_arena(
             None,
-            &self.problem,
+            self.problem,
             init_state,
             None,
             P::Cost::default(),
+            &mut self.next_id,
             self.arena,
-        ));
+        )?;
+        self.stats.count_generated(0);
+        self.frontier.enqueue(root);
+        self.observer.on_generate(root);
 
         //let mut n_iter = 0;
         let result: InnerResult<P::State, P::Action>;
 
         let mut max_frontier_size = 0;
         while let Some(curr_node) = self.frontier.dequeue() {
+            if let Some(limit) = self.check_limits() {
+                return Ok(InnerResult::stopped(limit, max_frontier_size));
+            }
             *n_iter += 1;
+            self.stats.expanded += 1;
 
             let curr_state = curr_node.get_state();
 
+            self.observer.on_expand(*n_iter, curr_node);
+
             if self.problem.is_suitable(&curr_state) {
+                self.observer
+                    .on_goal(*n_iter, curr_state, curr_node.get_g_cost());
                 result = InnerResult::<P::State, P::Action>::found(
                     *curr_node.get_state(),
                     curr_node.get_plan().into(),
                     max_frontier_size,
                 );
-                return result;
+                return Ok(result);
             } else {
                 let depth = curr_node.get_depth();
                 if lim.map_or(true, |x| x > depth) {
                     for action in self.problem.executable_actions(curr_state) {
                         let (new_state, cost) = self.problem.result(curr_state, &action);
-                        let new_node = Node::in_arena(
+                        let new_node = Node::try_numbered_in_arena(
                             Some(curr_node),
-                            &self.problem,
+                            self.problem,
                             new_state,
                             Some(action),
                             cost,
+                            &mut self.next_id,
                             self.arena,
-                        );
+                        )?;
+                        self.stats.count_generated(depth + 1);
                         self.frontier.enqueue(new_node);
+                        self.observer.on_generate(new_node);
                     }
                 }
             }
@@ -401,7 +845,21 @@ where
             }
         }
         result = InnerResult::<P::State, P::Action>::not_found(max_frontier_size);
-        return result;
+        Ok(result)
+    }
+}
+
+impl<'a, P, Backend> LimitSearch for TreeExplorer<'a, P, Backend>
+where
+    P: SuitableState + Utility,
+    Backend: FrontierBackend<'a, P>,
+{
+    fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    fn limits(&self) -> &SearchLimits {
+        &self.limits
     }
 }
 
@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// Counters collected by the explorers during a search.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchStats {
+    /// Nodes created, the root included.
+    pub generated: usize,
+    pub expanded: usize,
+    /// Successors discarded because their state was already explored.
+    pub duplicates: usize,
+    /// Successors discarded because the frontier already had a cheaper node for their state.
+    pub discarded: usize,
+    /// Frontier nodes replaced by a cheaper node for the same state.
+    pub replaced: usize,
+    /// Explored states moved back to the frontier.
+    pub reopened: usize,
+    /// Bytes allocated in the arena at the end of the search.
+    pub arena_bytes: usize,
+    /// Generated nodes for every depth of the search tree.
+    pub nodes_per_depth: Vec<usize>,
+}
+
+impl SearchStats {
+    pub(crate) fn count_generated(&mut self, depth: usize) {
+        self.generated += 1;
+        if self.nodes_per_depth.len() <= depth {
+            self.nodes_per_depth.resize(depth + 1, 0);
+        }
+        self.nodes_per_depth[depth] += 1;
+    }
+
+    /// Adds the counters of another search, used to combine the workers of a parallel search.
+    pub(crate) fn merge(&mut self, other: &SearchStats) {
+        self.generated += other.generated;
+        self.expanded += other.expanded;
+        self.duplicates += other.duplicates;
+        self.discarded += other.discarded;
+        self.replaced += other.replaced;
+        self.reopened += other.reopened;
+        self.arena_bytes += other.arena_bytes;
+        if self.nodes_per_depth.len() < other.nodes_per_depth.len() {
+            self.nodes_per_depth.resize(other.nodes_per_depth.len(), 0);
+        }
+        for (count, other) in self.nodes_per_depth.iter_mut().zip(&other.nodes_per_depth) {
+            *count += other;
+        }
+    }
+
+    /// The branching factor b* that a uniform tree of depth `depth` would need to contain
+    /// all the generated nodes: N + 1 = 1 + b* + (b*)^2 + ... + (b*)^d, where the root is not counted in N.
+    pub fn effective_branching_factor(&self, depth: usize) -> Option<f64> {
+        let n = self.generated.checked_sub(1)? as f64;
+        if depth == 0 || n < depth as f64 {
+            return None;
+        }
+        let nodes = |b: f64| (1..=depth).map(|i| b.powi(i as i32)).sum::<f64>();
+        let (mut low, mut high) = (1.0, n.max(1.0));
+        for _ in 0..100 {
+            let mid = (low + high) / 2.0;
+            if nodes(mid) < n {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Some((low + high) / 2.0)
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "generated: {}\nexpanded: {}\nduplicates: {}\ndiscarded: {}\nreplaced: {}\nreopened: {}\narena bytes: {}\nnodes per depth: {:?}",
+            self.generated,
+            self.expanded,
+            self.duplicates,
+            self.discarded,
+            self.replaced,
+            self.reopened,
+            self.arena_bytes,
+            self.nodes_per_depth
+        )
+    }
+}
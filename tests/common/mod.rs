@@ -0,0 +1,527 @@
+//! Toy problems shared by the integration tests, every test file uses only some of them.
+#![allow(dead_code)]
+
+use std::{
+    f64::consts::PI,
+    marker::PhantomData,
+    ops::{Add, RangeInclusive},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use agent::{
+    error::Result,
+    problem::{
+        CostructSolution, Crossover, Neighborhoods, PheromoneFeature, Problem, StatePerturbation,
+        SuitableState, Utility,
+    },
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+// Cities on a circle in shuffled order, the shortest tour visits them along the circle.
+// The tour starts and ends in the city 0, the state holds the cities after it.
+pub struct Tsp {
+    points: Vec<(f64, f64)>,
+}
+
+impl Tsp {
+    pub fn new(n: usize) -> Self {
+        let mut slots: Vec<usize> = (0..n).collect();
+        slots.shuffle(&mut StdRng::seed_from_u64(0));
+        let points = slots
+            .into_iter()
+            .map(|slot| {
+                let angle = 2.0 * PI * slot as f64 / n as f64;
+                (10.0 * angle.cos(), 10.0 * angle.sin())
+            })
+            .collect();
+        Self { points }
+    }
+
+    pub fn distance(&self, a: usize, b: usize) -> u32 {
+        let (pa, pb) = (self.points[a], self.points[b]);
+        (100.0 * ((pa.0 - pb.0).powi(2) + (pa.1 - pb.1).powi(2)).sqrt()).round() as u32
+    }
+
+    pub fn optimum(&self) -> u32 {
+        let n = self.points.len();
+        // every side of the regular polygon has the same length
+        n as u32 * self.distance(0, self.nearest(0))
+    }
+
+    pub fn nearest(&self, a: usize) -> usize {
+        (0..self.points.len())
+            .filter(|b| *b != a)
+            .min_by_key(|b| self.distance(a, *b))
+            .unwrap()
+    }
+}
+
+impl Problem for Tsp {
+    type State = Vec<usize>;
+}
+
+impl CostructSolution for Tsp {
+    type Action = usize;
+    type Cost = u32;
+
+    fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+        let n = self.points.len();
+        let cities: Vec<usize> = if state.len() + 1 == n {
+            vec![0]
+        } else {
+            (1..n).filter(|c| !state.contains(c)).collect()
+        };
+        cities.into_iter()
+    }
+
+    fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+        let last = state.last().copied().unwrap_or(0);
+        let mut state = state.clone();
+        state.push(*action);
+        (state, self.distance(last, *action))
+    }
+}
+
+impl SuitableState for Tsp {
+    fn is_suitable(&self, state: &Self::State) -> bool {
+        state.len() == self.points.len()
+    }
+}
+
+impl PheromoneFeature for Tsp {
+    type Feature = (usize, usize);
+
+    fn feature(&self, state: &Self::State, action: &Self::Action) -> Self::Feature {
+        let last = state.last().copied().unwrap_or(0);
+        (last.min(*action), last.max(*action))
+    }
+
+    fn desirability(&self, state: &Self::State, action: &Self::Action) -> f64 {
+        let last = state.last().copied().unwrap_or(0);
+        1.0 / self.distance(last, *action) as f64
+    }
+}
+
+pub fn tour_cost(problem: &Tsp, tour: &[usize]) -> u32 {
+    let mut last = 0;
+    let mut cost = 0;
+    for city in tour {
+        cost += problem.distance(last, *city);
+        last = *city;
+    }
+    cost
+}
+
+// Queens placed one column at a time and then moved inside their column, the heuristic counts
+// the conflicts of the placed ones. `with_delta` enables the delta evaluation. The second
+// neighborhood swaps two columns.
+pub struct Queens {
+    pub n: usize,
+    delta: bool,
+    /// Calls to `heuristic`.
+    pub full_evaluations: AtomicUsize,
+    /// States built by `perturb` and `perturb_in_place`.
+    pub built: AtomicUsize,
+}
+
+impl Queens {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            delta: false,
+            full_evaluations: AtomicUsize::new(0),
+            built: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_delta(n: usize) -> Self {
+        Self {
+            delta: true,
+            ..Self::new(n)
+        }
+    }
+
+    fn conflicts_at(state: &[usize], col: usize, row: usize) -> i32 {
+        let mut conflicts = 0;
+        for (other_col, other_row) in state.iter().enumerate() {
+            if other_col != col
+                && (*other_row == row || other_row.abs_diff(row) == other_col.abs_diff(col))
+            {
+                conflicts += 1;
+            }
+        }
+        conflicts
+    }
+}
+
+impl Problem for Queens {
+    type State = Vec<usize>;
+}
+
+impl CostructSolution for Queens {
+    type Action = usize;
+    type Cost = i32;
+
+    fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+        let rows = if state.len() < self.n { self.n } else { 0 };
+        0..rows
+    }
+
+    fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+        let mut state = state.clone();
+        state.push(*action);
+        (state, 0)
+    }
+}
+
+impl Utility for Queens {
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        self.full_evaluations.fetch_add(1, Ordering::Relaxed);
+        let mut conflicts = 0;
+        for i in 0..state.len() {
+            for j in (i + 1)..state.len() {
+                if state[i] == state[j] || state[i].abs_diff(state[j]) == i.abs_diff(j) {
+                    conflicts += 1;
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+impl SuitableState for Queens {
+    fn is_suitable(&self, state: &Self::State) -> bool {
+        state.len() == self.n
+    }
+}
+
+impl StatePerturbation for Queens {
+    type Perturbation = (usize, usize);
+
+    fn perturbations(&self, state: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+        let n = self.n;
+        (0..state.len())
+            .flat_map(move |col| (0..n).map(move |row| (col, row)))
+            .filter(move |(col, row)| state[*col] != *row)
+    }
+
+    fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
+        self.built.fetch_add(1, Ordering::Relaxed);
+        let mut state = state.clone();
+        state[action.0] = action.1;
+        state
+    }
+
+    fn perturb_in_place(&self, state: &mut Self::State, action: &Self::Perturbation) {
+        self.built.fetch_add(1, Ordering::Relaxed);
+        state[action.0] = action.1;
+    }
+
+    fn delta_heuristic(
+        &self,
+        state: &Self::State,
+        h: i32,
+        action: &Self::Perturbation,
+    ) -> Option<Result<i32>> {
+        let (col, row) = *action;
+        self.delta.then(|| {
+            Ok(
+                h - Self::conflicts_at(state, col, state[col])
+                    + Self::conflicts_at(state, col, row),
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Move {
+    Row(usize, usize),
+    Swap(usize, usize),
+}
+
+impl Neighborhoods for Queens {
+    type Move = Move;
+
+    fn neighborhoods(&self) -> usize {
+        2
+    }
+
+    fn neighbors(&self, k: usize, state: &Self::State) -> impl Iterator<Item = Self::Move> {
+        let n = self.n;
+        let state = state.clone();
+        (0..n).flat_map(move |i| {
+            let state = state.clone();
+            (0..n).filter_map(move |j| match k {
+                0 if state[i] != j => Some(Move::Row(i, j)),
+                1 if i < j => Some(Move::Swap(i, j)),
+                _ => None,
+            })
+        })
+    }
+
+    fn apply(&self, state: &Self::State, m: &Self::Move) -> Self::State {
+        let mut state = state.clone();
+        match *m {
+            Move::Row(col, row) => state[col] = row,
+            Move::Swap(i, j) => state.swap(i, j),
+        }
+        state
+    }
+}
+
+// Steps of one on the integers towards `target`, inside `range`. With `even` only the even states
+// are suitable.
+pub struct Line {
+    pub target: i32,
+    pub range: RangeInclusive<i32>,
+    pub steps: Vec<i32>,
+    pub even: bool,
+}
+
+impl Line {
+    pub fn new(target: i32) -> Self {
+        Self {
+            target,
+            range: i32::MIN..=i32::MAX,
+            steps: vec![-1, 1],
+            even: false,
+        }
+    }
+
+    pub fn within(self, range: RangeInclusive<i32>) -> Self {
+        Self { range, ..self }
+    }
+
+    pub fn even(self) -> Self {
+        Self { even: true, ..self }
+    }
+}
+
+impl Problem for Line {
+    type State = i32;
+}
+
+impl CostructSolution for Line {
+    type Action = ();
+    type Cost = i32;
+
+    fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+        std::iter::empty()
+    }
+
+    fn result(&self, state: &Self::State, _: &Self::Action) -> (Self::State, Self::Cost) {
+        (*state, 0)
+    }
+}
+
+impl Utility for Line {
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        (self.target - state).abs()
+    }
+}
+
+impl SuitableState for Line {
+    fn is_suitable(&self, state: &Self::State) -> bool {
+        !self.even || state % 2 == 0
+    }
+}
+
+impl StatePerturbation for Line {
+    type Perturbation = i32;
+
+    fn perturbations(&self, state: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+        let state = *state;
+        let range = self.range.clone();
+        self.steps.clone().into_iter().filter(move |d| {
+            state
+                .checked_add(*d)
+                .is_some_and(|next| range.contains(&next))
+        })
+    }
+
+    fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
+        state + action
+    }
+}
+
+impl Crossover for Line {
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        _rng: &mut R,
+        state: &Self::State,
+        other: &Self::State,
+    ) -> Self::State {
+        (state + other) / 2
+    }
+}
+
+// Strings of n bits, the goal has all of them set. The heuristic counts the unset bits as a `C`.
+pub struct Bits<C> {
+    pub n: usize,
+    /// Calls to `heuristic`.
+    pub evaluations: AtomicUsize,
+    cost: PhantomData<C>,
+}
+
+impl<C> Bits<C> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            evaluations: AtomicUsize::new(0),
+            cost: PhantomData,
+        }
+    }
+}
+
+impl<C> Problem for Bits<C> {
+    type State = Vec<bool>;
+}
+
+impl<C> CostructSolution for Bits<C>
+where
+    C: Default + Copy + Ord + Add<Output = C>,
+{
+    type Action = bool;
+    type Cost = C;
+
+    fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+        let bits: &[bool] = if state.len() < self.n {
+            &[false, true]
+        } else {
+            &[]
+        };
+        bits.iter().copied()
+    }
+
+    fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+        let mut state = state.clone();
+        state.push(*action);
+        (state, C::default())
+    }
+}
+
+impl<C> Utility for Bits<C>
+where
+    C: Default + Copy + Ord + Add<Output = C> + TryFrom<usize>,
+{
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        self.evaluations.fetch_add(1, Ordering::Relaxed);
+        let unset = state.iter().filter(|bit| !**bit).count();
+        C::try_from(unset).unwrap_or_else(|_| panic!("{} unset bits", unset))
+    }
+}
+
+impl<C> SuitableState for Bits<C> {
+    fn is_suitable(&self, state: &Self::State) -> bool {
+        state.len() == self.n
+    }
+}
+
+impl<C> StatePerturbation for Bits<C> {
+    type Perturbation = usize;
+
+    fn perturbations(&self, state: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+        0..state.len()
+    }
+
+    fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
+        let mut state = state.clone();
+        state[*action] = !state[*action];
+        state
+    }
+}
+
+impl<C> Crossover for Bits<C> {
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        state: &Self::State,
+        other: &Self::State,
+    ) -> Self::State {
+        let point = rng.random_range(0..self.n);
+        let mut child = state[..point].to_vec();
+        child.extend_from_slice(&other[point..]);
+        child
+    }
+}
+
+// Steps on the integers towards `goal`, never past `max`; a negative goal is never reached. Every
+// step costs its length and the heuristic divides the distance by the longest step.
+pub struct Walk {
+    pub goal: i32,
+    pub max: i32,
+    pub steps: Vec<i32>,
+}
+
+impl Walk {
+    /// Steps of one or two.
+    pub fn new(goal: i32) -> Self {
+        Self {
+            goal,
+            max: i32::MAX,
+            steps: vec![1, 2],
+        }
+    }
+
+    pub fn bounded(self, max: i32) -> Self {
+        Self { max, ..self }
+    }
+
+    pub fn with_steps(self, steps: Vec<i32>) -> Self {
+        Self { steps, ..self }
+    }
+}
+
+impl Problem for Walk {
+    type State = i32;
+}
+
+impl CostructSolution for Walk {
+    type Action = i32;
+    type Cost = i32;
+
+    fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+        let (state, max) = (*state, self.max);
+        self.steps
+            .clone()
+            .into_iter()
+            .filter(move |step| state.checked_add(*step).is_some_and(|next| next <= max))
+    }
+
+    fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+        (state + action, action.abs())
+    }
+}
+
+impl Utility for Walk {
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        let longest = self.steps.iter().map(|step| step.abs()).max().unwrap_or(1);
+        (self.goal - state).abs() / longest
+    }
+}
+
+impl SuitableState for Walk {
+    fn is_suitable(&self, state: &Self::State) -> bool {
+        *state == self.goal
+    }
+}
+
+impl PheromoneFeature for Walk {
+    type Feature = i32;
+
+    fn feature(&self, _: &Self::State, action: &Self::Action) -> Self::Feature {
+        *action
+    }
+}
+
+impl StatePerturbation for Walk {
+    type Perturbation = i32;
+
+    fn perturbations(&self, _: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+        vec![-1, 1].into_iter()
+    }
+
+    fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
+        state + action
+    }
+}
@@ -0,0 +1,163 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        improve::{
+            algorithms::{
+                AdaptiveAcceptance, AnnealingConfig, Cooling, CoolingSchedule, ImprovingAlgorithm,
+                Reheat, SimulatedAnnealing,
+            },
+            resolver::{AttemptOutcome, Progress, Resolver},
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::Line;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(3)
+    }
+
+    struct Steps {
+        t0: f64,
+        every: usize,
+    }
+
+    impl CoolingSchedule for Steps {
+        fn temperature(&self, t: usize) -> f64 {
+            self.t0 / 2f64.powi((t / self.every) as i32)
+        }
+    }
+
+    #[test]
+    fn test_schedules() {
+        let log = Cooling::Logarithmic { t0: 2.0 };
+        assert_eq!(log.temperature(0), 2.0);
+        assert!(log.temperature(100) < log.temperature(10));
+
+        let lundy = Cooling::LundyMees {
+            t0: 10.0,
+            beta: 0.5,
+        };
+        let mut expected = 10.0;
+        for t in 0..5 {
+            assert!((lundy.temperature(t) - expected).abs() < 1e-9);
+            expected /= 1.0 + 0.5 * expected;
+        }
+
+        let linear = Cooling::Linear { t0: 4.0, steps: 4 };
+        assert_eq!(linear.temperature(2), 2.0);
+        assert_eq!(linear.temperature(8), 0.0);
+    }
+
+    #[test]
+    fn test_custom_schedule() {
+        let problem = Line::new(20).within(-50..=50);
+        let algo = SimulatedAnnealing::with_schedule(rng(), Steps { t0: 1.0, every: 50 });
+        assert_eq!(algo.cooling().every, 50);
+        let result = Resolver::new(algo).resolve_restart(&problem, 10);
+        assert!(result.is_goal());
+
+        let closure = |t: usize| 1.0 / (t as f64 + 1.0);
+        let mut algo = SimulatedAnnealing::with_schedule(rng(), closure);
+        let result = algo.attempt(&problem);
+        assert!(result.iterations > 0);
+    }
+
+    #[test]
+    fn test_initial_temperature() {
+        // every uphill move costs 1, accepted with probability 1/2 at the start
+        let problem = Line::new(20).within(-50..=50);
+        let config = AnnealingConfig {
+            initial_acceptance: Some(0.5),
+            ..AnnealingConfig::default()
+        };
+        let cooling = Cooling::Exponential {
+            t0: 2.0,
+            alpha: 0.99,
+        };
+        let mut algo = SimulatedAnnealing::from_config(rng(), cooling, config);
+        algo.set_limits(SearchLimits::new().with_max_expansions(1));
+        let result = algo.attempt(&problem);
+        let annealing = result.progress.unwrap().annealing.unwrap();
+        let t0 = annealing.scale * 2.0;
+        assert!((t0 - 1.0 / 2f64.ln()).abs() < 1e-9);
+        assert_eq!(annealing.scale, annealing.initial_scale);
+    }
+
+    #[test]
+    fn test_reheat() {
+        // the goal is out of reach, the best state never improves after the start
+        let problem = Line::new(1000).within(-50..=50);
+        let cooling = Cooling::Exponential {
+            t0: 1.0,
+            alpha: 0.9,
+        };
+        let config = AnnealingConfig {
+            reheat: Some(Reheat { after: 5, max: 3 }),
+            ..AnnealingConfig::default()
+        };
+        let mut algo = SimulatedAnnealing::from_config(rng(), cooling, config);
+        let mut plain = SimulatedAnnealing::with_schedule(rng(), cooling);
+        let start = vec![50];
+        let reheated = algo.resume(&problem, Progress::new(start.clone()));
+        let cold = plain.resume(&problem, Progress::new(start));
+        assert_eq!(reheated.outcome, AttemptOutcome::LocalOptimum);
+        assert!(reheated.iterations > cold.iterations);
+
+        algo.set_limits(SearchLimits::new().with_max_expansions(50));
+        let result = algo.resume(&problem, Progress::new(vec![50]));
+        let annealing = result.progress.unwrap().annealing.unwrap();
+        assert_eq!(annealing.reheats, 3);
+    }
+
+    #[test]
+    fn test_adaptive_acceptance() {
+        // a hot schedule accepts almost every uphill move, the scale goes down towards the target
+        let problem = Line::new(1000).within(-50..=50);
+        let config = AnnealingConfig {
+            adaptive: Some(AdaptiveAcceptance {
+                target: 0.2,
+                window: 10,
+            }),
+            ..AnnealingConfig::default()
+        };
+        let hot = Cooling::Exponential {
+            t0: 100.0,
+            alpha: 0.9999,
+        };
+        let mut algo = SimulatedAnnealing::from_config(rng(), hot, config);
+        algo.set_limits(SearchLimits::new().with_max_expansions(2000));
+        let result = algo.attempt(&problem);
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        let annealing = result.progress.unwrap().annealing.unwrap();
+        assert!(annealing.scale < annealing.initial_scale);
+        assert!(annealing.scale >= annealing.initial_scale / 100.0);
+        assert_eq!(algo.config().adaptive.unwrap().window, 10);
+    }
+
+    #[test]
+    fn test_resume_schedule() {
+        let problem = Line::new(1000).within(-50..=50);
+        let cooling = Cooling::LundyMees { t0: 5.0, beta: 0.1 };
+        let config = AnnealingConfig {
+            reheat: Some(Reheat { after: 20, max: 2 }),
+            ..AnnealingConfig::default()
+        };
+        let mut full = SimulatedAnnealing::from_config(rng(), cooling, config);
+        let expected = full.resume(&problem, Progress::new(vec![50]));
+
+        let mut algo = SimulatedAnnealing::from_config(rng(), cooling, config);
+        algo.set_limits(SearchLimits::new().with_max_expansions(30));
+        let stopped = algo.resume(&problem, Progress::new(vec![50]));
+        let progress = stopped.progress.unwrap();
+        assert_eq!(progress.annealing.unwrap().reheats, 1);
+
+        let mut resumed = SimulatedAnnealing::from_config(algo.rng().clone(), cooling, config);
+        let result = resumed.resume(&problem, progress);
+        assert_eq!(result.state, expected.state);
+        assert_eq!(result.iterations, expected.iterations);
+    }
+}
@@ -0,0 +1,136 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        error::Error,
+        improve::{
+            algorithms::{HillClimbing, SteepestDescend},
+            benchmark::{
+                mann_whitney, wilson_interval, Benchmark, BenchmarkReport, Distribution, Metric,
+            },
+        },
+        limits::{LimitSearch, SearchLimits},
+    };
+
+    use crate::common::Line;
+
+    #[test]
+    fn test_statistics() {
+        let d = Distribution::of(&[4.0, 1.0, 3.0, 2.0], 0.95).unwrap();
+        assert_eq!((d.mean, d.median, d.min, d.max), (2.5, 2.5, 1.0, 4.0));
+        assert!((d.std_dev - 1.2910).abs() < 1e-4);
+        // 1.96 * sd / sqrt(4)
+        assert!((d.ci.1 - d.mean - 1.2652).abs() < 1e-3, "{:?}", d.ci);
+        assert_eq!(Distribution::of(&[], 0.95), None);
+
+        let (low, high) = wilson_interval(8, 10, 0.95);
+        assert!((low - 0.4902).abs() < 1e-3 && (high - 0.9433).abs() < 1e-3);
+        assert_eq!(wilson_interval(0, 0, 0.95), (0.0, 1.0));
+
+        let a: Vec<f64> = (0..10).map(f64::from).collect();
+        let b: Vec<f64> = (10..20).map(f64::from).collect();
+        let test = mann_whitney(&a, &b).unwrap();
+        assert_eq!(test.u, 0.0);
+        assert!(test.p_value < 0.001);
+        assert!(mann_whitney(&a, &a).unwrap().p_value > 0.9);
+        assert_eq!(mann_whitney(&a, &[]), None);
+    }
+
+    #[test]
+    fn test_grid() {
+        let near = Line::new(3).within(-10..=10);
+        let far = Line::new(-8).within(-10..=10);
+        let benchmark = Benchmark::new(0..5)
+            .with_instance("near", &near)
+            .with_instance("far", &far)
+            .with_algorithm("steepest", "", SteepestDescend::new)
+            .with_parameters("hill", [("lateral 0", 0), ("lateral 10", 10)], |l, rng| {
+                HillClimbing::with_max_lateral(rng, *l)
+            });
+        let report = benchmark.run();
+        assert_eq!(report.runs.len(), 2 * 3 * 5);
+        assert!(report.runs.iter().all(|run| run.success && run.h == 0.0));
+
+        let summaries = report.summaries();
+        assert_eq!(summaries.len(), 6);
+        assert_eq!(summaries[1].label(), "hill (lateral 0)");
+        for s in &summaries {
+            assert_eq!((s.runs, s.successes, s.success_rate), (5, 5, 1.0));
+            assert!(s.success_ci.0 > 0.5 && s.success_ci.1 == 1.0);
+            assert!(s.time_to_target.is_some());
+        }
+        assert_eq!(summaries[0].iterations.median, 4.0);
+        assert_eq!(summaries[3].iterations.median, 9.0);
+
+        // three candidates, three pairs on every instance
+        let comparisons = report.compare(Metric::Iterations);
+        assert_eq!(comparisons.len(), 6);
+        assert!(comparisons.iter().all(|c| c.test.p_value == 1.0));
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| far | hill (lateral 10) | 5 |"));
+        assert!(markdown.contains("Mann–Whitney U test on the cost"));
+
+        let mut csv = Vec::new();
+        report.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 31);
+        assert_eq!(csv.lines().next(), Some(BenchmarkReport::CSV_HEADER));
+        let mut summary = Vec::new();
+        report.write_summary_csv(&mut summary).unwrap();
+        assert_eq!(String::from_utf8(summary).unwrap().lines().count(), 7);
+    }
+
+    #[test]
+    fn test_limits_target_and_restarts() {
+        let problem = Line::new(5).within(-10..=10);
+        let mut benchmark = Benchmark::new([1, 2, 3])
+            .with_instance("line", &problem)
+            .with_algorithm("steepest", "", SteepestDescend::new);
+        benchmark.set_limits(SearchLimits::new().with_max_expansions(2));
+        let report = benchmark.run();
+        let summary = &report.summaries()[0];
+        assert_eq!(summary.success_rate, 0.0);
+        assert_eq!(summary.time_to_target, None);
+        assert_eq!(summary.cost.mean, 3.0);
+        assert!(report.to_markdown().contains("| - |"));
+
+        // the limits stay with the benchmark
+        let benchmark = benchmark.with_target(3).with_restarts(3);
+        let summary = &benchmark.run().summaries()[0];
+        assert_eq!(summary.success_rate, 1.0);
+        assert!(summary.time_to_target.is_some());
+
+        // the restarts run only while the target is missed
+        let benchmark = benchmark.with_target(0);
+        let restarted = &benchmark.run().summaries()[0];
+        assert!(restarted.iterations.mean > summary.iterations.mean);
+
+        let empty: Benchmark<Line> = Benchmark::new([0]).with_instance("line", &problem);
+        assert!(matches!(empty.try_run(), Err(Error::Invariant(_))));
+        let invalid = Benchmark::new([0])
+            .with_instance("line", &problem)
+            .with_algorithm("steepest", "", SteepestDescend::new)
+            .with_confidence(1.0);
+        assert!(matches!(invalid.try_run(), Err(Error::InvalidState(_))));
+    }
+
+    #[test]
+    fn test_verified_goals() {
+        // h = 0 in 11, which is not suitable
+        let problem = Line::new(11).within(-20..=20).even();
+        let benchmark = Benchmark::new(0..3)
+            .with_instance("odd", &problem)
+            .with_algorithm("steepest", "", SteepestDescend::new);
+        assert!(benchmark.run().runs.iter().all(|run| run.success));
+
+        let benchmark = benchmark.verified();
+        let report = benchmark.run();
+        assert!(report.runs.iter().all(|run| !run.success && run.h == 0.0));
+
+        // a failed attempt misses the target too
+        let summary = &benchmark.with_target(1).run().summaries()[0];
+        assert_eq!(summary.success_rate, 0.0);
+    }
+}
@@ -0,0 +1,163 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        improve::algorithms::{ImprovingAlgorithm, SimulatedAnnealing},
+        limits::{Limit, LimitSearch, SearchLimits},
+        statexplorer::resolver::{AStarExplorer, BFSExplorer, DFSExplorer, SearchOutcome},
+    };
+    use bumpalo::Bump;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::{Line, Walk};
+
+    // Steps of one, two or three, or one back, towards 20.
+    fn walk() -> Walk {
+        Walk::new(20).with_steps(vec![-1, 1, 2, 3])
+    }
+
+    #[test]
+    fn test_explorer_resume() {
+        let problem = walk();
+        let arena = Bump::new();
+        let full = BFSExplorer::new(&problem, &arena).search(0);
+
+        let arena = Bump::new();
+        let mut explorer = BFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_expansions(10));
+        let stopped = explorer.search(0);
+        assert_eq!(
+            stopped.outcome,
+            SearchOutcome::LimitReached(Limit::Expansions)
+        );
+        let checkpoint = explorer.checkpoint();
+        drop(explorer);
+        drop(arena);
+
+        // the limits count from the checkpoint, so the same budget lets it expand 10 more nodes
+        let arena = Bump::new();
+        let mut explorer = BFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_expansions(10));
+        let result = explorer.resume(checkpoint.clone());
+        assert_eq!(
+            result.outcome,
+            SearchOutcome::LimitReached(Limit::Expansions)
+        );
+        assert_eq!(result.n_iter, 20);
+
+        let arena = Bump::new();
+        let mut explorer = BFSExplorer::new(&problem, &arena);
+        let result = explorer.resume(checkpoint);
+        assert_eq!(result.outcome, SearchOutcome::Solved);
+        assert_eq!(result.actions, full.actions);
+        assert_eq!(result.n_iter, full.n_iter);
+        assert_eq!(result.stats.generated, full.stats.generated);
+    }
+
+    #[test]
+    fn test_astar_resume_is_optimal() {
+        let problem = walk();
+        let arena = Bump::new();
+        let full = AStarExplorer::new(&problem, &arena).search(0);
+
+        let mut explorer = AStarExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_expansions(3));
+        explorer.search(0);
+        let checkpoint = explorer.checkpoint();
+        assert!(checkpoint.nodes.len() >= checkpoint.frontier.len());
+
+        let arena = Bump::new();
+        let result = AStarExplorer::new(&problem, &arena).resume(checkpoint);
+        let cost: i32 = result.actions.unwrap().iter().map(|a| a.abs()).sum();
+        let full_cost: i32 = full.actions.unwrap().iter().map(|a| a.abs()).sum();
+        assert_eq!(cost, full_cost);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_checkpoint_json_round_trip() {
+        use agent::statexplorer::checkpoint::Checkpoint;
+
+        let problem = walk();
+        let arena = Bump::new();
+        let mut explorer = BFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_expansions(10));
+        explorer.search(0);
+        let checkpoint = explorer.checkpoint();
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let back: Checkpoint<i32, i32, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, checkpoint);
+    }
+
+    fn slow(t: usize) -> f64 {
+        100.0 / (t as f64 + 1.0)
+    }
+
+    #[test]
+    fn test_annealing_resume() {
+        let problem = Line::new(30);
+        let mut algo = SimulatedAnnealing::with_cooling(StdRng::seed_from_u64(7), slow);
+        let full = algo.attempt(&problem);
+        assert!(full.iterations > 50);
+
+        let mut algo = SimulatedAnnealing::with_cooling(StdRng::seed_from_u64(7), slow);
+        algo.set_limits(SearchLimits::new().with_max_expansions(50));
+        let stopped = algo.attempt(&problem);
+        assert_eq!(stopped.limit, Some(Limit::Expansions));
+        let progress = stopped.progress.unwrap();
+        assert_eq!(progress.iteration, 50);
+        assert_eq!(progress.seed, None);
+
+        // the random generator of the algorithm is left alone, so it continues the same attempt
+        algo.set_limits(SearchLimits::new());
+        let result = algo.resume(&problem, progress);
+        assert_eq!(result.state, full.state);
+        assert_eq!(result.h, full.h);
+        assert_eq!(result.iterations, full.iterations);
+
+        let mut algo =
+            SimulatedAnnealing::with_cooling(StdRng::seed_from_u64(7), slow).with_rng_checkpoints();
+        algo.set_limits(SearchLimits::new().with_max_expansions(50));
+        let progress = algo.attempt(&problem).progress.unwrap();
+        assert!(progress.seed.is_some());
+
+        // the random generator starts again from the seed saved in the progress, whatever the
+        // one of the algorithm, so a new process continues like the interrupted one
+        let mut resumed = SimulatedAnnealing::with_cooling(StdRng::seed_from_u64(99), slow)
+            .with_rng_checkpoints();
+        let result = resumed.resume(&problem, progress.clone());
+        algo.set_limits(SearchLimits::new());
+        let continued = algo.resume(&problem, progress);
+        assert_eq!(result.state, continued.state);
+        assert_eq!(result.h, continued.h);
+        assert_eq!(result.iterations, continued.iterations);
+        assert!(result.iterations > 50);
+    }
+
+    #[test]
+    fn test_iterative_resume() {
+        let problem = walk();
+        let arena = Bump::new();
+        let full = DFSExplorer::new(&problem, &arena).iterative_search(0, 10);
+        assert_eq!(full.outcome, SearchOutcome::Solved);
+
+        let arena = Bump::new();
+        let mut explorer = DFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_expansions(30));
+        explorer.iterative_search(0, 10);
+        let checkpoint = explorer.checkpoint();
+        assert_eq!(checkpoint.depth_limit, Some(4));
+        assert_eq!(checkpoint.deepening.as_ref().map(|d| d.max_limit), Some(10));
+        drop(explorer);
+
+        // the deepening goes on after the interrupted depth limit
+        let arena = Bump::new();
+        let result = DFSExplorer::new(&problem, &arena).resume(checkpoint);
+        assert_eq!(result.outcome, SearchOutcome::Solved);
+        assert_eq!(result.actions, full.actions);
+        assert_eq!(result.n_iter, full.n_iter);
+        assert_eq!(result.stats.generated, full.stats.generated);
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        improve::colony::{AntColony, AntConfig, AntVariant},
+        limits::{Limit, LimitSearch, SearchLimits},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::{tour_cost, Tsp, Walk};
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(6)
+    }
+
+    #[test]
+    fn test_ant_system() {
+        let problem = Tsp::new(12);
+        let mut colony = AntColony::new(rng(), AntConfig::default());
+        let result = colony.solve(&problem);
+        let best = result.best.unwrap();
+        assert_eq!(best.cost, problem.optimum());
+        assert_eq!(best.cost, tour_cost(&problem, &best.actions));
+        assert_eq!(best.state, best.actions);
+        assert_eq!(result.iterations, 100);
+        assert_eq!(result.completed, 100 * 20);
+        assert!(!colony.pheromones().is_empty());
+    }
+
+    #[test]
+    fn test_endless_walks() {
+        // the goal is behind the ants, every walk stops after `max_steps`
+        let config = AntConfig {
+            ants: 3,
+            max_iter: Some(2),
+            max_steps: 100,
+            ..AntConfig::default()
+        };
+        let result = AntColony::new(rng(), config).solve(&Walk::new(-1));
+        assert_eq!(result.best, None);
+        assert_eq!(result.completed, 0);
+        assert_eq!(result.iterations, 2);
+    }
+
+    #[test]
+    fn test_max_min() {
+        let problem = Tsp::new(16);
+        let config = AntConfig {
+            variant: AntVariant::MaxMin { ratio: 50.0 },
+            evaporation: 0.05,
+            initial: 100.0,
+            ..AntConfig::default()
+        };
+        let mut colony = AntColony::new(rng(), config);
+        let best = colony.solve(&problem).best.unwrap();
+        assert_eq!(best.cost, problem.optimum());
+
+        // every pheromone between the bounds of the best tour
+        let max = 1.0 / (0.05 * (1.0 + best.cost as f64));
+        let pheromones = colony.pheromones();
+        assert!((pheromones.default_value() - max / 50.0).abs() < 1e-12);
+        for (a, b) in [(0, problem.nearest(0)), (1, 2), (3, 9)] {
+            let tau = pheromones.get(&(a.min(b), a.max(b)));
+            assert!(tau >= max / 50.0 - 1e-12 && tau <= max + 1e-12);
+        }
+        let edge = (0, problem.nearest(0));
+        // an edge of the best tour, reinforced in every iteration, approaches the upper bound
+        assert!(pheromones.get(&edge) > 0.9 * max);
+    }
+
+    #[test]
+    fn test_limits() {
+        let problem = Tsp::new(10);
+        let config = AntConfig {
+            max_iter: None,
+            ants: 7,
+            ..AntConfig::default()
+        };
+        let mut colony = AntColony::new(rng(), config);
+        colony.set_limits(SearchLimits::new().with_max_generated(30));
+        let result = colony.solve(&problem);
+        assert_eq!(result.limit, Some(Limit::Generated));
+        assert_eq!(result.iterations, 5);
+        assert_eq!(result.completed, 35);
+        assert!(result.best.is_some());
+    }
+}
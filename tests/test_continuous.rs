@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use agent::{
+        error::Error,
+        improve::continuous::{
+            CmaEs, CmaEsConfig, ContinuousOptimizer, DeConfig, DifferentialEvolution, NelderMead,
+            NelderMeadConfig, ParticleSwarm, PsoConfig,
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        problem::ContinuousProblem,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // minimum in the origin, away from the centre of the box where some optimizers start
+    struct Sphere {
+        n: usize,
+    }
+
+    impl ContinuousProblem for Sphere {
+        fn dimension(&self) -> usize {
+            self.n
+        }
+
+        fn bounds(&self) -> Vec<(f64, f64)> {
+            vec![(-3.0, 5.12); self.n]
+        }
+
+        fn objective(&self, x: &[f64]) -> f64 {
+            x.iter().map(|x| x * x).sum()
+        }
+    }
+
+    // minimum in (1, ..., 1), at the end of a curved valley
+    struct Rosenbrock {
+        n: usize,
+    }
+
+    impl ContinuousProblem for Rosenbrock {
+        fn dimension(&self) -> usize {
+            self.n
+        }
+
+        fn bounds(&self) -> Vec<(f64, f64)> {
+            vec![(-2.048, 2.048); self.n]
+        }
+
+        fn objective(&self, x: &[f64]) -> f64 {
+            x.windows(2)
+                .map(|w| 100.0 * (w[1] - w[0] * w[0]).powi(2) + (1.0 - w[0]).powi(2))
+                .sum()
+        }
+    }
+
+    // a local minimum near every point of the integer grid, the global one in the origin
+    struct Rastrigin {
+        n: usize,
+    }
+
+    impl ContinuousProblem for Rastrigin {
+        fn dimension(&self) -> usize {
+            self.n
+        }
+
+        fn bounds(&self) -> Vec<(f64, f64)> {
+            vec![(-5.12, 5.12); self.n]
+        }
+
+        fn objective(&self, x: &[f64]) -> f64 {
+            10.0 * x.len() as f64
+                + x.iter()
+                    .map(|x| x * x - 10.0 * (2.0 * PI * x).cos())
+                    .sum::<f64>()
+        }
+    }
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(8)
+    }
+
+    fn near(x: &[f64], target: f64, tolerance: f64) -> bool {
+        x.iter().all(|x| (x - target).abs() < tolerance)
+    }
+
+    #[test]
+    fn test_sphere() {
+        let problem = Sphere { n: 5 };
+        let results = [
+            DifferentialEvolution::new(rng(), DeConfig::default()).minimize(&problem),
+            ParticleSwarm::new(rng(), PsoConfig::default()).minimize(&problem),
+            CmaEs::new(rng(), CmaEsConfig::default()).minimize(&problem),
+            NelderMead::new(NelderMeadConfig::default()).minimize(&problem),
+            NelderMead::new(NelderMeadConfig::default()).refine(&problem, vec![3.0; 5]),
+        ];
+        for result in results {
+            assert!(result.value < 1e-6, "{:?}", result);
+            assert!(near(&result.x, 0.0, 1e-3));
+            assert_eq!(result.limit, None);
+        }
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        let problem = Rosenbrock { n: 4 };
+        let de = DeConfig {
+            max_iter: Some(3000),
+            ..DeConfig::default()
+        };
+        let results = [
+            DifferentialEvolution::new(rng(), de).minimize(&problem),
+            CmaEs::new(rng(), CmaEsConfig::default()).minimize(&problem),
+            NelderMead::new(NelderMeadConfig::default()).minimize(&problem),
+        ];
+        for result in results {
+            assert!(result.value < 1e-4, "{:?}", result);
+            assert!(near(&result.x, 1.0, 1e-2));
+        }
+    }
+
+    #[test]
+    fn test_rastrigin() {
+        let problem = Rastrigin { n: 2 };
+        let de = DifferentialEvolution::new(rng(), DeConfig::default()).minimize(&problem);
+        let pso = ParticleSwarm::new(rng(), PsoConfig::default()).minimize(&problem);
+        let cma = CmaEsConfig {
+            lambda: Some(50),
+            sigma: 0.5,
+            ..CmaEsConfig::default()
+        };
+        let cma = CmaEs::new(rng(), cma).minimize(&problem);
+        for result in [de, pso, cma] {
+            assert!(result.value < 1e-6, "{:?}", result);
+            assert!(near(&result.x, 0.0, 1e-3));
+        }
+
+        // from a local minimum, Nelder-Mead with a small simplex stays there
+        let config = NelderMeadConfig {
+            step: 0.01,
+            ..NelderMeadConfig::default()
+        };
+        let refined = NelderMead::new(config).refine(&problem, vec![1.1, 0.9]);
+        assert!(near(&refined.x, 1.0, 0.05));
+        assert!(refined.value < problem.objective(&[1.1, 0.9]));
+    }
+
+    #[test]
+    fn test_limits_and_errors() {
+        let problem = Sphere { n: 10 };
+        let mut algo = CmaEs::new(rng(), CmaEsConfig::default());
+        algo.set_limits(SearchLimits::new().with_max_expansions(3));
+        let result = algo.minimize(&problem);
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        assert_eq!(result.iterations, 3);
+
+        let mut algo = ParticleSwarm::new(rng(), PsoConfig::default());
+        algo.set_limits(SearchLimits::new().with_max_generated(100));
+        let result = algo.minimize(&problem);
+        assert_eq!(result.limit, Some(Limit::Generated));
+        assert_eq!(result.evaluated, 120);
+        assert_eq!(result.value, problem.objective(&result.x));
+
+        // every mutation needs three individuals other than the target
+        let config = DeConfig {
+            population: 4,
+            max_iter: Some(10),
+            ..DeConfig::default()
+        };
+        let result = DifferentialEvolution::new(rng(), config).minimize(&problem);
+        assert_eq!(result.evaluated, 4 + 4 * result.iterations);
+        let config = DeConfig {
+            population: 3,
+            ..config
+        };
+        let mut algo = DifferentialEvolution::new(rng(), config);
+        assert!(matches!(
+            algo.try_minimize(&problem),
+            Err(Error::InvalidState(_))
+        ));
+
+        let mut algo = NelderMead::new(NelderMeadConfig::default());
+        assert!(matches!(
+            algo.try_refine(&problem, vec![0.0; 3]),
+            Err(Error::InvalidState(_))
+        ));
+    }
+}
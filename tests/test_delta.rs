@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use agent::{
+        improve::{
+            algorithms::{
+                HillClimbing, ImprovingAlgorithm, LocalBeam, PerturbationKey, SimulatedAnnealing,
+                SteepestDescend, TabuSearch,
+            },
+            resolver::Progress,
+        },
+        problem::Utility,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::Queens;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(11)
+    }
+
+    // the same seed gives the same attempt with and without delta evaluation
+    fn same_attempt<A>(algo: impl Fn() -> A)
+    where
+        A: ImprovingAlgorithm<Queens>,
+    {
+        let full = Queens::new(8);
+        let delta = Queens::with_delta(8);
+        let expected = algo().attempt(&full);
+        let result = algo().attempt(&delta);
+        assert_eq!(result.state, expected.state);
+        assert_eq!(result.h, expected.h);
+        assert_eq!(result.h, delta.heuristic(&result.state));
+        assert_eq!(result.iterations, expected.iterations);
+        assert!(
+            delta.full_evaluations.load(Ordering::Relaxed) * 10
+                < full.full_evaluations.load(Ordering::Relaxed)
+        );
+    }
+
+    #[test]
+    fn test_steepest_descend() {
+        same_attempt(|| SteepestDescend::new(rng()));
+    }
+
+    #[test]
+    fn test_hill_climbing() {
+        same_attempt(|| HillClimbing::with_max_lateral(rng(), 10));
+    }
+
+    #[test]
+    fn test_simulated_annealing() {
+        same_attempt(|| SimulatedAnnealing::new(rng()));
+    }
+
+    #[test]
+    fn test_local_beam() {
+        same_attempt(|| LocalBeam::from_parts(rng(), 4, Some(50)));
+    }
+
+    #[test]
+    fn test_tabu_search() {
+        same_attempt(|| TabuSearch::from_parts(rng(), PerturbationKey, 3, 50));
+    }
+
+    #[test]
+    fn test_single_evaluation() {
+        // without delta evaluation every neighbour is built once, the accepted ones included
+        let full = Queens::new(8);
+        let result = SteepestDescend::new(rng()).resume(&full, Progress::new(vec![vec![0; 8]]));
+        assert_eq!(
+            full.full_evaluations.load(Ordering::Relaxed),
+            result.evaluated
+        );
+        assert_eq!(full.built.load(Ordering::Relaxed), result.evaluated - 1);
+
+        let delta = Queens::with_delta(8);
+        let result = SteepestDescend::new(rng()).resume(&delta, Progress::new(vec![vec![0; 8]]));
+        assert_eq!(delta.full_evaluations.load(Ordering::Relaxed), 1);
+        assert_eq!(delta.built.load(Ordering::Relaxed), result.iterations - 1);
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use agent::{
+        error::{Error, Result},
+        improve::{algorithms::GeneticAlgorithm, resolver::Resolver},
+        problem::{
+            CostructSolution, Crossover, Problem, StatePerturbation, SuitableState, Utility,
+        },
+        statexplorer::resolver::BFSExplorer,
+    };
+    use bumpalo::Bump;
+    use ordered_float::OrderedFloat;
+    use rand::Rng;
+
+    // Counts up to 4, but the heuristic cannot evaluate the state 3.
+    struct Counter {}
+
+    impl Problem for Counter {
+        type State = i32;
+    }
+
+    impl CostructSolution for Counter {
+        type Action = i32;
+        type Cost = OrderedFloat<f64>;
+
+        fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+            vec![1].into_iter()
+        }
+
+        fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+            (state + action, OrderedFloat(1.0))
+        }
+    }
+
+    impl Utility for Counter {
+        fn heuristic(&self, state: &Self::State) -> Self::Cost {
+            self.try_heuristic(state)
+                .unwrap_or_else(|e| panic!("{}", e))
+        }
+
+        fn try_heuristic(&self, state: &Self::State) -> Result<Self::Cost> {
+            if *state == 3 {
+                return Err(Error::InvalidState("3 cannot be evaluated".to_string()));
+            }
+            Ok(OrderedFloat((4 - state).abs() as f64))
+        }
+    }
+
+    impl SuitableState for Counter {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            *state == 4
+        }
+    }
+
+    impl StatePerturbation for Counter {
+        type Perturbation = i32;
+
+        fn perturbations(&self, _: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+            vec![-1, 1].into_iter()
+        }
+
+        fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
+            state + action
+        }
+    }
+
+    impl Crossover for Counter {
+        fn crossover<R: Rng + ?Sized>(
+            &self,
+            _: &mut R,
+            state: &Self::State,
+            _: &Self::State,
+        ) -> Self::State {
+            *state
+        }
+    }
+
+    #[test]
+    fn test_explorer_reports_invalid_state() {
+        let problem = Counter {};
+        let arena = Bump::new();
+        let mut explorer = BFSExplorer::new(&problem, &arena);
+        let result = explorer.try_search(0);
+        assert!(matches!(result, Err(Error::InvalidState(_))));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid state")]
+    fn test_explorer_panics_without_try() {
+        let problem = Counter {};
+        let arena = Bump::new();
+        BFSExplorer::new(&problem, &arena).search(0);
+    }
+
+    #[test]
+    fn test_empty_population() {
+        let problem = Counter {};
+        let mut resolver =
+            Resolver::new(GeneticAlgorithm::from_parts(rand::rng(), 0, Some(10), 0.1));
+        let result = resolver.try_resolve(&problem);
+        assert!(matches!(result, Err(Error::InvalidWeights(_))));
+    }
+}
@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use std::{fmt, ops::Add};
+
+    use agent::{
+        problem::{CostructSolution, Problem, SuitableState, Utility},
+        statexplorer::{
+            export::{NodeStatus, Snapshot, TreeRecorder},
+            resolver::{AStarExplorer, ReopenPolicy},
+        },
+    };
+    use bumpalo::Bump;
+
+    const EDGES: [(char, char, u32); 5] = [
+        ('S', 'A', 1),
+        ('S', 'B', 3),
+        ('A', 'C', 1),
+        ('B', 'C', 1),
+        ('C', 'G', 3),
+    ];
+
+    struct Graph {}
+
+    impl Problem for Graph {
+        type State = char;
+    }
+
+    impl CostructSolution for Graph {
+        type Action = char;
+        type Cost = u32;
+
+        fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+            let from = *state;
+            EDGES
+                .iter()
+                .filter(move |(f, _, _)| *f == from)
+                .map(|(_, to, _)| *to)
+        }
+
+        fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+            let cost = EDGES
+                .iter()
+                .find(|(f, t, _)| f == state && t == action)
+                .map(|(_, _, c)| *c)
+                .unwrap();
+            (*action, cost)
+        }
+    }
+
+    impl Utility for Graph {
+        fn heuristic(&self, state: &Self::State) -> Self::Cost {
+            match state {
+                'A' => 4,
+                _ => 0,
+            }
+        }
+    }
+
+    impl SuitableState for Graph {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            *state == 'G'
+        }
+    }
+
+    // Counts up to 3, every state has the same `Debug` output and the costs are shown with their sign.
+    struct Counter {}
+
+    #[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    struct Signed(i32);
+
+    impl Add for Signed {
+        type Output = Self;
+
+        fn add(self, other: Self) -> Self {
+            Signed(self.0 + other.0)
+        }
+    }
+
+    impl fmt::Display for Signed {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:+}", self.0)
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct Count(u8);
+
+    impl fmt::Debug for Count {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "count")
+        }
+    }
+
+    impl Problem for Counter {
+        type State = Count;
+    }
+
+    impl CostructSolution for Counter {
+        type Action = ();
+        type Cost = Signed;
+
+        fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+            std::iter::once(())
+        }
+
+        fn result(&self, state: &Self::State, _: &Self::Action) -> (Self::State, Self::Cost) {
+            (Count(state.0 + 1), Signed(1))
+        }
+    }
+
+    impl Utility for Counter {
+        fn heuristic(&self, state: &Self::State) -> Self::Cost {
+            Signed(3 - state.0 as i32)
+        }
+    }
+
+    impl SuitableState for Counter {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            state.0 == 3
+        }
+    }
+
+    fn record(policy: ReopenPolicy) -> TreeRecorder<char> {
+        let problem = Graph {};
+        let arena = Bump::new();
+        let mut recorder = TreeRecorder::with_snapshots();
+        let mut explorer = AStarExplorer::new(&problem, &arena);
+        explorer.set_reopen_policy(policy);
+        explorer.set_observer(&mut recorder);
+        assert!(explorer.search('S').actions.is_some());
+        drop(explorer);
+        recorder
+    }
+
+    #[test]
+    fn test_record_tree_with_reopening() {
+        let recorder = record(ReopenPolicy::Always);
+        let summary: Vec<(&str, Option<usize>, NodeStatus, Option<usize>)> = recorder
+            .nodes()
+            .iter()
+            .map(|n| (n.state.as_str(), n.parent, n.status, n.expanded_at))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("'S'", None, NodeStatus::Expanded, Some(1)),
+                ("'A'", Some(0), NodeStatus::Expanded, Some(4)),
+                ("'B'", Some(0), NodeStatus::Expanded, Some(2)),
+                ("'C'", Some(2), NodeStatus::Expanded, Some(3)),
+                ("'G'", Some(3), NodeStatus::Dead, None),
+                ("'C'", Some(1), NodeStatus::Expanded, Some(5)),
+                ("'G'", Some(5), NodeStatus::Goal, Some(6)),
+            ]
+        );
+        assert_eq!(recorder.nodes()[6].g, "5");
+        assert_eq!(recorder.nodes()[1].f.as_deref(), Some("5"));
+        assert_eq!(
+            recorder.snapshots()[4],
+            Snapshot {
+                iteration: 5,
+                frontier: vec![4, 5],
+                explored: vec![0, 1, 2],
+            }
+        );
+        assert_eq!(recorder.snapshots().len(), 6);
+    }
+
+    #[test]
+    fn test_record_duplicates() {
+        let recorder = record(ReopenPolicy::Never);
+        let duplicate = &recorder.nodes()[5];
+        assert_eq!(duplicate.status, NodeStatus::Duplicate);
+        assert_eq!(duplicate.parent, Some(1));
+        assert_eq!(duplicate.g, "2");
+        assert_eq!(duplicate.h, None);
+        assert_eq!(recorder.nodes()[4].status, NodeStatus::Goal);
+    }
+
+    #[test]
+    fn test_export_dot_and_json() {
+        let recorder = record(ReopenPolicy::Always);
+
+        let mut dot = Vec::new();
+        recorder.write_dot(&mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("digraph search {"));
+        assert!(dot.contains("n0 -> n1 [label=\"'A'\"];"));
+        assert!(dot.contains("n6 [label=\"'G'\\ng=5 h=0 f=5\\n#6\""));
+
+        let mut json = Vec::new();
+        recorder.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains(
+            "{\"id\": 4, \"parent\": 3, \"state\": \"'G'\", \"action\": \"'G'\", \"g\": 7, \"h\": 0, \"f\": 7, \"depth\": 3, \"expanded_at\": null, \"status\": \"dead\"}"
+        ));
+        assert!(json.contains("{\"iteration\": 6, \"frontier\": [6], \"explored\": [0, 1, 2, 5]}"));
+    }
+
+    #[test]
+    fn test_states_with_equal_debug() {
+        let problem = Counter {};
+        let arena = Bump::new();
+        let mut recorder = TreeRecorder::with_snapshots();
+        let mut explorer = AStarExplorer::new(&problem, &arena);
+        explorer.set_observer(&mut recorder);
+        assert!(explorer.search(Count(0)).actions.is_some());
+        drop(explorer);
+
+        let statuses: Vec<NodeStatus> = recorder.nodes().iter().map(|n| n.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                NodeStatus::Expanded,
+                NodeStatus::Expanded,
+                NodeStatus::Expanded,
+                NodeStatus::Goal
+            ]
+        );
+        assert_eq!(recorder.snapshots()[3].explored, vec![0, 1, 2]);
+
+        // "+1" is not a JSON number
+        let mut json = Vec::new();
+        recorder.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains("\"g\": \"+1\", \"h\": \"+2\", \"f\": \"+3\""));
+    }
+}
@@ -0,0 +1,148 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        error::Error,
+        improve::{
+            algorithms::{
+                GeneticAlgorithm, GeneticConfig, ImprovingAlgorithm, RankFitness, Replacement,
+                Selection,
+            },
+            resolver::{Progress, Resolver},
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        observer::{NoObserver, SearchEvent, TraceObserver},
+        problem::Utility,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // The cost is an `usize`, not convertible to `f64`.
+    type Bits = crate::common::Bits<usize>;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(5)
+    }
+
+    fn config(selection: Selection) -> GeneticConfig {
+        GeneticConfig {
+            selection,
+            elitism: 2,
+            ..GeneticConfig::new(30, Some(300), 0.3)
+        }
+    }
+
+    #[test]
+    fn test_selections() {
+        let problem = Bits::new(24);
+        for selection in [
+            Selection::Roulette,
+            Selection::StochasticUniversal,
+            Selection::Tournament(3),
+            Selection::Rank,
+            Selection::Truncation(10),
+        ] {
+            let algo =
+                GeneticAlgorithm::from_config(rng(), config(selection)).with_fitness(RankFitness);
+            let result = Resolver::new(algo).resolve_restart(&problem, 5);
+            assert!(result.is_goal(), "{:?}", selection);
+            assert_eq!(result.state, vec![true; 24]);
+        }
+    }
+
+    #[test]
+    fn test_fitness_closure() {
+        let problem = Bits::new(24);
+        let fitness = |h: &usize| 1.0 / (1.0 + *h as f64);
+        let mut algo =
+            GeneticAlgorithm::from_config(rng(), config(Selection::Roulette)).with_fitness(fitness);
+        let result = algo.attempt(&problem);
+        assert!(result.h < 5);
+        assert_eq!(algo.config().elitism, 2);
+    }
+
+    #[test]
+    fn test_elitism() {
+        // without mutations, the elite keeps the best individual in every generation
+        let problem = Bits::new(40);
+        let config = GeneticConfig {
+            selection: Selection::Tournament(2),
+            elitism: 1,
+            ..GeneticConfig::new(10, Some(30), 0.0)
+        };
+        let mut algo = GeneticAlgorithm::from_config(rng(), config).with_fitness(RankFitness);
+        let mut trace = TraceObserver::new();
+        algo.attempt_observed(&problem, &mut trace);
+        let costs: Vec<usize> = trace
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                SearchEvent::Iteration { h, .. } => Some(*h),
+                _ => None,
+            })
+            .collect();
+        assert!(!costs.is_empty());
+        assert!(costs.windows(2).all(|w| w[1] <= w[0]));
+    }
+
+    #[test]
+    fn test_best_of_population() {
+        let problem = Bits::new(8);
+        let mut population = vec![vec![false; 8]; 9];
+        population.push(vec![true, true, true, true, true, true, true, false]);
+        let config = GeneticConfig {
+            elitism: 1,
+            ..GeneticConfig::new(10, Some(1), 0.0)
+        };
+        let mut algo = GeneticAlgorithm::from_config(rng(), config).with_fitness(RankFitness);
+        let result = algo.resume(&problem, Progress::new(population));
+        assert_eq!(result.h, 1);
+        assert_eq!(problem.heuristic(&result.state), 1);
+    }
+
+    #[test]
+    fn test_steady_state() {
+        let problem = Bits::new(64);
+        let config = GeneticConfig {
+            selection: Selection::Tournament(2),
+            replacement: Replacement::SteadyState(4),
+            // the elite applies only to the generational replacement
+            elitism: 20,
+            ..GeneticConfig::new(20, None, 0.5)
+        };
+        let mut algo = GeneticAlgorithm::from_config(rng(), config).with_fitness(RankFitness);
+        algo.set_limits(SearchLimits::new().with_max_expansions(10));
+        let result = algo.attempt(&problem);
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        let progress = result.progress.unwrap();
+        assert_eq!(progress.population.len(), 20);
+        assert_eq!(progress.evaluated, 20 + 10 * 4);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let problem = Bits::new(8);
+        for config in [
+            GeneticConfig {
+                replacement: Replacement::SteadyState(0),
+                ..GeneticConfig::new(10, None, 0.5)
+            },
+            GeneticConfig {
+                elitism: 10,
+                ..GeneticConfig::new(10, None, 0.5)
+            },
+        ] {
+            let mut algo = GeneticAlgorithm::from_config(rng(), config).with_fitness(RankFitness);
+            let result = algo.try_resume_observed(
+                &problem,
+                Progress::new(vec![vec![false; 8]; 10]),
+                &mut NoObserver,
+            );
+            assert!(
+                matches!(result, Err(Error::InvalidState(_))),
+                "{:?}",
+                config
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        error::Error,
+        improve::{
+            algorithms::{Grasp, HillClimbing, ImprovingAlgorithm, SteepestDescend},
+            resolver::Resolver,
+        },
+        limits::{LimitSearch, SearchLimits},
+        problem::{CostructSolution, Problem, StatePerturbation, SuitableState, Utility},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::Queens;
+
+    // every state is a dead end
+    struct Stuck;
+
+    impl Problem for Stuck {
+        type State = Vec<u8>;
+    }
+
+    impl CostructSolution for Stuck {
+        type Action = ();
+        type Cost = i32;
+
+        fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+            std::iter::empty()
+        }
+
+        fn result(&self, state: &Self::State, _: &Self::Action) -> (Self::State, Self::Cost) {
+            (state.clone(), 0)
+        }
+    }
+
+    impl Utility for Stuck {
+        fn heuristic(&self, _: &Self::State) -> Self::Cost {
+            1
+        }
+    }
+
+    impl SuitableState for Stuck {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            !state.is_empty()
+        }
+    }
+
+    impl StatePerturbation for Stuck {
+        type Perturbation = ();
+
+        fn perturbations(&self, _: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+            std::iter::empty()
+        }
+
+        fn perturb(&self, state: &Self::State, _: &Self::Perturbation) -> Self::State {
+            state.clone()
+        }
+    }
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(4)
+    }
+
+    #[test]
+    fn test_construction() {
+        let problem = Queens::new(16);
+        let mut conflicts = Vec::new();
+        for alpha in [0.0, 1.0] {
+            let mut grasp = Grasp::new(rng(), SteepestDescend::new(rng()), alpha);
+            let total: i32 = (0..20)
+                .map(|_| {
+                    let state = grasp.construct(&problem);
+                    assert_eq!(state.len(), 16);
+                    problem.heuristic(&state)
+                })
+                .sum();
+            conflicts.push(total);
+        }
+        // the greedy construction is far better than a random one
+        assert!(conflicts[0] * 3 < conflicts[1], "{:?}", conflicts);
+    }
+
+    #[test]
+    fn test_grasp() {
+        let problem = Queens::new(16);
+        let grasp = Grasp::new(rng(), HillClimbing::with_max_lateral(rng(), 10), 0.3);
+        assert_eq!(grasp.alpha(), 0.3);
+        let result = Resolver::new(grasp).resolve_restart(&problem, 20);
+        assert!(result.is_goal());
+        assert_eq!(problem.heuristic(&result.state), 0);
+    }
+
+    #[test]
+    fn test_limits_and_errors() {
+        let mut grasp = Grasp::new(rng(), SteepestDescend::new(rng()), 0.5);
+        grasp.set_limits(SearchLimits::new().with_max_expansions(3));
+        assert_eq!(grasp.local().limits().max_expansions, Some(3));
+        let result = grasp.attempt(&Queens::new(30));
+        assert!(result.iterations <= 3);
+
+        let mut grasp = Grasp::new(rng(), SteepestDescend::new(rng()), 0.5);
+        assert!(matches!(
+            grasp.try_attempt(&Stuck),
+            Err(Error::NoInitialState(_))
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        error::Error,
+        improve::{
+            algorithms::{
+                Acceptance, Descent, IlsConfig, ImprovingAlgorithm, IteratedLocalSearch,
+                SteepestDescend, VariableNeighborhoodSearch, VnsConfig,
+            },
+            resolver::{AttemptOutcome, Progress, Resolver},
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        problem::Utility,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::Queens;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(7)
+    }
+
+    #[test]
+    fn test_ils_queens() {
+        let problem = Queens::new(10);
+        let ils = IteratedLocalSearch::from_parts(rng(), SteepestDescend::new(rng()), 2, 200);
+        let mut resolver = Resolver::new(ils);
+        let result = resolver.resolve(&problem);
+        assert!(result.is_goal());
+        assert_eq!(problem.heuristic(&result.state), 0);
+    }
+
+    #[test]
+    fn test_ils_acceptance() {
+        // without kicks the local search always ends in the same local optimum
+        let problem = Queens::new(8);
+        let local = SteepestDescend::new(rng());
+        let stuck = SteepestDescend::new(rng()).resume(&problem, Progress::new(vec![vec![0; 8]]));
+        assert_ne!(stuck.h, 0);
+
+        let config = IlsConfig {
+            kick: 0,
+            max_iter: 5,
+            acceptance: Acceptance::Always,
+        };
+        let mut ils = IteratedLocalSearch::from_config(rng(), local, config);
+        let result = ils.resume(&problem, Progress::new(vec![vec![0; 8]]));
+        assert_eq!(
+            result.outcome,
+            AttemptOutcome::BudgetExhausted(Limit::Expansions)
+        );
+        assert_eq!(result.h, stuck.h);
+        assert_eq!(result.iterations, 5);
+        // the states evaluated by the local searches, not their iterations
+        let again = SteepestDescend::new(rng()).resume(&problem, Progress::new(vec![stuck.state]));
+        assert!(again.evaluated > again.iterations);
+        assert_eq!(result.evaluated, stuck.evaluated + 4 * again.evaluated);
+        assert_eq!(ils.config().acceptance, Acceptance::Always);
+    }
+
+    #[test]
+    fn test_ils_resume() {
+        let problem = Queens::new(8);
+        let mut ils = IteratedLocalSearch::from_parts(rng(), SteepestDescend::new(rng()), 2, 500);
+        ils.set_limits(SearchLimits::new().with_max_expansions(1));
+        let stopped = ils.resume(&problem, Progress::new(vec![vec![0; 8]]));
+        assert_eq!(stopped.limit, Some(Limit::Expansions));
+        let progress = stopped.progress.unwrap();
+        assert_eq!(progress.population.len(), 2);
+        assert_eq!(problem.heuristic(&progress.population[1]), stopped.h);
+
+        ils.set_limits(SearchLimits::new());
+        let result = ils.resume(&problem, progress);
+        assert!(result.h <= stopped.h);
+        assert!(result.iterations > 1);
+    }
+
+    #[test]
+    fn test_vns_queens() {
+        let problem = Queens::new(10);
+        let mut resolver = Resolver::new(VariableNeighborhoodSearch::new(rng(), 200));
+        let result = resolver.resolve_restart(&problem, 5);
+        assert!(result.is_goal());
+        assert_eq!(problem.heuristic(&result.state), 0);
+    }
+
+    #[test]
+    fn test_vns_descent() {
+        // swapping columns keeps the rows of the start state, all equal
+        let problem = Queens::new(6);
+        let mut config = VnsConfig {
+            max_iter: 0,
+            descent: Descent::Neighborhood(1),
+        };
+        let mut vns = VariableNeighborhoodSearch::from_config(rng(), config);
+        let result = vns.resume(&problem, Progress::new(vec![vec![2; 6]]));
+        assert_eq!(result.state, vec![2; 6]);
+        assert_eq!(
+            result.outcome,
+            AttemptOutcome::BudgetExhausted(Limit::Expansions)
+        );
+
+        config.descent = Descent::Variable;
+        let mut vns = VariableNeighborhoodSearch::from_config(rng(), config);
+        let result = vns.resume(&problem, Progress::new(vec![vec![2; 6]]));
+        assert!(result.h < 15);
+        assert_eq!(result.iterations, 0);
+
+        config.descent = Descent::Neighborhood(2);
+        let mut vns = VariableNeighborhoodSearch::from_config(rng(), config);
+        assert!(matches!(
+            vns.try_attempt(&problem),
+            Err(Error::InvalidState(_))
+        ));
+    }
+
+    #[test]
+    fn test_vns_limits() {
+        let problem = Queens::new(20);
+        let mut vns = VariableNeighborhoodSearch::new(rng(), 1000);
+        vns.set_limits(SearchLimits::new().with_max_expansions(3));
+        let result = vns.attempt(&problem);
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        assert_eq!(result.iterations, 3);
+        assert_eq!(result.progress.unwrap().iteration, 3);
+    }
+}
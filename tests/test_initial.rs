@@ -0,0 +1,236 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use agent::{
+        error::{Error, Result},
+        improve::{
+            algorithms::{
+                GeneticAlgorithm, GeneticConfig, ImprovingAlgorithm, IteratedLocalSearch,
+                SteepestDescend,
+            },
+            initial::{
+                FromStates, GreedyConstruction, InitialStateGenerator, RandomWalk, WithGenerator,
+            },
+            resolver::Resolver,
+        },
+        observer::NoObserver,
+        problem::{CostructSolution, Problem, RandomState, SuitableState, Utility},
+    };
+    use ordered_float::OrderedFloat;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // The greedy construction ranks the actions by their cost as an `f64`.
+    type Bits = crate::common::Bits<i32>;
+
+    // A walk that never reaches a suitable state.
+    struct Endless;
+
+    impl Problem for Endless {
+        type State = usize;
+    }
+
+    impl CostructSolution for Endless {
+        type Action = ();
+        type Cost = i32;
+
+        fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+            std::iter::once(())
+        }
+
+        fn result(&self, state: &Self::State, _: &Self::Action) -> (Self::State, Self::Cost) {
+            (state + 1, 0)
+        }
+    }
+
+    impl Utility for Endless {
+        fn heuristic(&self, _: &Self::State) -> Self::Cost {
+            1
+        }
+    }
+
+    impl SuitableState for Endless {
+        fn is_suitable(&self, _: &Self::State) -> bool {
+            false
+        }
+    }
+
+    // Four choices among actions of the given costs, NaN or infinite ones included.
+    struct Unranked {
+        costs: Vec<f64>,
+    }
+
+    impl Problem for Unranked {
+        type State = Vec<usize>;
+    }
+
+    impl CostructSolution for Unranked {
+        type Action = usize;
+        type Cost = OrderedFloat<f64>;
+
+        fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+            let actions = if state.len() < 4 { self.costs.len() } else { 0 };
+            0..actions
+        }
+
+        fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+            let mut state = state.clone();
+            state.push(*action);
+            (state, OrderedFloat(self.costs[*action]))
+        }
+    }
+
+    impl Utility for Unranked {
+        fn heuristic(&self, _: &Self::State) -> Self::Cost {
+            OrderedFloat(0.0)
+        }
+    }
+
+    impl SuitableState for Unranked {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            state.len() == 4
+        }
+    }
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(6)
+    }
+
+    fn parse(line: &str) -> Result<Vec<bool>> {
+        line.trim()
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(Error::InvalidState(format!("not a bit: {}", c))),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_random_walk() {
+        let problem = Bits::new(8);
+        let state = RandomWalk::new(rng()).generate(&problem).unwrap();
+        assert_eq!(state.len(), 8);
+
+        // every walk is cut before a suitable state
+        let mut walk = RandomWalk::new(rng())
+            .with_max_restarts(5)
+            .with_max_steps(3);
+        assert!(matches!(
+            walk.generate(&problem),
+            Err(Error::NoInitialState(_))
+        ));
+
+        // the walks of `RandomState` are cut too
+        assert!(matches!(
+            Endless.try_random_state(&mut rng()),
+            Err(Error::NoInitialState(_))
+        ));
+    }
+
+    #[test]
+    fn test_sampler_and_greedy() {
+        let problem = Bits::new(12);
+        let mut sampled = 0;
+        let mut sampler = |problem: &Bits| {
+            sampled += 1;
+            Ok(vec![true; problem.n])
+        };
+        let result = SteepestDescend::new(rng()).attempt_from(&problem, &mut sampler);
+        assert!(result.is_goal());
+        assert_eq!(result.state, vec![true; 12]);
+        assert_eq!(sampled, 1);
+
+        let mut greedy = GreedyConstruction::new(rng(), 0.0);
+        assert_eq!(greedy.generate(&problem).unwrap(), vec![true; 12]);
+
+        // every construction is cut before a suitable state
+        let mut greedy = GreedyConstruction::new(rng(), 0.5)
+            .with_max_restarts(3)
+            .with_max_steps(50);
+        assert!(matches!(
+            greedy.generate(&Endless),
+            Err(Error::NoInitialState(_))
+        ));
+
+        let mut failing = |_: &Bits| Err(Error::NoInitialState("no sample".into()));
+        assert!(matches!(
+            SteepestDescend::new(rng()).try_attempt_from(&problem, &mut failing, &mut NoObserver),
+            Err(Error::NoInitialState(_))
+        ));
+    }
+
+    #[test]
+    fn test_greedy_unranked_scores() {
+        // NaN and infinite scores never enter the restricted candidates
+        let problem = Unranked {
+            costs: vec![f64::NAN, f64::INFINITY, 1.0, 2.0],
+        };
+        let mut greedy = GreedyConstruction::new(rng(), 1.0);
+        for _ in 0..10 {
+            let state = greedy.generate(&problem).unwrap();
+            assert!(state.iter().all(|action| *action >= 2), "{:?}", state);
+        }
+
+        // unless no score is finite
+        let problem = Unranked {
+            costs: vec![f64::NAN, f64::NEG_INFINITY],
+        };
+        for alpha in [0.0, 0.5, f64::NAN] {
+            let state = GreedyConstruction::new(rng(), alpha)
+                .generate(&problem)
+                .unwrap();
+            assert_eq!(state.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_from_states() {
+        let problem = Bits::new(4);
+        let path = std::env::temp_dir().join(format!("agent_initial_{}.txt", std::process::id()));
+        fs::write(&path, "1011\n\n0000\n").unwrap();
+        let mut states = FromStates::from_file(&path, parse).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(states.states().len(), 2);
+        let first = vec![true, false, true, true];
+        assert_eq!(states.generate(&problem).unwrap(), first);
+        assert_eq!(states.generate(&problem).unwrap(), vec![false; 4]);
+        assert_eq!(states.generate(&problem).unwrap(), first);
+
+        assert!(matches!(
+            FromStates::from_file(&path, parse),
+            Err(Error::NoInitialState(_))
+        ));
+        let mut empty = FromStates::new(Vec::new());
+        assert!(matches!(
+            empty.generate(&problem),
+            Err(Error::NoInitialState(_))
+        ));
+
+        // without iterations only the local search of ILS reaches the goal
+        let mut ils = IteratedLocalSearch::from_parts(rng(), SteepestDescend::new(rng()), 1, 0);
+        let result = ils.attempt_from(&problem, &mut FromStates::new(vec![first]));
+        assert!(result.is_goal());
+    }
+
+    #[test]
+    fn test_population() {
+        let problem = Bits::new(10);
+        let mut genetic =
+            GeneticAlgorithm::from_config(rng(), GeneticConfig::new(16, Some(200), 0.3));
+        let mut sampled = 0;
+        let mut sampler = |problem: &Bits| {
+            sampled += 1;
+            Ok(vec![false; problem.n])
+        };
+        genetic.attempt_from(&problem, &mut sampler);
+        assert_eq!(sampled, 16);
+
+        let algo = WithGenerator::new(SteepestDescend::new(rng()), RandomWalk::new(rng()));
+        let result = Resolver::new(algo).resolve_restart(&problem, 3);
+        assert!(result.is_goal());
+    }
+}
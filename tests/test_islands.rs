@@ -0,0 +1,149 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use agent::{
+        improve::{
+            algorithms::{GeneticConfig, ImprovingAlgorithm, RankFitness, Selection},
+            parallel::{IslandConfig, IslandModel, MigrationPolicy, Topology},
+            resolver::{AttemptOutcome, Progress},
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+    };
+
+    type Bits = crate::common::Bits<usize>;
+
+    fn genetic(max_iter: Option<usize>, pmut: f64) -> GeneticConfig {
+        GeneticConfig {
+            selection: Selection::Tournament(2),
+            elitism: 1,
+            ..GeneticConfig::new(20, max_iter, pmut)
+        }
+    }
+
+    fn islands(topology: Topology, policy: MigrationPolicy) -> IslandConfig {
+        IslandConfig {
+            islands: 4,
+            interval: 5,
+            migrants: 2,
+            topology,
+            policy,
+        }
+    }
+
+    #[test]
+    fn test_topologies() {
+        let problem = Bits::new(32);
+        for (topology, policy) in [
+            (Topology::Ring, MigrationPolicy::BestReplaceWorst),
+            (Topology::FullyConnected, MigrationPolicy::BestReplaceRandom),
+            (Topology::Ring, MigrationPolicy::RandomReplaceWorst),
+        ] {
+            let mut algo = IslandModel::new(1, genetic(Some(500), 0.5), islands(topology, policy))
+                .with_fitness(RankFitness);
+            let result = algo.attempt(&problem);
+            assert!(result.is_goal(), "{:?} {:?}", topology, policy);
+            assert_eq!(result.state, vec![true; 32]);
+        }
+    }
+
+    #[test]
+    fn test_history() {
+        let problem = Bits::new(200);
+        let mut algo = IslandModel::new(
+            2,
+            genetic(Some(20), 0.5),
+            islands(Topology::FullyConnected, MigrationPolicy::BestReplaceWorst),
+        )
+        .with_fitness(RankFitness);
+        let result = algo.attempt(&problem);
+        assert_eq!(
+            result.outcome,
+            AttemptOutcome::BudgetExhausted(Limit::Expansions)
+        );
+        let history = algo.history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[3].generations, 20);
+        for epoch in history {
+            assert_eq!(epoch.islands.len(), 4);
+            for island in &epoch.islands {
+                assert!(island.diversity > 0.0 && island.diversity <= 1.0);
+                assert!(island.best_h >= result.h);
+            }
+        }
+    }
+
+    #[test]
+    fn test_migration() {
+        // without mutations the second island never gets better than all zeros, unless the
+        // best individual of the first one migrates and is kept by the elite
+        let n = 16;
+        let mut best = vec![true; n];
+        best[0] = false;
+        let mut population = vec![best; 20];
+        population.extend(vec![vec![false; n]; 20]);
+        let problem = Bits::new(n);
+        for (migrants, expected) in [(1, 1), (0, n)] {
+            let config = IslandConfig {
+                islands: 2,
+                interval: 1,
+                migrants,
+                topology: Topology::Ring,
+                policy: MigrationPolicy::BestReplaceWorst,
+            };
+            let mut algo =
+                IslandModel::new(3, genetic(Some(2), 0.0), config).with_fitness(RankFitness);
+            algo.resume(&problem, Progress::new(population.clone()));
+            let history = algo.history();
+            assert_eq!(history[0].islands[1].best_h, n);
+            assert_eq!(history[1].islands[1].best_h, expected);
+            assert_eq!(history[1].islands[0].best_h, 1);
+            assert_eq!(history[1].islands[0].diversity, 1.0 / 20.0);
+        }
+    }
+
+    #[test]
+    fn test_evaluations() {
+        // every epoch evaluates again the populations of the islands, migrants included
+        let config = islands(Topology::Ring, MigrationPolicy::BestReplaceWorst);
+        for (n, max_iter) in [(200, Some(20)), (200, None), (12, Some(500))] {
+            let problem = Bits::new(n);
+            let mut algo =
+                IslandModel::new(6, genetic(max_iter, 0.5), config).with_fitness(RankFitness);
+            algo.set_limits(SearchLimits::new().with_max_expansions(15));
+            let result = algo.attempt(&problem);
+            assert_eq!(
+                result.evaluated,
+                problem.evaluations.load(Ordering::Relaxed)
+            );
+            if let Some(progress) = result.progress {
+                let result = algo.resume(&problem, progress);
+                assert_eq!(
+                    result.evaluated,
+                    problem.evaluations.load(Ordering::Relaxed)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_limits_and_resume() {
+        let problem = Bits::new(200);
+        let config = islands(Topology::Ring, MigrationPolicy::BestReplaceWorst);
+        let mut algo = IslandModel::new(4, genetic(None, 0.5), config).with_fitness(RankFitness);
+        algo.set_limits(SearchLimits::new().with_max_expansions(10));
+        let result = algo.attempt(&problem);
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        let progress = result.progress.unwrap();
+        assert_eq!(progress.iteration, 10);
+        assert_eq!(progress.population.len(), 80);
+        assert_eq!(algo.history().len(), 2);
+
+        let result = algo.resume(&problem, progress);
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        assert_eq!(result.progress.unwrap().iteration, 20);
+        assert_eq!(algo.history()[1].generations, 20);
+    }
+}
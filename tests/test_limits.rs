@@ -0,0 +1,154 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{atomic::AtomicBool, Arc},
+        time::Duration,
+    };
+
+    use agent::{
+        improve::{
+            algorithms::{ImprovingAlgorithm, SimulatedAnnealing},
+            resolver::Resolver,
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        problem::{CostructSolution, Problem, StatePerturbation, SuitableState, Utility},
+        statexplorer::resolver::{BFSExplorer, DFSExplorer, SearchOutcome},
+    };
+    use bumpalo::Bump;
+
+    use crate::common::Walk;
+
+    // Every state has the same value, so an annealing that never cools never stops.
+    struct Plateau {}
+
+    impl Problem for Plateau {
+        type State = i32;
+    }
+
+    impl CostructSolution for Plateau {
+        type Action = ();
+        type Cost = i32;
+
+        fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+            std::iter::empty()
+        }
+
+        fn result(&self, state: &Self::State, _: &Self::Action) -> (Self::State, Self::Cost) {
+            (*state, 0)
+        }
+    }
+
+    impl Utility for Plateau {
+        fn heuristic(&self, _: &Self::State) -> Self::Cost {
+            1
+        }
+    }
+
+    impl SuitableState for Plateau {
+        fn is_suitable(&self, _: &Self::State) -> bool {
+            true
+        }
+    }
+
+    impl StatePerturbation for Plateau {
+        type Perturbation = i32;
+
+        fn perturbations(&self, _: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+            vec![-1, 1].into_iter()
+        }
+
+        fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
+            state + action
+        }
+    }
+
+    fn hot(_: usize) -> f64 {
+        1.0
+    }
+
+    #[test]
+    fn test_outcomes() {
+        let arena = Bump::new();
+        let solvable = Walk::new(4).bounded(10);
+        let result = BFSExplorer::new(&solvable, &arena).search(0);
+        assert_eq!(result.outcome, SearchOutcome::Solved);
+
+        let unsolvable = Walk::new(-1).bounded(10);
+        let result = BFSExplorer::new(&unsolvable, &arena).search(0);
+        assert_eq!(result.outcome, SearchOutcome::Exhausted);
+    }
+
+    #[test]
+    fn test_dfs_expansion_limit() {
+        let problem = Walk::new(-1);
+        let arena = Bump::new();
+        let mut explorer = DFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_expansions(100));
+        let result = explorer.search(0);
+
+        assert_eq!(
+            result.outcome,
+            SearchOutcome::LimitReached(Limit::Expansions)
+        );
+        assert!(result.actions.is_none());
+        assert_eq!(result.stats.expanded, 100);
+    }
+
+    #[test]
+    fn test_generated_and_memory_limits() {
+        let problem = Walk::new(-1);
+        let arena = Bump::new();
+        let mut explorer = DFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_generated(1000));
+        let result = explorer.search(0);
+        assert_eq!(
+            result.outcome,
+            SearchOutcome::LimitReached(Limit::Generated)
+        );
+        assert!(result.stats.generated >= 1000);
+
+        let arena = Bump::new();
+        let mut explorer = DFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_max_memory(1 << 16));
+        let result = explorer.search(0);
+        assert_eq!(result.outcome, SearchOutcome::LimitReached(Limit::Memory));
+    }
+
+    #[test]
+    fn test_time_budget_and_cancellation() {
+        let problem = Walk::new(-1);
+        let arena = Bump::new();
+        let mut explorer = DFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_time_budget(Duration::from_millis(20)));
+        let result = explorer.search(0);
+        assert_eq!(result.outcome, SearchOutcome::LimitReached(Limit::Time));
+
+        let token = Arc::new(AtomicBool::new(true));
+        let mut explorer = DFSExplorer::new(&problem, &arena);
+        explorer.set_limits(SearchLimits::new().with_cancel_token(token));
+        let result = explorer.search(0);
+        assert_eq!(
+            result.outcome,
+            SearchOutcome::LimitReached(Limit::Cancelled)
+        );
+        assert_eq!(result.n_iter, 0);
+    }
+
+    #[test]
+    fn test_annealing_limits() {
+        let problem = Plateau {};
+        let mut algo = SimulatedAnnealing::with_cooling(rand::rng(), hot);
+        algo.set_limits(SearchLimits::new().with_max_expansions(50));
+        let result = algo.attempt(&problem);
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        assert_eq!(result.iterations, 50);
+
+        let mut resolver = Resolver::new(SimulatedAnnealing::with_cooling(rand::rng(), hot));
+        resolver.set_limits(SearchLimits::new().with_time_budget(Duration::from_millis(20)));
+        let result = resolver.resolve_restart(&problem, usize::MAX);
+        assert_eq!(result.limit, Some(Limit::Time));
+        assert_eq!(result.h, 1);
+    }
+}
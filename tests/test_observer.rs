@@ -0,0 +1,161 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        improve::{
+            algorithms::{
+                GeneticAlgorithm, GeneticConfig, ImprovingAlgorithm, SimulatedAnnealing,
+                SteepestDescend,
+            },
+            resolver::{Progress, Resolver},
+        },
+        observer::{
+            diversity, CountingObserver, HistoryObserver, IterationStats, SearchEvent,
+            TraceObserver,
+        },
+        statexplorer::resolver::BFSExplorer,
+    };
+    use bumpalo::Bump;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::{Line, Walk};
+
+    #[test]
+    fn test_counting_observer_bfs() {
+        let problem = Walk::new(4);
+        let arena = Bump::new();
+        let mut counter = CountingObserver::default();
+        let mut explorer = BFSExplorer::new(&problem, &arena);
+        explorer.set_observer(&mut counter);
+        let result = explorer.search(0);
+        drop(explorer);
+
+        assert_eq!(result.actions.unwrap(), vec![2, 2]);
+        assert_eq!(counter.expanded, result.n_iter);
+        assert_eq!(counter.expanded, 5);
+        assert_eq!(counter.generated, 6);
+        assert_eq!(counter.duplicates, 3);
+        assert_eq!(counter.goals, 1);
+    }
+
+    #[test]
+    fn test_trace_observer_steepest_descend() {
+        let problem = Line::new(3);
+        let mut trace = TraceObserver::new();
+        let mut resolver = Resolver::new(SteepestDescend::new(rand::rng()));
+        let result = resolver.resolve_observed(&problem, &mut trace);
+
+        assert_eq!(result.state, 3);
+        assert_eq!(
+            trace.events,
+            vec![
+                SearchEvent::Iteration {
+                    iteration: 1,
+                    state: 0,
+                    h: 3
+                },
+                SearchEvent::Improvement {
+                    iteration: 1,
+                    state: 1,
+                    h: 2
+                },
+                SearchEvent::Iteration {
+                    iteration: 2,
+                    state: 1,
+                    h: 2
+                },
+                SearchEvent::Improvement {
+                    iteration: 2,
+                    state: 2,
+                    h: 1
+                },
+                SearchEvent::Iteration {
+                    iteration: 3,
+                    state: 2,
+                    h: 1
+                },
+                SearchEvent::Improvement {
+                    iteration: 3,
+                    state: 3,
+                    h: 0
+                },
+                SearchEvent::Iteration {
+                    iteration: 4,
+                    state: 3,
+                    h: 0
+                },
+                SearchEvent::Goal {
+                    iteration: 4,
+                    state: 3,
+                    cost: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_history_steepest_descend() {
+        let problem = Line::new(3);
+        let mut history = HistoryObserver::new();
+        let mut resolver = Resolver::new(SteepestDescend::new(rand::rng()));
+        resolver.resolve_observed(&problem, &mut history);
+
+        let best: Vec<i32> = history.records.iter().map(|r| r.stats.best_h).collect();
+        assert_eq!(best, vec![3, 2, 1, 0]);
+        let first = history.records[0].stats;
+        assert_eq!(first, IterationStats::new(1, 1, 3, 3));
+        assert!(history
+            .records
+            .windows(2)
+            .all(|w| w[0].elapsed <= w[1].elapsed && w[0].stats.evaluated < w[1].stats.evaluated));
+
+        let csv = history.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], HistoryObserver::<i32>::CSV_HEADER);
+        let fields: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(fields.len(), 8);
+        assert_eq!(fields[0], "1");
+        assert_eq!(&fields[2..], &["1", "3", "3", "", "", ""]);
+    }
+
+    #[test]
+    fn test_history_annealing_and_genetic() {
+        let problem = Line::new(3);
+        let mut history = HistoryObserver::new();
+        let mut annealing = SimulatedAnnealing::new(StdRng::seed_from_u64(2));
+        annealing.attempt_observed(&problem, &mut history);
+        assert!(!history.records.is_empty());
+        for record in &history.records {
+            let stats = record.stats;
+            assert!(stats.temperature.is_some_and(|t| t > 0.0));
+            assert!(stats.acceptance.is_none_or(|a| (0.0..=1.0).contains(&a)));
+            assert!(stats.best_h <= stats.current_h);
+            assert_eq!(stats.diversity, None);
+        }
+
+        let mut history = HistoryObserver::new();
+        let mut genetic = GeneticAlgorithm::from_config(
+            StdRng::seed_from_u64(2),
+            GeneticConfig::new(8, Some(5), 0.5),
+        );
+        let population = vec![-40, -20, 20, 40, 45, 30, -30, 50];
+        genetic
+            .try_resume_observed(&problem, Progress::new(population), &mut history)
+            .unwrap();
+        assert!(!history.records.is_empty());
+        for record in &history.records {
+            let diversity = record.stats.diversity.unwrap();
+            assert!(diversity > 0.0 && diversity <= 1.0);
+            assert_eq!(record.stats.temperature, None);
+        }
+    }
+
+    #[test]
+    fn test_diversity() {
+        assert_eq!(diversity(&[1, 1, 1, 1]), 0.25);
+        assert_eq!(diversity(&[3, 1, 2, 1]), 0.75);
+        assert_eq!(diversity::<i32>(&[]), 0.0);
+    }
+}
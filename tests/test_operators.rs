@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod tests {
+    use agent::{
+        improve::{
+            algorithms::{GeneticAlgorithm, GeneticConfig, RankFitness, Selection},
+            operators::{
+                BlendCrossover, CrossoverOperator, CycleCrossover, GaussianMutation,
+                InsertMutation, InversionMutation, MutationOperator, OnePoint, OrderCrossover, Pmx,
+                Sbx, SwapMutation, TwoPoint, Uniform, WithOperators,
+            },
+            resolver::Resolver,
+        },
+        problem::{CostructSolution, Problem, SuitableState, Utility},
+    };
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    // Permutations of 0..n, the goal is the identity.
+    struct Sorting {
+        n: usize,
+    }
+
+    impl Problem for Sorting {
+        type State = Vec<usize>;
+    }
+
+    impl CostructSolution for Sorting {
+        type Action = usize;
+        type Cost = usize;
+
+        fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+            let state = state.clone();
+            (0..self.n).filter(move |i| !state.contains(i))
+        }
+
+        fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+            let mut state = state.clone();
+            state.push(*action);
+            (state, 0)
+        }
+    }
+
+    impl Utility for Sorting {
+        fn heuristic(&self, state: &Self::State) -> Self::Cost {
+            state.iter().enumerate().filter(|(i, x)| *i != **x).count()
+        }
+    }
+
+    impl SuitableState for Sorting {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            state.len() == self.n
+        }
+    }
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(9)
+    }
+
+    fn is_permutation(state: &[usize]) -> bool {
+        let mut sorted = state.to_vec();
+        sorted.sort();
+        sorted == (0..state.len()).collect::<Vec<_>>()
+    }
+
+    fn parents(rng: &mut StdRng) -> (Vec<usize>, Vec<usize>) {
+        let mut a: Vec<usize> = (0..10).collect();
+        let mut b = a.clone();
+        a.shuffle(rng);
+        b.shuffle(rng);
+        (a, b)
+    }
+
+    #[test]
+    fn test_permutation_crossovers() {
+        let mut rng = rng();
+        for _ in 0..200 {
+            let (a, b) = parents(&mut rng);
+            assert!(is_permutation(&Pmx.crossover(&mut rng, &a, &b)));
+            assert!(is_permutation(&OrderCrossover.crossover(&mut rng, &a, &b)));
+            let child = CycleCrossover.crossover(&mut rng, &a, &b);
+            assert!(is_permutation(&child));
+            assert!((0..10).all(|i| child[i] == a[i] || child[i] == b[i]));
+        }
+    }
+
+    #[test]
+    fn test_permutation_mutations() {
+        let mut rng = rng();
+        for _ in 0..200 {
+            let (mut state, _) = parents(&mut rng);
+            SwapMutation.mutate(&mut rng, &mut state);
+            InsertMutation.mutate(&mut rng, &mut state);
+            InversionMutation.mutate(&mut rng, &mut state);
+            assert!(is_permutation(&state));
+        }
+    }
+
+    #[test]
+    fn test_vector_crossovers() {
+        let mut rng = rng();
+        let a = vec![0; 12];
+        let b = vec![1; 12];
+        for _ in 0..100 {
+            let child = OnePoint.crossover(&mut rng, &a, &b);
+            assert_eq!(child[0], 0);
+            assert_eq!(child[11], 1);
+            assert!(child.windows(2).all(|w| w[0] <= w[1]));
+
+            let child = TwoPoint.crossover(&mut rng, &a, &b);
+            assert_eq!(child.len(), 12);
+            assert!(child.contains(&1));
+            let ones: Vec<usize> = (0..12).filter(|i| child[*i] == 1).collect();
+            assert_eq!(ones.len(), ones[ones.len() - 1] - ones[0] + 1);
+
+            let child = Uniform.crossover(&mut rng, &a, &b);
+            assert_eq!(child.len(), 12);
+        }
+    }
+
+    #[test]
+    fn test_real_operators() {
+        let mut rng = rng();
+        let a = vec![0.0, 1.0, 5.0];
+        let b = vec![1.0, 1.0, 3.0];
+        for _ in 0..100 {
+            let child = BlendCrossover { alpha: 0.5 }.crossover(&mut rng, &a, &b);
+            assert!((-0.5..=1.5).contains(&child[0]));
+            assert_eq!(child[1], 1.0);
+            assert!((2.0..=6.0).contains(&child[2]));
+
+            let child = Sbx { eta: 20.0 }.crossover(&mut rng, &a, &b);
+            assert!((child[1] - 1.0).abs() < 1e-9);
+            assert!(child.iter().all(|x| x.is_finite()));
+        }
+
+        let mut state = a.clone();
+        GaussianMutation {
+            sigma: 1.0,
+            rate: 0.0,
+        }
+        .mutate(&mut rng, &mut state);
+        assert_eq!(state, a);
+        GaussianMutation {
+            sigma: 1.0,
+            rate: 1.0,
+        }
+        .mutate(&mut rng, &mut state);
+        assert!(state.iter().zip(&a).all(|(x, y)| x != y));
+    }
+
+    #[test]
+    fn test_genetic_with_operators() {
+        let problem = Sorting { n: 8 };
+        let problem = WithOperators::new(&problem, OrderCrossover, SwapMutation);
+        let config = GeneticConfig {
+            selection: Selection::Tournament(3),
+            elitism: 2,
+            ..GeneticConfig::new(40, Some(500), 0.5)
+        };
+        let algo = GeneticAlgorithm::from_config(rng(), config).with_fitness(RankFitness);
+        let result = Resolver::new(algo).resolve_restart(&problem, 5);
+        assert!(result.is_goal());
+        assert_eq!(result.state, (0..8).collect::<Vec<_>>());
+        assert_eq!(problem.problem().n, 8);
+    }
+}
@@ -0,0 +1,234 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use agent::{
+        error::{Error, Result},
+        improve::{
+            algorithms::{ImprovingAlgorithm, LocalBeam, SteepestDescend},
+            parallel::ParallelResolver,
+            resolver::{AttemptOutcome, AttemptResult, Progress, Resolver},
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        observer::SearchObserver,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::Line;
+
+    // Returns the attempts of `script` in order, one for every restart.
+    struct Scripted {
+        script: Vec<AttemptResult<Line>>,
+        limits: SearchLimits,
+    }
+
+    impl LimitSearch for Scripted {
+        fn set_limits(&mut self, limits: SearchLimits) {
+            self.limits = limits;
+        }
+
+        fn limits(&self) -> &SearchLimits {
+            &self.limits
+        }
+    }
+
+    impl ImprovingAlgorithm<Line> for Scripted {
+        fn try_attempt_observed(
+            &mut self,
+            _: &Line,
+            _: &mut dyn SearchObserver<Line>,
+        ) -> Result<AttemptResult<Line>> {
+            Ok(self.script.remove(0))
+        }
+
+        fn try_resume_observed(
+            &mut self,
+            _: &Line,
+            _: Progress<i32, i32>,
+            _: &mut dyn SearchObserver<Line>,
+        ) -> Result<AttemptResult<Line>> {
+            unreachable!()
+        }
+    }
+
+    // Always ends with `outcome` at distance `h` from the target, after a pause so that every
+    // worker of a parallel resolver runs.
+    struct Repeated {
+        h: i32,
+        outcome: AttemptOutcome,
+        limits: SearchLimits,
+    }
+
+    impl Repeated {
+        fn new(h: i32, outcome: AttemptOutcome) -> Self {
+            Self {
+                h,
+                outcome,
+                limits: SearchLimits::new(),
+            }
+        }
+    }
+
+    impl LimitSearch for Repeated {
+        fn set_limits(&mut self, limits: SearchLimits) {
+            self.limits = limits;
+        }
+
+        fn limits(&self) -> &SearchLimits {
+            &self.limits
+        }
+    }
+
+    impl ImprovingAlgorithm<Line> for Repeated {
+        fn try_attempt_observed(
+            &mut self,
+            problem: &Line,
+            _: &mut dyn SearchObserver<Line>,
+        ) -> Result<AttemptResult<Line>> {
+            thread::sleep(Duration::from_millis(10));
+            Ok(AttemptResult::new(problem.target - self.h, self.h, 1)
+                .with_evaluated(3)
+                .with_outcome(self.outcome))
+        }
+
+        fn try_resume_observed(
+            &mut self,
+            _: &Line,
+            _: Progress<i32, i32>,
+            _: &mut dyn SearchObserver<Line>,
+        ) -> Result<AttemptResult<Line>> {
+            unreachable!()
+        }
+    }
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(1)
+    }
+
+    #[test]
+    fn test_goal_and_local_optimum() {
+        let mut algo = SteepestDescend::new(rng());
+        let result = algo.attempt(&Line::new(10).even());
+        assert_eq!(result.outcome, AttemptOutcome::GoalReached);
+        assert_eq!(result.state, 10);
+
+        let mut stuck = Line::new(10).even();
+        stuck.steps = vec![-1];
+        let result = algo.attempt(&stuck);
+        assert_eq!(result.outcome, AttemptOutcome::LocalOptimum);
+        assert_eq!(result.state, 0);
+    }
+
+    #[test]
+    fn test_budget_exhausted() {
+        let mut algo = SteepestDescend::new(rng());
+        algo.set_limits(SearchLimits::new().with_max_expansions(3));
+        let result = algo.attempt(&Line::new(10).even());
+        assert_eq!(
+            result.outcome,
+            AttemptOutcome::BudgetExhausted(Limit::Expansions)
+        );
+
+        let mut beam = LocalBeam::from_parts(rng(), 2, Some(2));
+        let result = beam.attempt(&Line::new(10).even());
+        assert_eq!(
+            result.outcome,
+            AttemptOutcome::BudgetExhausted(Limit::Expansions)
+        );
+        assert!(result.h > 0);
+    }
+
+    #[test]
+    fn test_dead_population_fails() {
+        let mut problem = Line::new(10).even();
+        problem.steps = vec![];
+        let mut beam = LocalBeam::from_parts(rng(), 2, None);
+        let result = beam.attempt(&problem);
+        assert_eq!(result.outcome, AttemptOutcome::Failed);
+        // the best member of the initial population, not a state with a made-up h
+        assert_eq!((result.state, result.h), (0, 10));
+
+        let mut empty = LocalBeam::from_parts(rng(), 0, None);
+        assert!(matches!(
+            empty.try_attempt(&problem),
+            Err(Error::EmptyPopulation)
+        ));
+    }
+
+    #[test]
+    fn test_verified_goals() {
+        // h = 0 in 11, which is not suitable
+        let problem = Line::new(11).even();
+        let mut resolver = Resolver::new(SteepestDescend::new(rng()));
+        let result = resolver.resolve(&problem);
+        assert_eq!(result.h, 0);
+        assert!(result.is_goal());
+
+        let mut resolver = Resolver::new(SteepestDescend::new(rng())).verified();
+        let result = resolver.resolve(&problem);
+        assert_eq!(result.outcome, AttemptOutcome::Failed);
+
+        let single = resolver.resolve(&problem).iterations;
+        let result = resolver.resolve_restart(&problem, 5);
+        assert!(!result.is_goal());
+        assert_eq!(result.iterations, 5 * single);
+
+        let result = Resolver::new(SteepestDescend::new(rng()))
+            .verified()
+            .resolve_restart(&Line::new(12).even(), 5);
+        assert!(result.is_goal());
+        assert_eq!(result.state, 12);
+    }
+
+    #[test]
+    fn test_parallel_verified_goals() {
+        let mut resolver = ParallelResolver::new(0, SteepestDescend::new)
+            .verified()
+            .with_threads(2);
+        let result = resolver.resolve(&Line::new(11).even(), 6);
+        assert_eq!(result.outcome, AttemptOutcome::Failed);
+
+        let result = resolver.resolve(&Line::new(12).even(), 6);
+        assert_eq!(result.outcome, AttemptOutcome::GoalReached);
+
+        let mut resolver = ParallelResolver::new(0, SteepestDescend::new).with_threads(2);
+        assert!(resolver.resolve(&Line::new(11).even(), 6).is_goal());
+    }
+
+    #[test]
+    fn test_parallel_ranking() {
+        // a failed attempt, even with h = 0, is worse than a stopped one
+        let exhausted = AttemptOutcome::BudgetExhausted(Limit::Expansions);
+        let mut resolver = ParallelResolver::portfolio(0)
+            .with_algorithm(|_| Repeated::new(0, AttemptOutcome::Failed))
+            .with_algorithm(move |_| Repeated::new(6, exhausted))
+            .with_threads(2);
+        let result = resolver.resolve(&Line::new(10), 4);
+        assert_eq!((result.outcome, result.h, result.state), (exhausted, 6, 4));
+        assert_eq!((result.iterations, result.evaluated), (4, 12));
+    }
+
+    #[test]
+    fn test_restarts_keep_the_best_attempt() {
+        let script = vec![
+            AttemptResult::stopped(4, 6, 3, Limit::Expansions)
+                .with_progress(Progress::new(vec![4])),
+            AttemptResult::new(11, 1, 2).with_outcome(AttemptOutcome::Failed),
+            AttemptResult::new(0, 10, 1),
+        ];
+        let mut resolver = Resolver::new(Scripted {
+            script,
+            limits: SearchLimits::new(),
+        });
+        let result = resolver.resolve_restart(&Line::new(10).even(), 3);
+        assert_eq!((result.state, result.h, result.iterations), (4, 6, 6));
+        assert_eq!(
+            result.outcome,
+            AttemptOutcome::BudgetExhausted(Limit::Expansions)
+        );
+        assert_eq!(result.limit, Some(Limit::Expansions));
+        assert_eq!(result.progress.unwrap().population, vec![4]);
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use agent::{
+        limits::{Limit, LimitSearch, SearchLimits},
+        problem::{CostructSolution, Problem, SuitableState, Utility},
+        statexplorer::{
+            parallel::HDAStarExplorer,
+            resolver::{AStarExplorer, SearchOutcome},
+        },
+    };
+    use bumpalo::Bump;
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    enum Move {
+        Left,
+        Right,
+        Up,
+        Down,
+    }
+
+    /// A grid where entering a cell costs its weight, walls have weight 0.
+    struct Grid {
+        weights: Vec<Vec<u32>>,
+        goal: (usize, usize),
+    }
+
+    impl Grid {
+        fn new(rows: usize, cols: usize) -> Self {
+            let weights = (0..rows)
+                .map(|r| {
+                    (0..cols)
+                        .map(|c| {
+                            if c % 6 == 3 && c + 1 < cols && r % 9 != (c / 6) % 9 {
+                                0
+                            } else {
+                                1 + ((r * 7 + c * 13) % 5) as u32
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            Self {
+                weights,
+                goal: (rows - 1, cols - 1),
+            }
+        }
+
+        fn cost_of(&self, start: (usize, usize), plan: &[Move]) -> u32 {
+            let mut state = start;
+            let mut total = 0;
+            for action in plan {
+                let (next, cost) = self.result(&state, action);
+                state = next;
+                total += cost;
+            }
+            assert_eq!(state, self.goal);
+            total
+        }
+    }
+
+    impl Problem for Grid {
+        type State = (usize, usize);
+    }
+
+    impl CostructSolution for Grid {
+        type Action = Move;
+        type Cost = u32;
+
+        fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+            let (r, c) = *state;
+            let rows = self.weights.len();
+            let cols = self.weights[0].len();
+            let mut actions = Vec::with_capacity(4);
+            if c > 0 && self.weights[r][c - 1] > 0 {
+                actions.push(Move::Left);
+            }
+            if c + 1 < cols && self.weights[r][c + 1] > 0 {
+                actions.push(Move::Right);
+            }
+            if r > 0 && self.weights[r - 1][c] > 0 {
+                actions.push(Move::Up);
+            }
+            if r + 1 < rows && self.weights[r + 1][c] > 0 {
+                actions.push(Move::Down);
+            }
+            actions.into_iter()
+        }
+
+        fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, u32) {
+            let (r, c) = *state;
+            let next = match action {
+                Move::Left => (r, c - 1),
+                Move::Right => (r, c + 1),
+                Move::Up => (r - 1, c),
+                Move::Down => (r + 1, c),
+            };
+            (next, self.weights[next.0][next.1])
+        }
+    }
+
+    impl Utility for Grid {
+        fn heuristic(&self, state: &Self::State) -> u32 {
+            (self.goal.0.abs_diff(state.0) + self.goal.1.abs_diff(state.1)) as u32
+        }
+    }
+
+    impl SuitableState for Grid {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            *state == self.goal
+        }
+    }
+
+    fn astar_cost(problem: &Grid) -> u32 {
+        let arena = Bump::new();
+        let mut explorer = AStarExplorer::new(problem, &arena);
+        let result = explorer.search((0, 0));
+        problem.cost_of((0, 0), &result.actions.unwrap())
+    }
+
+    #[test]
+    fn test_hda_star_optimal() {
+        let problem = Grid::new(40, 40);
+        let expected = astar_cost(&problem);
+        for threads in [1, 2, 4, 8] {
+            let mut explorer = HDAStarExplorer::with_threads(&problem, threads);
+            let result = explorer.search((0, 0));
+            assert_eq!(result.outcome, SearchOutcome::Solved);
+            assert_eq!(result.state, Some(problem.goal));
+            let cost = problem.cost_of((0, 0), &result.actions.unwrap());
+            assert_eq!(cost, expected, "{} threads", threads);
+            assert_eq!(result.stats.expanded, result.n_iter);
+            assert!(result.stats.generated > result.stats.expanded);
+        }
+    }
+
+    #[test]
+    fn test_hda_star_root_is_goal() {
+        let problem = Grid::new(5, 5);
+        let mut explorer = HDAStarExplorer::with_threads(&problem, 3);
+        let result = explorer.search(problem.goal);
+        assert_eq!(result.outcome, SearchOutcome::Solved);
+        assert_eq!(result.actions, Some(vec![]));
+    }
+
+    #[test]
+    fn test_hda_star_exhausted() {
+        let mut problem = Grid::new(10, 10);
+        // walls around the goal
+        problem.weights[8][9] = 0;
+        problem.weights[9][8] = 0;
+        let mut explorer = HDAStarExplorer::with_threads(&problem, 4);
+        let result = explorer.search((0, 0));
+        assert_eq!(result.outcome, SearchOutcome::Exhausted);
+        assert!(result.actions.is_none());
+    }
+
+    #[test]
+    fn test_hda_star_limits() {
+        let problem = Grid::new(40, 40);
+        let mut explorer = HDAStarExplorer::with_threads(&problem, 4);
+        explorer.set_limits(SearchLimits::new().with_max_expansions(50));
+        let result = explorer.search((0, 0));
+        assert_eq!(
+            result.outcome,
+            SearchOutcome::LimitReached(Limit::Expansions)
+        );
+        assert!(result.actions.is_none());
+        assert!(result.stats.expanded <= 50);
+    }
+}
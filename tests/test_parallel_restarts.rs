@@ -0,0 +1,66 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    use agent::{
+        error::Error,
+        improve::{
+            algorithms::{HillClimbing, SteepestDescend},
+            parallel::ParallelResolver,
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        problem::SuitableState,
+    };
+
+    use crate::common::Queens;
+
+    #[test]
+    fn test_parallel_restarts() {
+        let problem = Queens::new(8);
+        let mut resolver = ParallelResolver::new(3, SteepestDescend::new).with_threads(4);
+        let result = resolver.resolve(&problem, 1000);
+        assert_eq!(result.h, 0);
+        assert_eq!(result.limit, None);
+        assert!(result.iterations > 0);
+        assert!(problem.is_suitable(&result.state));
+    }
+
+    #[test]
+    fn test_portfolio() {
+        let problem = Queens::new(8);
+        let mut resolver = ParallelResolver::portfolio(11)
+            .with_algorithm(SteepestDescend::new)
+            .with_algorithm(|rng| HillClimbing::with_max_lateral(rng, 10))
+            .with_threads(3);
+        let result = resolver.resolve(&problem, 1000);
+        assert_eq!(result.h, 0);
+    }
+
+    #[test]
+    fn test_restart_budget() {
+        // with a single restart the 8 queens are rarely solved by steepest descent
+        let problem = Queens::new(8);
+        let mut resolver = ParallelResolver::new(5, SteepestDescend::new).with_threads(4);
+        let one = resolver.resolve(&problem, 1);
+        let many = resolver.resolve(&problem, 400);
+        assert!(one.iterations < many.iterations || many.h == 0);
+        assert!(many.h <= one.h);
+    }
+
+    #[test]
+    fn test_cancel_and_errors() {
+        let problem = Queens::new(8);
+        let mut resolver = ParallelResolver::new(5, SteepestDescend::new).with_threads(2);
+        resolver.set_limits(SearchLimits::new().with_cancel_token(Arc::new(AtomicBool::new(true))));
+        let result = resolver.resolve(&problem, 1000);
+        assert_eq!(result.limit, Some(Limit::Cancelled));
+
+        let mut empty = ParallelResolver::<Queens>::portfolio(0);
+        assert!(matches!(
+            empty.try_resolve(&problem, 10),
+            Err(Error::Invariant(_))
+        ));
+    }
+}
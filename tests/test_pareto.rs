@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+    use agent::{
+        improve::pareto::{
+            crowding_distance, dominates, hypervolume, igd, non_dominated_sort, Nsga2, Nsga2Config,
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        problem::{CostructSolution, Crossover, MultiObjective, MutateGene, Problem, RandomState},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // Schaffer's problem: x^2 and (x - 2)^2, the Pareto set is [0, 2].
+    struct Schaffer;
+
+    impl Problem for Schaffer {
+        type State = f64;
+    }
+
+    impl CostructSolution for Schaffer {
+        type Action = ();
+        type Cost = u32;
+
+        fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+            std::iter::empty()
+        }
+
+        fn result(&self, state: &Self::State, _: &Self::Action) -> (Self::State, Self::Cost) {
+            (*state, 0)
+        }
+    }
+
+    impl RandomState for Schaffer {
+        fn random_state<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::State {
+            rng.random_range(-10.0..10.0)
+        }
+    }
+
+    impl MultiObjective for Schaffer {
+        fn objectives(&self, state: &Self::State) -> Vec<f64> {
+            vec![state * state, (state - 2.0).powi(2)]
+        }
+    }
+
+    impl Crossover for Schaffer {
+        fn crossover<R: Rng + ?Sized>(
+            &self,
+            rng: &mut R,
+            state: &Self::State,
+            other: &Self::State,
+        ) -> Self::State {
+            let t: f64 = rng.random_range(-0.25..1.25);
+            state + t * (other - state)
+        }
+    }
+
+    impl MutateGene for Schaffer {
+        fn mutate_gene<R: Rng + ?Sized>(&self, rng: &mut R, state: &Self::State) -> Self::State {
+            state + rng.random_range(-0.5..0.5)
+        }
+    }
+
+    fn true_front(points: usize) -> Vec<Vec<f64>> {
+        (0..points)
+            .map(|i| {
+                let x = 2.0 * i as f64 / (points - 1) as f64;
+                Schaffer.objectives(&x)
+            })
+            .collect()
+    }
+
+    fn config(max_iter: Option<usize>) -> Nsga2Config {
+        Nsga2Config {
+            k: 40,
+            max_iter,
+            pmut: 0.2,
+        }
+    }
+
+    #[test]
+    fn test_dominance_and_sorting() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 3.0]));
+        assert!(!dominates(&[1.0, 2.0], &[1.0, 2.0]));
+        assert!(!dominates(&[1.0, 4.0], &[2.0, 3.0]));
+
+        let costs = vec![
+            vec![3.0, 3.0],
+            vec![1.0, 4.0],
+            vec![2.0, 2.0],
+            vec![4.0, 1.0],
+            vec![4.0, 4.0],
+        ];
+        let mut fronts = non_dominated_sort(&costs);
+        fronts.iter_mut().for_each(|front| front.sort());
+        assert_eq!(fronts, vec![vec![1, 2, 3], vec![0], vec![4]]);
+    }
+
+    #[test]
+    fn test_crowding_distance() {
+        let costs = vec![
+            vec![0.0, 4.0],
+            vec![1.0, 3.0],
+            vec![3.0, 1.0],
+            vec![4.0, 0.0],
+        ];
+        let distance = crowding_distance(&costs, &[0, 1, 2, 3]);
+        assert_eq!(distance[0], f64::INFINITY);
+        assert_eq!(distance[3], f64::INFINITY);
+        assert!((distance[1] - 1.5).abs() < 1e-9);
+        assert!((distance[2] - 1.5).abs() < 1e-9);
+        assert_eq!(crowding_distance(&costs, &[0, 1]), vec![f64::INFINITY; 2]);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let points = vec![
+            vec![1.0, 3.0],
+            vec![2.0, 2.0],
+            vec![3.0, 1.0],
+            vec![5.0, 0.0],
+        ];
+        // the last point does not dominate the reference
+        assert!((hypervolume(&points, &[4.0, 4.0]) - 6.0).abs() < 1e-9);
+        let cube = vec![vec![0.0, 0.0, 0.0]];
+        assert!((hypervolume(&cube, &[1.0, 2.0, 3.0]) - 6.0).abs() < 1e-9);
+        let corner = vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+        ];
+        assert!((hypervolume(&corner, &[2.0, 2.0, 2.0]) - 4.0).abs() < 1e-9);
+
+        assert_eq!(igd(&points, &points), 0.0);
+        let reference = vec![vec![0.0, 0.0], vec![3.0, 0.0]];
+        assert!((igd(&[vec![0.0, 1.0]], &reference) - (1.0 + 10f64.sqrt()) / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_nsga2() {
+        let mut algo = Nsga2::new(StdRng::seed_from_u64(2), config(Some(100)));
+        let front = algo.run(&Schaffer);
+        assert_eq!(front.generations, 100);
+        assert_eq!(front.evaluated, 40 * 101);
+        assert_eq!(front.limit, None);
+        assert_eq!(front.solutions.len(), 40);
+        assert!(front
+            .solutions
+            .iter()
+            .all(|s| (-0.05..=2.05).contains(&s.state)));
+
+        let costs = front.costs();
+        let reference = true_front(200);
+        let optimum = hypervolume(&reference, &[4.0, 4.0]);
+        assert!(hypervolume(&costs, &[4.0, 4.0]) > 0.97 * optimum);
+        assert!(igd(&costs, &reference) < 0.1);
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut algo = Nsga2::new(StdRng::seed_from_u64(2), config(None));
+        algo.set_limits(SearchLimits::new().with_max_expansions(5));
+        let front = algo.run(&Schaffer);
+        assert_eq!(front.limit, Some(Limit::Expansions));
+        assert_eq!(front.generations, 5);
+        assert!(!front.solutions.is_empty());
+
+        // the starting point is never lost, no solution of the front is dominated by it
+        algo.set_limits(SearchLimits::new().with_max_generated(50));
+        let front = algo.evolve(&Schaffer, vec![0.5; 10]);
+        assert_eq!(front.limit, Some(Limit::Generated));
+        assert_eq!(front.evaluated, 50);
+        let start = Schaffer.objectives(&0.5);
+        assert!(front.solutions.iter().all(|s| !dominates(&start, &s.costs)));
+    }
+}
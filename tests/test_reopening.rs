@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use agent::{
+        problem::{CostructSolution, Problem, SuitableState, Utility},
+        statexplorer::resolver::{AStarExplorer, ReopenPolicy},
+    };
+    use bumpalo::Bump;
+
+    // S -> A (1), S -> B (3), A -> C (1), B -> C (1), C -> G (3)
+    //
+    // h(A) = 4 is admissible (h*(A) = 4) but not consistent with h(S) = 0,
+    // so A* closes C through B (g = 4) before reaching it through A (g = 2).
+    const EDGES: [(char, char, u32); 5] = [
+        ('S', 'A', 1),
+        ('S', 'B', 3),
+        ('A', 'C', 1),
+        ('B', 'C', 1),
+        ('C', 'G', 3),
+    ];
+
+    struct InconsistentGraph {}
+
+    impl Problem for InconsistentGraph {
+        type State = char;
+    }
+
+    impl CostructSolution for InconsistentGraph {
+        type Action = char;
+        type Cost = u32;
+
+        fn executable_actions(&self, state: &Self::State) -> impl Iterator<Item = Self::Action> {
+            let from = *state;
+            EDGES
+                .iter()
+                .filter(move |(f, _, _)| *f == from)
+                .map(|(_, to, _)| *to)
+        }
+
+        fn result(&self, state: &Self::State, action: &Self::Action) -> (Self::State, Self::Cost) {
+            let cost = EDGES
+                .iter()
+                .find(|(f, t, _)| f == state && t == action)
+                .map(|(_, _, c)| *c)
+                .unwrap();
+            (*action, cost)
+        }
+    }
+
+    impl Utility for InconsistentGraph {
+        fn heuristic(&self, state: &Self::State) -> Self::Cost {
+            match state {
+                'A' => 4,
+                _ => 0,
+            }
+        }
+    }
+
+    impl SuitableState for InconsistentGraph {
+        fn is_suitable(&self, state: &Self::State) -> bool {
+            *state == 'G'
+        }
+    }
+
+    fn plan_cost(plan: &[char]) -> u32 {
+        let problem = InconsistentGraph {};
+        let mut state = 'S';
+        let mut total = 0;
+        for action in plan {
+            let (next, cost) = problem.result(&state, action);
+            state = next;
+            total += cost;
+        }
+        total
+    }
+
+    fn solve(policy: ReopenPolicy) -> Vec<char> {
+        let problem = InconsistentGraph {};
+        let arena = Bump::new();
+        let mut explorer = AStarExplorer::new(&problem, &arena);
+        explorer.set_reopen_policy(policy);
+        explorer.search('S').actions.unwrap()
+    }
+
+    #[test]
+    fn test_never_reopen_is_suboptimal() {
+        let plan = solve(ReopenPolicy::Never);
+        assert_eq!(plan, vec!['B', 'C', 'G']);
+        assert_eq!(plan_cost(&plan), 7);
+    }
+
+    #[test]
+    fn test_always_reopen_is_optimal() {
+        let plan = solve(ReopenPolicy::Always);
+        assert_eq!(plan, vec!['A', 'C', 'G']);
+        assert_eq!(plan_cost(&plan), 5);
+    }
+
+    #[test]
+    fn test_bounded_reopen() {
+        assert_eq!(plan_cost(&solve(ReopenPolicy::Bounded(1))), 5);
+        assert_eq!(plan_cost(&solve(ReopenPolicy::Bounded(0))), 7);
+    }
+}
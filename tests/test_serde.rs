@@ -0,0 +1,109 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        improve::{
+            algorithms::{
+                Cooling, GeneticConfig, LocalBeamConfig, Selection, SteepestDescend, Verbosity,
+            },
+            resolver::{AttemptResult, Resolver, ResolverResult},
+        },
+        limits::Limit,
+        observer::{SearchEvent, TraceObserver},
+        statexplorer::resolver::{BFSExplorer, ReopenPolicy, SearchOutcome, SearchResult},
+    };
+    use bumpalo::Bump;
+
+    use crate::common::Walk;
+
+    #[test]
+    fn test_search_result_round_trip() {
+        let problem = Walk::new(4);
+        let arena = Bump::new();
+        let result = BFSExplorer::new(&problem, &arena).search(1);
+
+        let json = serde_json::to_string(&result).unwrap();
+        let back: SearchResult<i32, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.state, result.state);
+        assert_eq!(back.actions, result.actions);
+        assert_eq!(back.n_iter, result.n_iter);
+        assert_eq!(back.total_time, result.total_time);
+        assert_eq!(back.stats, result.stats);
+        assert_eq!(back.outcome, SearchOutcome::Solved);
+    }
+
+    #[test]
+    fn test_resolver_results_round_trip() {
+        let problem = Walk::new(4);
+        let mut trace = TraceObserver::new();
+        let mut resolver = Resolver::new(SteepestDescend::new(rand::rng()));
+        let result = resolver.resolve_observed(&problem, &mut trace);
+
+        let json = serde_json::to_string(&result).unwrap();
+        let back: ResolverResult<Walk> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.state, result.state);
+        assert_eq!(back.h, result.h);
+        assert_eq!(back.iterations, result.iterations);
+        assert_eq!(back.duration, result.duration);
+
+        let json = serde_json::to_string(&trace).unwrap();
+        let back: TraceObserver<i32, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.events, trace.events);
+        assert!(matches!(back.events.last(), Some(SearchEvent::Goal { .. })));
+
+        let attempt: AttemptResult<Walk> = AttemptResult::stopped(3, 1, 10, Limit::Time);
+        let json = serde_json::to_string(&attempt).unwrap();
+        let back: AttemptResult<Walk> = serde_json::from_str(&json).unwrap();
+        assert_eq!((back.state, back.h, back.iterations), (3, 1, 10));
+        assert_eq!(back.limit, Some(Limit::Time));
+    }
+
+    #[test]
+    fn test_configurations_round_trip() {
+        let beam = LocalBeamConfig {
+            k: 8,
+            max_iter: Some(100),
+        };
+        let json = serde_json::to_string(&beam).unwrap();
+        assert_eq!(
+            serde_json::from_str::<LocalBeamConfig>(&json).unwrap(),
+            beam
+        );
+
+        let genetic = GeneticConfig {
+            selection: Selection::Tournament(3),
+            elitism: 2,
+            ..GeneticConfig::new(20, None, 0.05)
+        };
+        let json = serde_json::to_string(&genetic).unwrap();
+        assert_eq!(
+            serde_json::from_str::<GeneticConfig>(&json).unwrap(),
+            genetic
+        );
+
+        let cooling = Cooling::Exponential {
+            t0: 100.0,
+            alpha: 0.95,
+        };
+        let json = serde_json::to_string(&cooling).unwrap();
+        let back: Cooling = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.temperature(10), cooling.temperature(10));
+        let back: Cooling = serde_json::from_str("\"Inverse\"").unwrap();
+        assert_eq!(back.temperature(4), 0.25);
+        assert!(serde_json::to_string(&Cooling::Custom(|_| 1.0)).is_err());
+
+        let json = serde_json::to_string(&Verbosity::Max).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Verbosity>(&json).unwrap(),
+            Verbosity::Max
+        );
+        let json = serde_json::to_string(&ReopenPolicy::Bounded(3)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ReopenPolicy>(&json).unwrap(),
+            ReopenPolicy::Bounded(3)
+        );
+    }
+}
@@ -0,0 +1,40 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::statexplorer::{resolver::BFSExplorer, stats::SearchStats};
+    use bumpalo::Bump;
+
+    use crate::common::Walk;
+
+    #[test]
+    fn test_bfs_stats() {
+        let problem = Walk::new(4);
+        let arena = Bump::new();
+        let mut explorer = BFSExplorer::new(&problem, &arena);
+        let result = explorer.search(0);
+        let stats = &result.stats;
+
+        assert_eq!(result.actions.as_ref().unwrap(), &vec![2, 2]);
+        assert_eq!(stats.expanded, result.n_iter);
+        assert_eq!(stats.expanded, 5);
+        assert_eq!(stats.generated, 9);
+        assert_eq!(stats.nodes_per_depth.iter().sum::<usize>(), stats.generated);
+        assert_eq!(stats.discarded, 3);
+        assert_eq!(stats.duplicates, 0);
+        assert!(stats.arena_bytes > 0);
+        assert!(result.effective_branching_factor().unwrap() >= 1.0);
+    }
+
+    #[test]
+    fn test_effective_branching_factor() {
+        let stats = SearchStats {
+            generated: 1 + 2 + 4 + 8,
+            ..Default::default()
+        };
+        let b = stats.effective_branching_factor(3).unwrap();
+        assert!((b - 2.0).abs() < 1e-9);
+        assert_eq!(stats.effective_branching_factor(0), None);
+        assert_eq!(SearchStats::default().effective_branching_factor(2), None);
+    }
+}
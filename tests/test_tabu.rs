@@ -0,0 +1,182 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use agent::{
+        error::Error,
+        improve::{
+            algorithms::{
+                Aspiration, ImprovingAlgorithm, PerturbationKey, StateKey, SteepestDescend,
+                TabuConfig, TabuSearch,
+            },
+            resolver::{AttemptOutcome, Progress, Resolver},
+        },
+        limits::{Limit, LimitSearch, SearchLimits},
+        observer::NoObserver,
+        problem::{CostructSolution, Problem, StatePerturbation, SuitableState, Utility},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::common::Queens;
+
+    // States from 0 to 30, with a local optimum in 0 and the goal in 20.
+    struct Valley {}
+
+    impl Problem for Valley {
+        type State = i32;
+    }
+
+    impl CostructSolution for Valley {
+        type Action = ();
+        type Cost = i32;
+
+        fn executable_actions(&self, _: &Self::State) -> impl Iterator<Item = Self::Action> {
+            std::iter::empty()
+        }
+
+        fn result(&self, state: &Self::State, _: &Self::Action) -> (Self::State, Self::Cost) {
+            (*state, 0)
+        }
+    }
+
+    impl Utility for Valley {
+        fn heuristic(&self, state: &Self::State) -> Self::Cost {
+            if *state < 10 {
+                5 + state
+            } else {
+                (20 - state).abs()
+            }
+        }
+    }
+
+    impl SuitableState for Valley {
+        fn is_suitable(&self, _: &Self::State) -> bool {
+            true
+        }
+    }
+
+    impl StatePerturbation for Valley {
+        type Perturbation = i32;
+
+        fn perturbations(&self, state: &Self::State) -> impl Iterator<Item = Self::Perturbation> {
+            let state = *state;
+            [-1, 1]
+                .into_iter()
+                .filter(move |d| (0..=30).contains(&(state + d)))
+        }
+
+        fn perturb(&self, state: &Self::State, action: &Self::Perturbation) -> Self::State {
+            state + action
+        }
+    }
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(4)
+    }
+
+    #[test]
+    fn test_tabu_escapes_local_optimum() {
+        let problem = Valley {};
+        let stuck = SteepestDescend::new(rng()).attempt(&problem);
+        assert_eq!(stuck.outcome, AttemptOutcome::LocalOptimum);
+
+        let mut tabu = TabuSearch::from_parts(rng(), StateKey, 5, 100);
+        let result = tabu.attempt(&problem);
+        assert_eq!(result.outcome, AttemptOutcome::GoalReached);
+        assert_eq!(result.state, 20);
+        assert_eq!(result.iterations, 20);
+    }
+
+    #[test]
+    fn test_tenure_and_max_iter() {
+        // without a tabu list the search goes back and forth around the local optimum
+        let problem = Valley {};
+        let mut tabu = TabuSearch::from_parts(rng(), PerturbationKey, 0, 50);
+        let result = tabu.attempt(&problem);
+        assert_eq!(
+            result.outcome,
+            AttemptOutcome::BudgetExhausted(Limit::Expansions)
+        );
+        assert_eq!(result.state, 0);
+        assert_eq!(result.h, 5);
+        assert_eq!(result.iterations, 50);
+    }
+
+    #[test]
+    fn test_aspiration() {
+        // from 2 the second step towards 0 repeats the tabu perturbation -1
+        let problem = Valley {};
+        let mut config = TabuConfig {
+            tenure: 10,
+            max_iter: 2,
+            aspiration: Aspiration::BestSoFar,
+        };
+        let mut tabu = TabuSearch::from_config(rng(), PerturbationKey, config);
+        let result = tabu.resume(&problem, Progress::new(vec![2]));
+        assert_eq!(result.state, 0);
+
+        config.aspiration = Aspiration::Never;
+        let mut tabu = TabuSearch::from_config(rng(), PerturbationKey, config);
+        let result = tabu.resume(&problem, Progress::new(vec![2]));
+        assert_eq!(result.state, 1);
+        assert_eq!(tabu.config().aspiration, Aspiration::Never);
+    }
+
+    #[test]
+    fn test_tabu_queens() {
+        let problem = Queens::new(12);
+        let column = |_: &Vec<usize>, m: &(usize, usize), _: &Vec<usize>| m.0;
+        let mut resolver = Resolver::new(TabuSearch::from_parts(rng(), column, 4, 1000));
+        let result = resolver.resolve_restart(&problem, 10);
+        assert!(result.is_goal());
+        assert_eq!(problem.heuristic(&result.state), 0);
+    }
+
+    #[test]
+    fn test_tabu_resume() {
+        let problem = Valley {};
+        let mut tabu = TabuSearch::from_parts(rng(), StateKey, 5, 100);
+        tabu.set_limits(SearchLimits::new().with_max_expansions(12));
+        let stopped = tabu.attempt(&problem);
+        assert_eq!(stopped.limit, Some(Limit::Expansions));
+        assert_eq!(stopped.state, 0);
+        let progress = stopped.progress.unwrap();
+        assert_eq!(progress.population, vec![12, 0]);
+
+        tabu.set_limits(SearchLimits::new());
+        let result = tabu.resume(&problem, progress);
+        assert_eq!(result.state, 20);
+        assert_eq!(result.iterations, 20);
+    }
+
+    #[test]
+    fn test_tabu_list_resume() {
+        // stopped while climbing out of the local optimum, only the tabu list keeps it from going back
+        let problem = Valley {};
+        let mut tabu = TabuSearch::from_parts(rng(), StateKey, 5, 100);
+        tabu.set_limits(SearchLimits::new().with_max_expansions(4));
+        let progress = tabu.attempt(&problem).progress.unwrap();
+        assert_eq!(progress.population, vec![4, 0]);
+        assert_eq!(progress.tabu, vec![(0, 0), (1, 1), (2, 1), (3, 1)]);
+
+        let mut resumed = TabuSearch::from_parts(StdRng::seed_from_u64(99), StateKey, 5, 100);
+        let result = resumed.resume(&problem, progress.clone());
+        assert_eq!(result.state, 20);
+        assert_eq!(result.iterations, 20);
+
+        let forgotten = Progress {
+            tabu: Vec::new(),
+            ..progress.clone()
+        };
+        assert_eq!(resumed.resume(&problem, forgotten).state, 0);
+
+        let invalid = Progress {
+            tabu: vec![(0, 1)],
+            ..progress
+        };
+        assert!(matches!(
+            resumed.try_resume_observed(&problem, invalid, &mut NoObserver),
+            Err(Error::InvalidState(_))
+        ));
+    }
+}